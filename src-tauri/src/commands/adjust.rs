@@ -0,0 +1,93 @@
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use base64::Engine;
+use image::imageops::FilterType;
+use tauri::AppHandle;
+
+use super::generation::render_in_background;
+use crate::error::AppError;
+use crate::services::raster::{self, Adjustments, Effect, EffectChain, OutputOptions};
+use crate::state::AppState;
+
+/// Longest side of the downscaled copy used for live previews.
+const PREVIEW_SIZE: u32 = 960;
+
+/// Render `adjustments` over a downscaled copy of the image and return it as
+/// base64 PNG. The copy is cached, so dragging a slider only re-applies the chain.
+#[tauri::command]
+pub async fn preview_adjustments(
+    image_path: String,
+    adjustments: Adjustments,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, AppError> {
+    let cached = state
+        .preview_source
+        .lock()
+        .unwrap()
+        .as_ref()
+        .filter(|(path, _)| *path == image_path)
+        .map(|(_, image)| image.clone());
+
+    let source = match cached {
+        Some(image) => image,
+        None => {
+            let path = PathBuf::from(&image_path);
+            let image = tokio::task::spawn_blocking(move || -> Result<_, AppError> {
+                let (image, _) = raster::open_image(&path)?;
+                let scale = PREVIEW_SIZE as f64 / image.width().max(image.height()) as f64;
+                if scale >= 1.0 {
+                    return Ok(image);
+                }
+                let width = ((image.width() as f64 * scale).round() as u32).max(1);
+                let height = ((image.height() as f64 * scale).round() as u32).max(1);
+                Ok(image::imageops::resize(&image, width, height, FilterType::Triangle))
+            })
+            .await
+            .map_err(|e| AppError::Generation(format!("Preview task failed: {}", e)))??;
+            *state.preview_source.lock().unwrap() = Some((image_path, image.clone()));
+            image
+        }
+    };
+
+    let preview = raster::preview(&source, &chain(adjustments));
+    let mut png = Cursor::new(Vec::new());
+    preview
+        .write_to(&mut png, image::ImageFormat::Png)
+        .map_err(|e| AppError::Generation(format!("Failed to encode preview: {}", e)))?;
+    Ok(base64::engine::general_purpose::STANDARD.encode(png.into_inner()))
+}
+
+/// Render `adjustments` at full resolution into a new file next to the
+/// original, which is left untouched. Returns the new file's path.
+#[tauri::command]
+pub async fn apply_adjustments(
+    app: AppHandle,
+    image_path: String,
+    adjustments: Adjustments,
+    output: Option<OutputOptions>,
+) -> Result<String, AppError> {
+    let input = PathBuf::from(&image_path);
+    let stem = input
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    let output_path = input.with_file_name(format!("{}_adjusted.png", stem));
+
+    let path = output_path.clone();
+    let options = output.unwrap_or_default();
+    render_in_background(&app, move |progress| {
+        raster::process_file(&input, &path, &options, &chain(adjustments), progress)
+    })
+    .await?;
+
+    Ok(output_path.to_string_lossy().to_string())
+}
+
+fn chain(adjustments: Adjustments) -> EffectChain {
+    EffectChain {
+        seed: None,
+        effects: vec![Effect::Adjust(adjustments)],
+    }
+}
@@ -3,6 +3,7 @@ use std::path::PathBuf;
 use base64::Engine;
 
 use crate::error::AppError;
+use crate::state::AppState;
 
 #[tauri::command]
 pub async fn save_image_to_disk(
@@ -20,6 +21,29 @@ pub async fn save_image_to_disk(
     Ok(dest.to_string_lossy().to_string())
 }
 
+/// Write the most recent vector-art result as an SVG file.
+#[tauri::command]
+pub async fn export_svg(
+    destination_path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, AppError> {
+    let svg = state
+        .last_scene
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|scene| scene.to_svg())
+        .ok_or_else(|| AppError::FileOp("No vector scene to export".into()))?;
+
+    let dest = PathBuf::from(&destination_path);
+    if let Some(parent) = dest.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+
+    tokio::fs::write(&dest, svg).await?;
+    Ok(dest.to_string_lossy().to_string())
+}
+
 #[tauri::command]
 pub async fn delete_temp_image(image_path: String) -> Result<(), AppError> {
     let path = PathBuf::from(&image_path);
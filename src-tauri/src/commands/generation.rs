@@ -4,8 +4,12 @@ use tauri::{AppHandle, Emitter};
 use uuid::Uuid;
 
 use crate::error::AppError;
-use crate::models::generation::{AiProvider, GenerationRequest, GenerationResult};
+use crate::models::generation::{
+    AiProvider, GenerationRequest, GenerationResult, RenderProgress,
+};
 use crate::models::settings::AppSettings;
+use crate::services::raster::{self, EffectChain, OutputOptions};
+use crate::services::svg_generator::scene::Scene;
 use crate::services::{openai, pollinations, svg_generator};
 use crate::state::AppState;
 
@@ -30,19 +34,52 @@ pub async fn generate_image(
 
     let use_svg = !request.styles.is_empty();
 
+    let mut effects = request.effects.clone();
+    effects.seed.get_or_insert_with(|| rand::random::<u32>() as u64);
+    if use_svg && request.tileable {
+        effects.keep_tileable();
+    }
+
+    let mut scene = None;
+    let mut seed = None;
     let (final_width, final_height) = if use_svg {
-        // Vector art mode: generate SVG → rasterize to PNG at exact target resolution
-        svg_generator::generate(
-            &request.styles,
-            &request.color_schemes,
-            request.custom_prompt.as_deref(),
-            request.target_width,
-            request.target_height,
-            &output_path,
-        )?;
-        (request.target_width, request.target_height)
+        // Vector art mode: compose a scene → rasterize to PNG at exact target resolution.
+        // Seeds stay within 32 bits so they survive a round trip through JS numbers.
+        let vector_seed = request.seed.unwrap_or_else(|| rand::random::<u32>() as u64);
+        let target = svg_generator::Target {
+            width: request.target_width,
+            height: request.target_height,
+            scale_factor: request.scale_factor,
+            tileable: request.tileable,
+        };
+        let path = output_path.clone();
+        let options = request.output;
+        let chain = effects.clone();
+        // Composing runs simulations and triangulations too, so it stays off
+        // the async runtime along with the render
+        let styles = request.styles.clone();
+        let schemes = request.color_schemes.clone();
+        let prompt = request.custom_prompt.clone();
+        let symmetry = request.symmetry;
+        let generated = render_in_background(&app, move |progress| {
+            let mut composed =
+                svg_generator::compose(&styles, &schemes, prompt.as_deref(), vector_seed, &target);
+            if let Some(symmetry) = symmetry {
+                composed.symmetrize(symmetry);
+            }
+            svg_generator::rasterize(&composed, &path, &options, &chain, progress)?;
+            Ok(composed)
+        })
+        .await?;
+        // Tiles come out smaller than the target
+        let size = (generated.width.round() as u32, generated.height.round() as u32);
+        *state.last_scene.lock().unwrap() = Some(generated.clone());
+        scene = Some(generated);
+        seed = Some(vector_seed);
+        size
     } else {
         // Direct prompt mode: use AI image generation API
+        *state.last_scene.lock().unwrap() = None;
         let prompt = build_prompt(request.custom_prompt.as_deref());
 
         let (gen_width, gen_height) = match request.provider {
@@ -69,12 +106,23 @@ pub async fn generate_image(
         };
 
         // Resize if the API returned smaller than target
-        if gen_width < request.target_width || gen_height < request.target_height {
+        let size = if gen_width < request.target_width || gen_height < request.target_height {
             resize_image(&output_path, request.target_width, request.target_height)?;
             (request.target_width, request.target_height)
         } else {
             (gen_width, gen_height)
+        };
+
+        if !effects.is_empty() {
+            let options = request.output;
+            let path = output_path.clone();
+            let chain = effects.clone();
+            render_in_background(&app, move |progress| {
+                raster::process_file(&path, &path, &options, &chain, progress)
+            })
+            .await?;
         }
+        size
     };
 
     let _ = app.emit("generation-progress", "complete");
@@ -88,7 +136,76 @@ pub async fn generate_image(
         final_width,
         final_height,
         was_upscaled: false,
+        seed,
+        scene,
+        effects: Some(effects).filter(|e| !e.is_empty()),
+    })
+}
+
+/// Re-render a scene edited on the frontend (shapes removed, layers recolored,
+/// focal points moved) to a fresh PNG, leaving the previous result untouched.
+#[tauri::command]
+pub async fn render_scene(
+    app: AppHandle,
+    scene: Scene,
+    output: Option<OutputOptions>,
+    mut effects: Option<EffectChain>,
+    state: tauri::State<'_, AppState>,
+) -> Result<GenerationResult, AppError> {
+    let _ = app.emit("generation-progress", "generating");
+
+    let cache_dir = dirs::cache_dir()
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join("wallcraft");
+    tokio::fs::create_dir_all(&cache_dir).await?;
+    let output_path = cache_dir.join(format!("{}.png", Uuid::new_v4()));
+
+    if let Some(chain) = effects.as_mut().filter(|_| scene.tileable) {
+        chain.keep_tileable();
+    }
+    let path = output_path.clone();
+    let options = output.unwrap_or_default();
+    let chain = effects.clone().unwrap_or_default();
+    let scene = render_in_background(&app, move |progress| {
+        svg_generator::render_scene(scene, &path, &options, &chain, progress)
+    })
+    .await?;
+    let (width, height) = (scene.width.round() as u32, scene.height.round() as u32);
+
+    let _ = app.emit("generation-progress", "complete");
+
+    *state.last_scene.lock().unwrap() = Some(scene.clone());
+    *state.temp_dir.lock().unwrap() = Some(cache_dir);
+
+    Ok(GenerationResult {
+        image_path: output_path.to_string_lossy().to_string(),
+        original_width: width,
+        original_height: height,
+        final_width: width,
+        final_height: height,
+        was_upscaled: false,
+        seed: None,
+        scene: Some(scene),
+        effects,
+    })
+}
+
+/// Run a render on the blocking pool so large canvases don't stall the async
+/// runtime, forwarding strip progress as `render-progress` events.
+pub(crate) async fn render_in_background<T, F>(app: &AppHandle, job: F) -> Result<T, AppError>
+where
+    T: Send + 'static,
+    F: FnOnce(&(dyn Fn(u32, u32) + Sync)) -> Result<T, AppError> + Send + 'static,
+{
+    let app = app.clone();
+    tokio::task::spawn_blocking(move || {
+        let progress = |done, total| {
+            let _ = app.emit("render-progress", RenderProgress { done, total });
+        };
+        job(&progress)
     })
+    .await
+    .map_err(|e| AppError::Generation(format!("Render task failed: {}", e)))?
 }
 
 fn read_image_dimensions(path: &PathBuf) -> Result<(u32, u32), AppError> {
@@ -1,3 +1,4 @@
+pub mod adjust;
 pub mod files;
 pub mod generation;
 pub mod monitor;
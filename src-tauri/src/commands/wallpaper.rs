@@ -20,6 +20,7 @@ pub async fn set_wallpaper(path: String, mode: Option<String>) -> Result<(), App
             "span" => wallpaper::Mode::Span,
             "fit" => wallpaper::Mode::Fit,
             "center" => wallpaper::Mode::Center,
+            "tile" => wallpaper::Mode::Tile,
             _ => wallpaper::Mode::Crop,
         };
         wallpaper_manager::set_mode(wm)?;
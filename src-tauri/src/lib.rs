@@ -84,13 +84,17 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             commands::monitor::get_monitors,
             commands::generation::generate_image,
+            commands::generation::render_scene,
             commands::wallpaper::get_current_wallpaper,
             commands::wallpaper::set_wallpaper,
             commands::wallpaper::restore_wallpaper,
             commands::settings::get_settings,
             commands::settings::save_settings,
             commands::upscale::upscale_image,
+            commands::adjust::preview_adjustments,
+            commands::adjust::apply_adjustments,
             commands::files::save_image_to_disk,
+            commands::files::export_svg,
             commands::files::delete_temp_image,
             commands::files::read_image_base64,
         ])
@@ -1,5 +1,9 @@
 use serde::{Deserialize, Serialize};
 
+use crate::services::raster::{EffectChain, OutputOptions};
+use crate::services::svg_generator::scene::Scene;
+use crate::services::svg_generator::symmetry::Symmetry;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AiProvider {
     Pollinations,
@@ -17,6 +21,27 @@ pub struct GenerationRequest {
     pub provider: AiProvider,
     pub target_width: u32,
     pub target_height: u32,
+    /// Seed for vector art; a random one is picked when absent.
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Scale factor of the target monitor, so vector art can keep hairlines
+    /// crisp on high-DPI screens; absent for custom resolutions.
+    #[serde(default)]
+    pub scale_factor: Option<f64>,
+    /// Symmetry laid over every style of vector art; when absent the prompt
+    /// may still ask for one.
+    #[serde(default)]
+    pub symmetry: Option<Symmetry>,
+    /// Compose vector art as a small tile that repeats seamlessly, for tiled
+    /// wallpapers and web backgrounds. Only styles that can wrap are kept.
+    #[serde(default)]
+    pub tileable: bool,
+    /// Bit depth, dithering and color profile of the written PNG.
+    #[serde(default)]
+    pub output: OutputOptions,
+    /// Post-processing applied to the finished image, vector or AI.
+    #[serde(default)]
+    pub effects: EffectChain,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,4 +52,18 @@ pub struct GenerationResult {
     pub final_width: u32,
     pub final_height: u32,
     pub was_upscaled: bool,
+    /// Seed that reproduces a vector-art result; `None` for AI images.
+    pub seed: Option<u64>,
+    /// Editable composition behind a vector-art result; `None` for AI images.
+    pub scene: Option<Scene>,
+    /// Effect chain with its resolved seed; `None` when no effects were applied.
+    pub effects: Option<EffectChain>,
+}
+
+/// Payload of the `render-progress` event, sent as each strip of a vector
+/// render is encoded.
+#[derive(Debug, Clone, Serialize)]
+pub struct RenderProgress {
+    pub done: u32,
+    pub total: u32,
 }
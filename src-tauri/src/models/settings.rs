@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::services::raster::OutputOptions;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppSettings {
     pub ai_provider: String,
@@ -7,6 +9,8 @@ pub struct AppSettings {
     pub save_directory: Option<String>,
     pub upscale_enabled: bool,
     pub upscale_factor: u32,
+    #[serde(default)]
+    pub output: OutputOptions,
 }
 
 impl Default for AppSettings {
@@ -17,6 +21,7 @@ impl Default for AppSettings {
             save_directory: None,
             upscale_enabled: true,
             upscale_factor: 4,
+            output: OutputOptions::default(),
         }
     }
 }
@@ -1,5 +1,7 @@
+pub mod noise;
 pub mod openai;
 pub mod pollinations;
+pub mod raster;
 pub mod svg_generator;
 pub mod upscaler;
 pub mod wallpaper_manager;
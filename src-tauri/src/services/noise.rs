@@ -0,0 +1,98 @@
+//! Seeded, position-addressed noise.
+//!
+//! Every value is a pure function of the seed and coordinates, so strips
+//! rendered on different threads agree at their seams and the same seed always
+//! reproduces the same texture.
+
+/// 64-bit hash of a seed and a lattice point (SplitMix64 finalizer).
+pub fn hash(seed: u64, x: i64, y: i64) -> u64 {
+    let mut z = seed
+        ^ (x as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (y as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Uniform value in 0.0..1.0 for a lattice point.
+pub fn unit(seed: u64, x: i64, y: i64) -> f64 {
+    (hash(seed, x, y) >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Smoothly interpolated lattice noise in 0.0..1.0 with features about one unit apart.
+pub fn value(seed: u64, x: f64, y: f64) -> f64 {
+    let (x0, y0) = (x.floor(), y.floor());
+    let (fx, fy) = (smooth(x - x0), smooth(y - y0));
+    let (ix, iy) = (x0 as i64, y0 as i64);
+
+    let top = lerp(unit(seed, ix, iy), unit(seed, ix + 1, iy), fx);
+    let bottom = lerp(unit(seed, ix, iy + 1), unit(seed, ix + 1, iy + 1), fx);
+    lerp(top, bottom, fy)
+}
+
+/// [`value`] noise repeating every `period` units along both axes, for
+/// textures that tile.
+pub fn value_periodic(seed: u64, x: f64, y: f64, period: i64) -> f64 {
+    let (x0, y0) = (x.floor(), y.floor());
+    let (fx, fy) = (smooth(x - x0), smooth(y - y0));
+    let (ix, iy) = ((x0 as i64).rem_euclid(period), (y0 as i64).rem_euclid(period));
+    let (jx, jy) = ((ix + 1) % period, (iy + 1) % period);
+
+    let top = lerp(unit(seed, ix, iy), unit(seed, jx, iy), fx);
+    let bottom = lerp(unit(seed, ix, jy), unit(seed, jx, jy), fx);
+    lerp(top, bottom, fy)
+}
+
+/// Fractal sum of `octaves` layers of [`value`] noise, normalized to 0.0..1.0.
+pub fn fbm(seed: u64, x: f64, y: f64, octaves: u32) -> f64 {
+    let (mut sum, mut norm, mut amp, mut freq) = (0.0, 0.0, 1.0, 1.0);
+    for octave in 0..octaves {
+        sum += amp * value(seed.wrapping_add(octave as u64), x * freq, y * freq);
+        norm += amp;
+        amp *= 0.5;
+        freq *= 2.0;
+    }
+    sum / norm
+}
+
+/// Perlin gradient noise in about -1.0..=1.0, zero at every lattice point.
+/// Smoother and less blocky than [`value`], for fields that get differentiated
+/// or traced, like flow directions and contour lines.
+pub fn perlin(seed: u64, x: f64, y: f64) -> f64 {
+    let (x0, y0) = (x.floor(), y.floor());
+    let (ix, iy) = (x0 as i64, y0 as i64);
+    let (fx, fy) = (x - x0, y - y0);
+    let corner = |cx: i64, cy: i64| {
+        let angle = unit(seed, ix + cx, iy + cy) * std::f64::consts::TAU;
+        angle.cos() * (fx - cx as f64) + angle.sin() * (fy - cy as f64)
+    };
+    let (u, v) = (fade(fx), fade(fy));
+    let top = lerp(corner(0, 0), corner(1, 0), u);
+    let bottom = lerp(corner(0, 1), corner(1, 1), u);
+    lerp(top, bottom, v) * std::f64::consts::SQRT_2
+}
+
+/// Fractal sum of `octaves` layers of [`perlin`] noise, in about -1.0..=1.0.
+pub fn perlin_fbm(seed: u64, x: f64, y: f64, octaves: u32) -> f64 {
+    let (mut sum, mut norm, mut amp, mut freq) = (0.0, 0.0, 1.0, 1.0);
+    for octave in 0..octaves {
+        sum += amp * perlin(seed.wrapping_add(octave as u64), x * freq, y * freq);
+        norm += amp;
+        amp *= 0.5;
+        freq *= 2.0;
+    }
+    sum / norm
+}
+
+/// Quintic fade, whose continuous second derivative avoids grid creases.
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn smooth(t: f64) -> f64 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    a + (b - a) * t
+}
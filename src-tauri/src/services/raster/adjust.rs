@@ -0,0 +1,65 @@
+//! Tonal and color adjustments, applied per pixel to straight (unpremultiplied) color.
+
+use serde::{Deserialize, Serialize};
+
+/// Every field is neutral at 0.0.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Adjustments {
+    /// -1.0..=1.0, one stop darker to one stop brighter.
+    pub brightness: f64,
+    /// -1.0..=1.0 around mid-gray.
+    pub contrast: f64,
+    /// -1.0 (grayscale) ..= 1.0 (doubled).
+    pub saturation: f64,
+    /// Hue rotation in degrees.
+    pub hue: f64,
+    /// -1.0 (cool) ..= 1.0 (warm).
+    pub temperature: f64,
+    /// 0.0..=1.0; darkens and softens highlights for dark desktop themes.
+    pub dim: f64,
+}
+
+impl Adjustments {
+    pub fn apply(&self, rgb: [f64; 3]) -> [f64; 3] {
+        let [mut r, mut g, mut b] = rgb;
+
+        let warmth = self.temperature.clamp(-1.0, 1.0) * 0.15;
+        r *= 1.0 + warmth;
+        b *= 1.0 - warmth;
+
+        if self.hue != 0.0 {
+            [r, g, b] = rotate_hue([r, g, b], self.hue);
+        }
+
+        let luma = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+        let saturation = 1.0 + self.saturation.clamp(-1.0, 1.0);
+        let contrast = 1.0 + self.contrast.clamp(-1.0, 1.0);
+        let exposure = 2f64.powf(self.brightness.clamp(-1.0, 1.0));
+        let dim = self.dim.clamp(0.0, 1.0);
+
+        [r, g, b].map(|c| {
+            let c = luma + (c - luma) * saturation;
+            let c = ((c - 0.5) * contrast + 0.5) * exposure;
+            // Dimming pulls highlights down harder than shadows
+            let c = c * (1.0 - 0.5 * dim) - 0.2 * dim * c * c;
+            c.clamp(0.0, 1.0)
+        })
+    }
+}
+
+/// Luminance-preserving rotation around the gray axis, as CSS `hue-rotate()`.
+fn rotate_hue([r, g, b]: [f64; 3], degrees: f64) -> [f64; 3] {
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    [
+        r * (0.213 + cos * 0.787 - sin * 0.213)
+            + g * (0.715 - cos * 0.715 - sin * 0.715)
+            + b * (0.072 - cos * 0.072 + sin * 0.928),
+        r * (0.213 - cos * 0.213 + sin * 0.143)
+            + g * (0.715 + cos * 0.285 + sin * 0.140)
+            + b * (0.072 - cos * 0.072 - sin * 0.283),
+        r * (0.213 - cos * 0.213 - sin * 0.787)
+            + g * (0.715 - cos * 0.715 + sin * 0.715)
+            + b * (0.072 + cos * 0.928 + sin * 0.072),
+    ]
+}
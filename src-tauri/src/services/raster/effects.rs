@@ -0,0 +1,257 @@
+//! Post-processing effects applied to every strip before quantization.
+//!
+//! Lengths are given in pixels of a 1080p canvas and scaled by the image's
+//! short side, so a chain looks the same at any resolution. Effects that read
+//! neighboring pixels declare a halo; strips are rendered with that many extra
+//! rows above and below, then cropped.
+
+use std::f64::consts::PI;
+
+use serde::{Deserialize, Serialize};
+
+use super::adjust::Adjustments;
+use super::Tile;
+use crate::services::noise;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextureKind {
+    Paper,
+    Canvas,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Effect {
+    /// Monochrome film grain. `amount` 0..=1, `size` is the grain size.
+    Grain { amount: f64, size: f64 },
+    /// Darkened corners. `radius` 0..=1 is the untouched share of the half-diagonal.
+    Vignette { strength: f64, radius: f64 },
+    /// Gaussian blur with the given standard deviation.
+    Blur { radius: f64 },
+    /// Paper fibers or canvas weave multiplied over the image.
+    Texture { texture: TextureKind, strength: f64 },
+    /// Red and blue pulled apart towards the edges, up to `offset` at the corners.
+    ChromaticAberration { offset: f64 },
+    /// CRT-style horizontal lines, one every `spacing`.
+    Scanlines { spacing: f64, strength: f64 },
+    /// Brightness, contrast, color and dimming.
+    Adjust(Adjustments),
+}
+
+/// Ordered effects plus the seed that makes their noise reproducible.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EffectChain {
+    /// Picked at random when absent and filled in on the returned result.
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub effects: Vec<Effect>,
+}
+
+impl EffectChain {
+    pub fn is_empty(&self) -> bool {
+        self.effects.is_empty()
+    }
+
+    /// Drops the effects that would break a repeating tile: all but the
+    /// adjustments depend on where a pixel sits or reach across the edges.
+    pub fn keep_tileable(&mut self) {
+        self.effects.retain(|effect| matches!(effect, Effect::Adjust(_)));
+    }
+
+    /// Rows of context a strip needs on each side for the whole chain.
+    pub fn halo(&self, width: u32, height: u32) -> u32 {
+        let unit = unit(width, height);
+        self.effects
+            .iter()
+            .map(|effect| match *effect {
+                Effect::Blur { radius } => {
+                    box_sizes(radius * unit).iter().map(|r| *r as u32).sum::<u32>()
+                }
+                Effect::ChromaticAberration { offset } => (offset.abs() * unit).ceil() as u32 + 1,
+                _ => 0,
+            })
+            .sum()
+    }
+
+    /// Apply the chain to a strip of a `width`x`height` image.
+    pub fn apply(&self, tile: &mut Tile, width: u32, height: u32) {
+        let seed = self.seed.unwrap_or_default();
+        let unit = unit(width, height);
+        let (cx, cy) = (width as f64 / 2.0, height as f64 / 2.0);
+        let half_diagonal = cx.hypot(cy);
+
+        for (i, effect) in self.effects.iter().enumerate() {
+            // Each effect gets its own noise so stacked effects don't correlate
+            let seed = seed.wrapping_add(i as u64 * 0x9E37_79B9);
+            match *effect {
+                Effect::Grain { amount, size } => {
+                    let size = (size * unit).max(1.0);
+                    let amount = amount.clamp(0.0, 1.0) * 0.2;
+                    per_pixel(tile, |x, y, px| {
+                        let n = noise::value(seed, x / size, y / size)
+                            + noise::unit(seed ^ 1, x as i64, y as i64)
+                            - 1.0;
+                        let delta = (n * amount) as f32 * px[3];
+                        for c in &mut px[..3] {
+                            *c += delta;
+                        }
+                    });
+                }
+                Effect::Vignette { strength, radius } => {
+                    let radius = radius.clamp(0.0, 0.99);
+                    per_pixel(tile, |x, y, px| {
+                        let d = (x - cx).hypot(y - cy) / half_diagonal;
+                        let t = ((d - radius) / (1.0 - radius)).clamp(0.0, 1.0);
+                        scale(px, 1.0 - strength.clamp(0.0, 1.0) * t * t * (3.0 - 2.0 * t));
+                    });
+                }
+                Effect::Blur { radius } => blur(tile, radius * unit),
+                Effect::Texture { texture, strength } => {
+                    let strength = strength.clamp(0.0, 1.0);
+                    per_pixel(tile, |x, y, px| {
+                        let (u, v) = (x / unit, y / unit);
+                        let t = match texture {
+                            TextureKind::Paper => {
+                                0.7 * noise::fbm(seed, u / 3.0, v / 3.0, 4)
+                                    + 0.3 * noise::fbm(seed ^ 2, u / 90.0, v / 90.0, 3)
+                            }
+                            TextureKind::Canvas => {
+                                let weave = (u * PI / 3.0).sin() * (v * PI / 3.0).sin();
+                                0.5 + 0.3 * weave + 0.4 * (noise::value(seed, u, v) - 0.5)
+                            }
+                        };
+                        scale(px, 1.0 + strength * (t - 0.5));
+                    });
+                }
+                Effect::ChromaticAberration { offset } => {
+                    let k = offset * unit / half_diagonal;
+                    aberrate(tile, width, (cx, cy), k);
+                }
+                Effect::Scanlines { spacing, strength } => {
+                    let period = (spacing * unit).max(2.0);
+                    let strength = strength.clamp(0.0, 1.0);
+                    per_pixel(tile, |_, y, px| {
+                        let line = 0.5 + 0.5 * (2.0 * PI * y / period).cos();
+                        scale(px, 1.0 - strength * line);
+                    });
+                }
+                Effect::Adjust(adjustments) => per_pixel(tile, |_, _, px| {
+                    let a = px[3];
+                    if a <= 0.0 {
+                        return;
+                    }
+                    let rgb = [0, 1, 2].map(|c| (px[c] / a) as f64);
+                    let [r, g, b] = adjustments.apply(rgb);
+                    *px = [r as f32 * a, g as f32 * a, b as f32 * a, a];
+                }),
+            }
+        }
+    }
+}
+
+/// Scale from 1080p lengths to this image.
+fn unit(width: u32, height: u32) -> f64 {
+    width.min(height) as f64 / 1080.0
+}
+
+/// Run `f` on every pixel with its center in image coordinates.
+fn per_pixel(tile: &mut Tile, f: impl Fn(f64, f64, &mut [f32; 4])) {
+    let width = tile.width as usize;
+    for (i, px) in tile.pixels.iter_mut().enumerate() {
+        let x = (i % width) as f64 + 0.5;
+        let y = (tile.top as usize + i / width) as f64 + 0.5;
+        f(x, y, px);
+    }
+}
+
+fn scale(px: &mut [f32; 4], factor: f64) {
+    for c in &mut px[..3] {
+        *c *= factor as f32;
+    }
+}
+
+/// Radii of three box blurs that together approximate a gaussian of `sigma`.
+fn box_sizes(sigma: f64) -> [usize; 3] {
+    if sigma <= 0.0 {
+        return [0; 3];
+    }
+    let ideal = (12.0 * sigma * sigma / 3.0 + 1.0).sqrt();
+    let mut lower = ideal.floor() as i64;
+    if lower % 2 == 0 {
+        lower -= 1;
+    }
+    let lower = lower.max(1);
+    let upper = lower + 2;
+    let m = ((12.0 * sigma * sigma - (3 * lower * lower + 12 * lower + 9) as f64)
+        / (-4 * lower - 4) as f64)
+        .round() as i64;
+    [0, 1, 2].map(|i| (if i < m { lower } else { upper } as usize - 1) / 2)
+}
+
+fn blur(tile: &mut Tile, sigma: f64) {
+    let (w, h) = (tile.width as usize, tile.height as usize);
+    let mut line = Vec::new();
+    for r in box_sizes(sigma) {
+        if r == 0 {
+            continue;
+        }
+        for row in tile.pixels.chunks_exact_mut(w) {
+            line.clear();
+            line.extend_from_slice(row);
+            box_line(&line, row.iter_mut(), r);
+        }
+        for x in 0..w {
+            line.clear();
+            line.extend((0..h).map(|y| tile.pixels[y * w + x]));
+            box_line(&line, tile.pixels.iter_mut().skip(x).step_by(w), r);
+        }
+    }
+}
+
+/// Running-sum box blur of radius `r`, clamping at the ends.
+fn box_line<'a>(src: &[[f32; 4]], dst: impl Iterator<Item = &'a mut [f32; 4]>, r: usize) {
+    let n = src.len() as i64;
+    let at = |i: i64| src[i.clamp(0, n - 1) as usize];
+    let inv = 1.0 / (2 * r + 1) as f32;
+    let r = r as i64;
+
+    let mut sum = [0.0f32; 4];
+    for i in -r..=r {
+        let p = at(i);
+        for c in 0..4 {
+            sum[c] += p[c];
+        }
+    }
+    for (i, out) in dst.enumerate() {
+        let i = i as i64;
+        *out = sum.map(|s| s * inv);
+        let (add, sub) = (at(i + r + 1), at(i - r));
+        for c in 0..4 {
+            sum[c] += add[c] - sub[c];
+        }
+    }
+}
+
+/// Sample red and blue along rays from the center, scaled by 1 ± `k`.
+fn aberrate(tile: &mut Tile, width: u32, (cx, cy): (f64, f64), k: f64) {
+    let src = tile.pixels.clone();
+    let (w, h, top) = (width as usize, tile.height as usize, tile.top as f64);
+    let sample = |x: f64, y: f64, channel: usize| -> f32 {
+        let (x, y) = ((x - 0.5).clamp(0.0, (w - 1) as f64), (y - top - 0.5).clamp(0.0, (h - 1) as f64));
+        let (x0, y0) = (x.floor() as usize, y.floor() as usize);
+        let (x1, y1) = ((x0 + 1).min(w - 1), (y0 + 1).min(h - 1));
+        let (fx, fy) = ((x - x0 as f64) as f32, (y - y0 as f64) as f32);
+        let row = |y: usize| {
+            src[y * w + x0][channel] * (1.0 - fx) + src[y * w + x1][channel] * fx
+        };
+        row(y0) * (1.0 - fy) + row(y1) * fy
+    };
+
+    per_pixel(tile, |x, y, px| {
+        let (dx, dy) = (x - cx, y - cy);
+        px[0] = sample(cx + dx * (1.0 + k), cy + dy * (1.0 + k), 0);
+        px[2] = sample(cx + dx * (1.0 - k), cy + dy * (1.0 - k), 2);
+    });
+}
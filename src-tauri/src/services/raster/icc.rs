@@ -0,0 +1,194 @@
+//! Minimal ICC v4 display profiles (matrix + sRGB transfer curve), built at
+//! runtime from the color space's primaries.
+
+use super::ColorSpace;
+
+const D65: (f64, f64) = (0.3127, 0.3290);
+const D50_XYZ: [f64; 3] = [0.9642, 1.0, 0.8249];
+
+/// Bradford cone response matrix used for chromatic adaptation.
+const BRADFORD: [[f64; 3]; 3] = [
+    [0.8951, 0.2664, -0.1614],
+    [-0.7502, 1.7135, 0.0367],
+    [0.0389, -0.0685, 1.0296],
+];
+
+/// Serialized profile for embedding in an iCCP chunk.
+pub fn profile(space: ColorSpace) -> Vec<u8> {
+    let name = match space {
+        ColorSpace::Srgb => "sRGB",
+        ColorSpace::DisplayP3 => "Display P3",
+    };
+
+    let chad = adaptation(xy_to_xyz(D65), D50_XYZ);
+    let colorants = mul(&chad, &rgb_to_xyz(primaries(space)));
+    let column = |i: usize| [colorants[0][i], colorants[1][i], colorants[2][i]];
+
+    // Both spaces share the piecewise sRGB curve: ((x + 0.055) / 1.055)^2.4 above 0.04045
+    let trc = para(&[2.4, 1.0 / 1.055, 0.055 / 1.055, 1.0 / 12.92, 0.04045]);
+
+    build(&[
+        (*b"desc", mluc(name)),
+        (*b"cprt", mluc("No copyright, use freely")),
+        (*b"wtpt", xyz(D50_XYZ)),
+        (*b"chad", sf32(&chad)),
+        (*b"rXYZ", xyz(column(0))),
+        (*b"gXYZ", xyz(column(1))),
+        (*b"bXYZ", xyz(column(2))),
+        (*b"rTRC", trc.clone()),
+        (*b"gTRC", trc.clone()),
+        (*b"bTRC", trc),
+    ])
+}
+
+/// Linear-light matrix taking `from` RGB to `to` RGB. Both spaces are D65, so
+/// no adaptation is needed.
+pub fn conversion(from: ColorSpace, to: ColorSpace) -> [[f64; 3]; 3] {
+    mul(&invert(&rgb_to_xyz(primaries(to))), &rgb_to_xyz(primaries(from)))
+}
+
+/// Re-express an encoded color through `matrix` from [`conversion`], going
+/// through linear light since both spaces share the sRGB curve.
+pub fn convert(matrix: &[[f64; 3]; 3], rgb: [f32; 3]) -> [f32; 3] {
+    let linear = rgb.map(|c| {
+        let c = c as f64;
+        if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+    });
+    apply(matrix, linear).map(|c| {
+        let c = if c <= 0.0031308 { c * 12.92 } else { 1.055 * c.powf(1.0 / 2.4) - 0.055 };
+        c as f32
+    })
+}
+
+fn primaries(space: ColorSpace) -> [(f64, f64); 3] {
+    match space {
+        ColorSpace::Srgb => [(0.64, 0.33), (0.30, 0.60), (0.15, 0.06)],
+        ColorSpace::DisplayP3 => [(0.680, 0.320), (0.265, 0.690), (0.150, 0.060)],
+    }
+}
+
+/// Lay out the header, tag table and tag data.
+fn build(tags: &[([u8; 4], Vec<u8>)]) -> Vec<u8> {
+    let table_len = 4 + tags.len() * 12;
+    let mut data = Vec::new();
+    let mut table = Vec::with_capacity(table_len);
+    table.extend_from_slice(&(tags.len() as u32).to_be_bytes());
+
+    for (sig, body) in tags {
+        let offset = 128 + table_len + data.len();
+        table.extend_from_slice(sig);
+        table.extend_from_slice(&(offset as u32).to_be_bytes());
+        table.extend_from_slice(&(body.len() as u32).to_be_bytes());
+        data.extend_from_slice(body);
+        while data.len() % 4 != 0 {
+            data.push(0);
+        }
+    }
+
+    let size = 128 + table.len() + data.len();
+    let mut header = vec![0u8; 128];
+    header[0..4].copy_from_slice(&(size as u32).to_be_bytes());
+    header[8..12].copy_from_slice(&[4, 0x30, 0, 0]);
+    header[12..16].copy_from_slice(b"mntr");
+    header[16..20].copy_from_slice(b"RGB ");
+    header[20..24].copy_from_slice(b"XYZ ");
+    for (i, v) in [2024u16, 1, 1, 0, 0, 0].iter().enumerate() {
+        header[24 + i * 2..26 + i * 2].copy_from_slice(&v.to_be_bytes());
+    }
+    header[36..40].copy_from_slice(b"acsp");
+    header[68..80].copy_from_slice(&xyz(D50_XYZ)[8..20]);
+
+    [header, table, data].concat()
+}
+
+fn s15f16(v: f64) -> [u8; 4] {
+    ((v * 65536.0).round() as i32).to_be_bytes()
+}
+
+fn xyz(v: [f64; 3]) -> Vec<u8> {
+    let mut out = b"XYZ \0\0\0\0".to_vec();
+    for c in v {
+        out.extend_from_slice(&s15f16(c));
+    }
+    out
+}
+
+fn sf32(m: &[[f64; 3]; 3]) -> Vec<u8> {
+    let mut out = b"sf32\0\0\0\0".to_vec();
+    for c in m.iter().flatten() {
+        out.extend_from_slice(&s15f16(*c));
+    }
+    out
+}
+
+/// Parametric curve, function type 3: Y = (aX + b)^g for X >= d, else cX.
+fn para(params: &[f64; 5]) -> Vec<u8> {
+    let mut out = b"para\0\0\0\0\0\x03\0\0".to_vec();
+    for p in params {
+        out.extend_from_slice(&s15f16(*p));
+    }
+    out
+}
+
+/// Single-record multi-localized Unicode text.
+fn mluc(text: &str) -> Vec<u8> {
+    let utf16: Vec<u8> = text.encode_utf16().flat_map(|u| u.to_be_bytes()).collect();
+    let mut out = b"mluc\0\0\0\0".to_vec();
+    out.extend_from_slice(&1u32.to_be_bytes());
+    out.extend_from_slice(&12u32.to_be_bytes());
+    out.extend_from_slice(b"enUS");
+    out.extend_from_slice(&(utf16.len() as u32).to_be_bytes());
+    out.extend_from_slice(&28u32.to_be_bytes());
+    out.extend_from_slice(&utf16);
+    out
+}
+
+fn xy_to_xyz((x, y): (f64, f64)) -> [f64; 3] {
+    [x / y, 1.0, (1.0 - x - y) / y]
+}
+
+/// Linear RGB → XYZ (D65) from the primaries' chromaticities.
+fn rgb_to_xyz(primaries: [(f64, f64); 3]) -> [[f64; 3]; 3] {
+    let p = primaries.map(xy_to_xyz);
+    let m = [
+        [p[0][0], p[1][0], p[2][0]],
+        [p[0][1], p[1][1], p[2][1]],
+        [p[0][2], p[1][2], p[2][2]],
+    ];
+    let s = apply(&invert(&m), xy_to_xyz(D65));
+    [
+        [m[0][0] * s[0], m[0][1] * s[1], m[0][2] * s[2]],
+        [m[1][0] * s[0], m[1][1] * s[1], m[1][2] * s[2]],
+        [m[2][0] * s[0], m[2][1] * s[1], m[2][2] * s[2]],
+    ]
+}
+
+/// Bradford adaptation from white point `src` to `dst`.
+fn adaptation(src: [f64; 3], dst: [f64; 3]) -> [[f64; 3]; 3] {
+    let s = apply(&BRADFORD, src);
+    let d = apply(&BRADFORD, dst);
+    let scale = [
+        [d[0] / s[0], 0.0, 0.0],
+        [0.0, d[1] / s[1], 0.0],
+        [0.0, 0.0, d[2] / s[2]],
+    ];
+    mul(&invert(&BRADFORD), &mul(&scale, &BRADFORD))
+}
+
+fn apply(m: &[[f64; 3]; 3], v: [f64; 3]) -> [f64; 3] {
+    [0, 1, 2].map(|r| m[r][0] * v[0] + m[r][1] * v[1] + m[r][2] * v[2])
+}
+
+fn mul(a: &[[f64; 3]; 3], b: &[[f64; 3]; 3]) -> [[f64; 3]; 3] {
+    [0, 1, 2].map(|r| [0, 1, 2].map(|c| (0..3).map(|k| a[r][k] * b[k][c]).sum()))
+}
+
+fn invert(m: &[[f64; 3]; 3]) -> [[f64; 3]; 3] {
+    let [[a, b, c], [d, e, f], [g, h, i]] = *m;
+    let det = a * (e * i - f * h) - b * (d * i - f * g) + c * (d * h - e * g);
+    [
+        [(e * i - f * h) / det, (c * h - b * i) / det, (b * f - c * e) / det],
+        [(f * g - d * i) / det, (a * i - c * g) / det, (c * d - a * f) / det],
+        [(d * h - e * g) / det, (b * g - a * h) / det, (a * e - b * d) / det],
+    ]
+}
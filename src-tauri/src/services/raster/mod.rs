@@ -0,0 +1,294 @@
+//! High-precision raster output shared by every image the app writes.
+//!
+//! Images are produced as horizontal strips of floating-point pixels, rendered
+//! in parallel and streamed into the PNG encoder, so peak memory stays at a few
+//! strips however large the wallpaper is. Quantization happens only at the very
+//! end, with optional ordered dithering or 16-bit samples to avoid banding.
+
+mod adjust;
+mod effects;
+mod icc;
+
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+use image::{DynamicImage, ImageBuffer, ImageDecoder, Rgb, Rgba};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+
+pub use adjust::Adjustments;
+pub use effects::{Effect, EffectChain};
+
+pub type Rgba16Image = ImageBuffer<Rgba<u16>, Vec<u16>>;
+type RgbImage = ImageBuffer<Rgb<u8>, Vec<u8>>;
+
+/// Rows per strip. Strips are rendered one batch per worker thread at a time.
+const STRIP_ROWS: u32 = 128;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BitDepth {
+    #[default]
+    Eight,
+    Sixteen,
+}
+
+/// Color profile embedded in the PNG. Pixels are composited in sRGB and
+/// converted at quantization, so Display P3 output looks the same on any
+/// color-managed display, in a container that later edits can saturate into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorSpace {
+    #[default]
+    Srgb,
+    DisplayP3,
+}
+
+/// How rendered pixels are encoded to disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OutputOptions {
+    pub bit_depth: BitDepth,
+    /// Ordered dithering when quantizing to 8 bits; ignored for 16-bit output.
+    pub dither: bool,
+    pub color_space: ColorSpace,
+}
+
+impl Default for OutputOptions {
+    fn default() -> Self {
+        Self {
+            bit_depth: BitDepth::Eight,
+            dither: true,
+            color_space: ColorSpace::Srgb,
+        }
+    }
+}
+
+/// A strip of premultiplied RGBA pixels in 0.0..=1.0, `top` rows down the image.
+pub struct Tile {
+    pub width: u32,
+    pub height: u32,
+    pub top: u32,
+    pub pixels: Vec<[f32; 4]>,
+}
+
+impl Tile {
+    pub fn filled(width: u32, height: u32, top: u32, color: [f32; 4]) -> Tile {
+        Tile {
+            width,
+            height,
+            top,
+            pixels: vec![color; (width * height) as usize],
+        }
+    }
+
+    /// Rows `top..top + rows` of a decoded image, premultiplied.
+    fn from_image(image: &Rgba16Image, top: u32, rows: u32) -> Tile {
+        let width = image.width();
+        let start = (top * width) as usize * 4;
+        let end = ((top + rows) * width) as usize * 4;
+        let pixels = image.as_raw()[start..end]
+            .chunks_exact(4)
+            .map(|p| {
+                let a = p[3] as f32 / 65535.0;
+                let channel = |v: u16| v as f32 / 65535.0 * a;
+                [channel(p[0]), channel(p[1]), channel(p[2]), a]
+            })
+            .collect();
+        Tile { width, height: rows, top, pixels }
+    }
+
+    /// The `rows` rows starting at image row `top`, which must lie inside this tile.
+    fn crop(mut self, top: u32, rows: u32) -> Tile {
+        let start = ((top - self.top) * self.width) as usize;
+        self.pixels.truncate(start + (rows * self.width) as usize);
+        self.pixels.drain(..start);
+        Tile {
+            height: rows,
+            top,
+            ..self
+        }
+    }
+}
+
+/// Write a `width`x`height` PNG whose strips come from `render(top, rows)`,
+/// passed through `effects`. `progress` is called with (strips done, total).
+pub fn write_png<F>(
+    output_path: &PathBuf,
+    width: u32,
+    height: u32,
+    options: &OutputOptions,
+    effects: &EffectChain,
+    render: F,
+    progress: &(dyn Fn(u32, u32) + Sync),
+) -> Result<(), AppError>
+where
+    F: Fn(u32, u32) -> Result<Tile, AppError> + Sync,
+{
+    let png_err = |e: png::EncodingError| AppError::Generation(format!("Failed to save PNG: {}", e));
+
+    let mut info = png::Info::with_size(width, height);
+    info.color_type = png::ColorType::Rgb;
+    info.bit_depth = match options.bit_depth {
+        BitDepth::Eight => png::BitDepth::Eight,
+        BitDepth::Sixteen => png::BitDepth::Sixteen,
+    };
+    info.icc_profile = Some(Cow::Owned(icc::profile(options.color_space)));
+
+    let file = File::create(output_path)?;
+    let encoder = png::Encoder::with_info(BufWriter::new(file), info).map_err(png_err)?;
+    let mut writer = encoder.write_header().map_err(png_err)?;
+    let mut stream = writer.stream_writer().map_err(png_err)?;
+
+    let total = height.div_ceil(STRIP_ROWS);
+    let halo = effects.halo(width, height);
+    let batch = rayon::current_num_threads().max(1) as u32;
+
+    let mut done = 0;
+    progress(done, total);
+    while done < total {
+        let end = (done + batch).min(total);
+        let strips = (done..end)
+            .into_par_iter()
+            .map(|i| {
+                let top = i * STRIP_ROWS;
+                let rows = STRIP_ROWS.min(height - top);
+                if effects.is_empty() {
+                    return Ok(quantize(&render(top, rows)?, options));
+                }
+
+                let padded_top = top.saturating_sub(halo);
+                let padded_rows = (top + rows + halo).min(height) - padded_top;
+                let mut tile = render(padded_top, padded_rows)?;
+                effects.apply(&mut tile, width, height);
+                Ok(quantize(&tile.crop(top, rows), options))
+            })
+            .collect::<Result<Vec<_>, AppError>>()?;
+
+        for strip in strips {
+            stream.write_all(&strip)?;
+            done += 1;
+            progress(done, total);
+        }
+    }
+
+    stream.finish().map_err(png_err)?;
+    Ok(())
+}
+
+/// Re-encode `input` to `output` through `effects`, keeping the source's color
+/// profile. `output` is only replaced once the new image is complete, so both
+/// may be the same file and a failed encode leaves the original intact.
+pub fn process_file(
+    input: &PathBuf,
+    output: &PathBuf,
+    options: &OutputOptions,
+    effects: &EffectChain,
+    progress: &(dyn Fn(u32, u32) + Sync),
+) -> Result<(), AppError> {
+    let (image, color_space) = open_image(input)?;
+    let (width, height) = image.dimensions();
+    let options = OutputOptions { color_space, ..*options };
+
+    let partial = output.with_extension("partial.png");
+    let written = write_png(
+        &partial,
+        width,
+        height,
+        &options,
+        effects,
+        |top, rows| Ok(Tile::from_image(&image, top, rows)),
+        progress,
+    );
+    if written.is_err() {
+        let _ = std::fs::remove_file(&partial);
+    }
+    written?;
+    std::fs::rename(&partial, output)?;
+    Ok(())
+}
+
+/// Apply `effects` to an in-memory image, e.g. a downscaled copy for a live preview.
+pub fn preview(image: &Rgba16Image, effects: &EffectChain) -> RgbImage {
+    let (width, height) = image.dimensions();
+    let mut tile = Tile::from_image(image, 0, height);
+    effects.apply(&mut tile, width, height);
+    let data = quantize(&tile, &OutputOptions::default());
+    RgbImage::from_raw(width, height, data).expect("quantized buffer matches image size")
+}
+
+/// Decode an image and tell whether it was tagged with our Display P3 profile.
+/// P3 pixels are converted back to sRGB, which the rest of the pipeline expects.
+pub fn open_image(path: &PathBuf) -> Result<(Rgba16Image, ColorSpace), AppError> {
+    let decode_err = |e: image::ImageError| AppError::Generation(format!("Failed to decode image: {}", e));
+    let mut decoder = image::ImageReader::open(path)?
+        .with_guessed_format()?
+        .into_decoder()
+        .map_err(decode_err)?;
+    let color_space = match decoder.icc_profile() {
+        Ok(Some(icc)) if icc == icc::profile(ColorSpace::DisplayP3) => ColorSpace::DisplayP3,
+        _ => ColorSpace::Srgb,
+    };
+    let mut image = DynamicImage::from_decoder(decoder).map_err(decode_err)?.to_rgba16();
+    if color_space == ColorSpace::DisplayP3 {
+        let matrix = icc::conversion(ColorSpace::DisplayP3, ColorSpace::Srgb);
+        image.par_chunks_mut(4).for_each(|p| {
+            let rgb = icc::convert(&matrix, [0, 1, 2].map(|i| p[i] as f32 / 65535.0));
+            for (v, c) in p.iter_mut().zip(rgb) {
+                *v = (c.clamp(0.0, 1.0) * 65535.0 + 0.5) as u16;
+            }
+        });
+    }
+    Ok((image, color_space))
+}
+
+/// Flatten a tile onto black, convert it to the output color space and encode
+/// it as big-endian RGB rows.
+fn quantize(tile: &Tile, options: &OutputOptions) -> Vec<u8> {
+    let sixteen = options.bit_depth == BitDepth::Sixteen;
+    let to_p3 = (options.color_space == ColorSpace::DisplayP3)
+        .then(|| icc::conversion(ColorSpace::Srgb, ColorSpace::DisplayP3));
+    let mut out = Vec::with_capacity(tile.pixels.len() * if sixteen { 6 } else { 3 });
+
+    for (i, px) in tile.pixels.iter().enumerate() {
+        let x = i as u32 % tile.width;
+        let y = tile.top + i as u32 / tile.width;
+        let threshold = if options.dither && !sixteen { bayer(x, y) } else { 0.5 };
+
+        let rgb = [px[0], px[1], px[2]];
+        let rgb = match &to_p3 {
+            Some(matrix) => icc::convert(matrix, rgb),
+            None => rgb,
+        };
+        for c in rgb {
+            let c = c.clamp(0.0, 1.0);
+            if sixteen {
+                let v = (c * 65535.0 + 0.5) as u16;
+                out.extend_from_slice(&v.to_be_bytes());
+            } else {
+                out.push((c * 255.0 + threshold).min(255.0) as u8);
+            }
+        }
+    }
+    out
+}
+
+/// 8x8 ordered-dither threshold in 0.0..1.0. Indexed by absolute image
+/// position so the pattern stays continuous across strips.
+fn bayer(x: u32, y: u32) -> f32 {
+    const MATRIX: [[u8; 8]; 8] = [
+        [0, 32, 8, 40, 2, 34, 10, 42],
+        [48, 16, 56, 24, 50, 18, 58, 26],
+        [12, 44, 4, 36, 14, 46, 6, 38],
+        [60, 28, 52, 20, 62, 30, 54, 22],
+        [3, 35, 11, 43, 1, 33, 9, 41],
+        [51, 19, 59, 27, 49, 17, 57, 25],
+        [15, 47, 7, 39, 13, 45, 5, 37],
+        [63, 31, 55, 23, 61, 29, 53, 21],
+    ];
+    (MATRIX[(y % 8) as usize][(x % 8) as usize] as f32 + 0.5) / 64.0
+}
@@ -0,0 +1,537 @@
+//! Aperiodic tilings: Penrose's rhombs (P3) and kites and darts (P2), and
+//! the "hat" monotile of Smith, Myers, Kaplan and Goodman-Strauss.
+//!
+//! All three are grown by substitution, never by repeating a patch, so no
+//! stretch of a wide spanned canvas repeats another. Penrose tilings come from
+//! Robinson triangles split again and again from a sun of ten, dropping those
+//! that fall off the canvas at every step. Hats come from the four metatiles
+//! (H, T, P and F) of the paper, inflated into supertiles until one covers
+//! the canvas, then walked down to the hats. Tiles are colored by type and
+//! by which way they face, and outlined with grout in the background color.
+
+use std::f64::consts::PI;
+use std::rc::Rc;
+
+use rand::Rng;
+
+use super::scene::{Color, Node, Segment, Shape, Transform};
+use super::{pick, pick_ramp, ramp, Canvas, Palette};
+
+type Point = (f64, f64);
+
+const PHI: f64 = 1.618_033_988_749_895;
+
+const WHITE: Color = Color { r: 255, g: 255, b: 255 };
+
+#[derive(Clone, Copy)]
+enum Variant {
+    /// Thick and thin rhombs.
+    Rhombs,
+    /// Kites and darts.
+    KitesAndDarts,
+    Hat,
+}
+
+pub(super) fn generate(rng: &mut impl Rng, palette: &Palette, c: &Canvas) -> Vec<Node> {
+    let variant = match rng.gen_range(0..10) {
+        0..=3 => Variant::Rhombs,
+        4..=6 => Variant::KitesAndDarts,
+        _ => Variant::Hat,
+    };
+    let size = c.unit * rng.gen_range(0.04..0.09) / c.density.sqrt();
+    let tiles = match variant {
+        Variant::Rhombs | Variant::KitesAndDarts => penrose(rng, c, size, matches!(variant, Variant::Rhombs)),
+        Variant::Hat => hats(rng, c, size * 1.3),
+    };
+
+    let (classes, colors) = match variant {
+        Variant::Rhombs | Variant::KitesAndDarts => {
+            let classes = if matches!(variant, Variant::Rhombs) { 5 } else { 10 };
+            let ramps = pick_ramp(rng, palette, 4);
+            // Each type gets its own pair of colors, blended around the turns
+            let colors = (0..2 * classes)
+                .map(|i| {
+                    let (kind, turn) = (i / classes, i % classes);
+                    ramp(&ramps[kind * 2..kind * 2 + 2], cyclic(turn, classes))
+                })
+                .collect();
+            (classes, colors)
+        }
+        Variant::Hat => {
+            let accent = Color::hex(palette.bg).mix(WHITE, rng.gen_range(0.6..0.9));
+            if rng.gen_bool(0.5) {
+                // Six turns and the reflected hats, which stand out
+                let ramps = pick_ramp(rng, palette, 3);
+                let mut colors: Vec<Color> = (0..6).map(|turn| ramp(&ramps, cyclic(turn, 6))).collect();
+                colors.push(accent);
+                (6, colors)
+            } else {
+                // One color per metatile the hat came from
+                let mut colors = pick_ramp(rng, palette, 4);
+                colors.push(accent);
+                (0, colors)
+            }
+        }
+    };
+
+    let mut paths: Vec<Vec<Segment>> = vec![Vec::new(); colors.len()];
+    let mut grout = Vec::new();
+    for tile in &tiles {
+        let index = match tile.kind {
+            Kind::Reflected => colors.len() - 1,
+            Kind::Label(label) if classes == 0 => label as usize,
+            Kind::Label(_) => tile.turn % classes,
+            Kind::Penrose(kind) => kind * classes + tile.turn % classes,
+        };
+        let segments = polygon_segments(&tile.points);
+        grout.extend(segments.iter().copied());
+        paths[index].extend(segments);
+    }
+
+    // One path per color and one for all the grout keeps the scene small
+    let mut nodes: Vec<Node> = vec![Shape::rect(0.0, 0.0, c.w, c.h).fill(pick(rng, palette)).into()];
+    nodes.extend(
+        paths
+            .into_iter()
+            .zip(colors)
+            .filter(|(path, _)| !path.is_empty())
+            .map(|(path, color)| Shape::path(path).fill(color).into()),
+    );
+    let width = c.px(rng.gen_range(1.5..3.5));
+    nodes.push(Shape::path(grout).stroke(Color::hex(palette.bg), width).opacity(0.9).into());
+    nodes
+}
+
+/// 0.0 to 1.0 and back again over `n` steps, so colors wrap around the turns.
+fn cyclic(i: usize, n: usize) -> f64 {
+    1.0 - (2.0 * i as f64 / n as f64 - 1.0).abs()
+}
+
+#[derive(Clone, Copy)]
+enum Kind {
+    /// Penrose tile: 0 for thin rhombs and kites, 1 for thick rhombs and darts.
+    Penrose(usize),
+    /// A hat, labeled by its metatile.
+    Label(Label),
+    /// The odd hat out, mirrored, at the heart of each H metatile.
+    Reflected,
+}
+
+/// A finished tile on the canvas. `turn` counts how far it is rotated from
+/// its tiling's reference direction, in steps of the tiling's symmetry.
+struct Tile {
+    kind: Kind,
+    turn: usize,
+    points: Vec<Point>,
+}
+
+fn polygon_segments(points: &[Point]) -> Vec<Segment> {
+    let mut path = vec![Segment::MoveTo(points[0].0, points[0].1)];
+    path.extend(points[1..].iter().map(|&(x, y)| Segment::LineTo(x, y)));
+    path.push(Segment::Close);
+    path
+}
+
+fn lerp(p: Point, q: Point, t: f64) -> Point {
+    (p.0 + (q.0 - p.0) * t, p.1 + (q.1 - p.1) * t)
+}
+
+/// Whether a circle comes within `margin` of the canvas.
+fn visible(c: &Canvas, center: Point, radius: f64, margin: f64) -> bool {
+    let reach = radius + margin;
+    center.0 > -reach && center.0 < c.w + reach && center.1 > -reach && center.1 < c.h + reach
+}
+
+/// A Robinson triangle: half a Penrose tile, with the apex at `a`.
+#[derive(Clone, Copy)]
+struct Robinson {
+    /// 36° at the apex (half a thin rhomb or a kite) or 108° (half a thick
+    /// rhomb or a dart).
+    obtuse: bool,
+    a: Point,
+    b: Point,
+    c: Point,
+}
+
+/// Penrose tiles about `size` along an edge, from a sun of Robinson triangles
+/// centered anywhere on the canvas and split until its edges reach `size`.
+fn penrose(rng: &mut impl Rng, c: &Canvas, size: f64, rhombs: bool) -> Vec<Tile> {
+    let center = (rng.gen_range(0.0..c.w), rng.gen_range(0.0..c.h));
+    let reach = [(0.0, 0.0), (c.w, 0.0), (0.0, c.h), (c.w, c.h)]
+        .iter()
+        .map(|p| (p.0 - center.0).hypot(p.1 - center.1))
+        .fold(0.0, f64::max);
+    // The sun is a decagon; its inscribed circle has to reach every corner
+    let steps = ((reach / (18f64.to_radians().cos() * size)).ln() / PHI.ln()).ceil().max(0.0) as i32;
+    let radius = size * PHI.powi(steps);
+    let spin = rng.gen_range(0.0..PI / 5.0);
+
+    let mut triangles: Vec<Robinson> = (0..10)
+        .map(|i| {
+            let corner = |k: i32| {
+                let angle = (2 * i + k) as f64 * PI / 10.0 + spin;
+                (center.0 + radius * angle.cos(), center.1 + radius * angle.sin())
+            };
+            // Mirror every other triangle so neighbors pair up into tiles
+            let (first, second) = if i % 2 == 0 { (corner(1), corner(-1)) } else { (corner(-1), corner(1)) };
+            Robinson { obtuse: false, a: center, b: first, c: second }
+        })
+        .collect();
+
+    let mut edge = radius;
+    for _ in 0..steps {
+        triangles = triangles
+            .iter()
+            .flat_map(|t| if rhombs { split_rhombs(t) } else { split_kites(t) })
+            .collect();
+        edge /= PHI;
+        // Keep triangles whose partner might still be on the canvas
+        triangles.retain(|t| {
+            let mid = ((t.a.0 + t.b.0 + t.c.0) / 3.0, (t.a.1 + t.b.1 + t.c.1) / 3.0);
+            visible(c, mid, edge, edge)
+        });
+    }
+
+    // Each tile is two mirrored triangles; build it from the one that turns
+    // clockwise on screen. Rhombs pair along the base, kites and darts along
+    // the side from `a` to `b`.
+    let turns = if rhombs { 5 } else { 10 };
+    triangles
+        .iter()
+        .filter(|t| (t.b.0 - t.a.0) * (t.c.1 - t.a.1) - (t.b.1 - t.a.1) * (t.c.0 - t.a.0) > 0.0)
+        .map(|t| {
+            let (points, axis) = if rhombs {
+                let far = (t.b.0 + t.c.0 - t.a.0, t.b.1 + t.c.1 - t.a.1);
+                (vec![t.a, t.b, far, t.c], (far.0 - t.a.0, far.1 - t.a.1))
+            } else {
+                let axis = (t.b.0 - t.a.0, t.b.1 - t.a.1);
+                let along = ((t.c.0 - t.a.0) * axis.0 + (t.c.1 - t.a.1) * axis.1) / (axis.0 * axis.0 + axis.1 * axis.1);
+                let foot = lerp(t.a, t.b, along);
+                let mirror = (2.0 * foot.0 - t.c.0, 2.0 * foot.1 - t.c.1);
+                (vec![t.a, t.c, t.b, mirror], axis)
+            };
+            let angle = axis.1.atan2(axis.0) - spin;
+            let turn = (angle / (2.0 * PI / turns as f64)).round().rem_euclid(turns as f64) as usize;
+            Tile { kind: Kind::Penrose(t.obtuse as usize), turn, points }
+        })
+        .collect()
+}
+
+/// Split for rhombs (P3).
+fn split_rhombs(t: &Robinson) -> Vec<Robinson> {
+    let Robinson { obtuse, a, b, c } = *t;
+    let tri = |obtuse, a, b, c| Robinson { obtuse, a, b, c };
+    if obtuse {
+        let q = lerp(b, a, 1.0 / PHI);
+        let r = lerp(b, c, 1.0 / PHI);
+        vec![tri(true, r, c, a), tri(true, q, r, b), tri(false, r, q, a)]
+    } else {
+        let p = lerp(a, b, 1.0 / PHI);
+        vec![tri(false, c, p, b), tri(true, p, c, a)]
+    }
+}
+
+/// Split for kites and darts (P2).
+fn split_kites(t: &Robinson) -> Vec<Robinson> {
+    let Robinson { obtuse, a, b, c } = *t;
+    let tri = |obtuse, a, b, c| Robinson { obtuse, a, b, c };
+    if obtuse {
+        let z = lerp(b, c, 1.0 / PHI);
+        vec![tri(false, b, a, z), tri(true, z, c, a)]
+    } else {
+        let x = lerp(a, b, 1.0 / PHI);
+        let y = lerp(c, a, 1.0 / PHI);
+        vec![tri(false, c, x, b), tri(false, c, x, y), tri(true, y, a, x)]
+    }
+}
+
+/// The four metatiles, in the order rules refer to them.
+#[derive(Clone, Copy, PartialEq)]
+enum Label {
+    H,
+    T,
+    P,
+    F,
+}
+
+/// A hat, or a metatile or supertile made of transformed children.
+enum Hat {
+    Leaf { label: Label, reflected: bool },
+    Super { outline: Vec<Point>, children: Vec<(Transform, Rc<Hat>)>, radius: f64 },
+}
+
+const SQRT3_2: f64 = 0.866_025_403_784_438_6;
+
+/// The hat's 13 corners on the hexagonal grid it is drawn on.
+fn hat_outline() -> Vec<Point> {
+    [(0, 0), (-1, -1), (0, -2), (2, -2), (2, -1), (4, -2), (5, -1), (4, 0), (3, 0), (2, 2), (0, 3), (0, 2), (-1, 2)]
+        .iter()
+        .map(|&(x, y)| (x as f64 + 0.5 * y as f64, SQRT3_2 * y as f64))
+        .collect()
+}
+
+/// How each supertile is put together from the previous generation:
+/// the first child as is, then each further one glued along an edge to
+/// earlier children.
+enum Rule {
+    Root(Label),
+    /// Child `.2`'s edge `.3` glued to edge `.1` of child `.0`.
+    Edge(usize, usize, Label, usize),
+    /// Child `.4`'s edge `.5` glued from corner `.3` of child `.2` to
+    /// corner `.1` of child `.0`.
+    Span(usize, usize, usize, usize, Label, usize),
+}
+
+const RULES: [Rule; 29] = {
+    use Label::*;
+    use Rule::*;
+    [
+        Root(H),
+        Edge(0, 0, P, 2),
+        Edge(1, 0, H, 2),
+        Edge(2, 0, P, 2),
+        Edge(3, 0, H, 2),
+        Edge(4, 4, P, 2),
+        Edge(0, 4, F, 3),
+        Edge(2, 4, F, 3),
+        Span(4, 1, 3, 2, F, 0),
+        Edge(8, 3, H, 0),
+        Edge(9, 2, P, 0),
+        Edge(10, 2, H, 0),
+        Edge(11, 4, P, 2),
+        Edge(12, 0, H, 2),
+        Edge(13, 0, F, 3),
+        Edge(14, 2, F, 1),
+        Edge(15, 3, H, 4),
+        Edge(8, 2, F, 1),
+        Edge(17, 3, H, 0),
+        Edge(18, 2, P, 0),
+        Edge(19, 2, H, 2),
+        Edge(20, 4, F, 3),
+        Edge(20, 0, P, 2),
+        Edge(22, 0, H, 2),
+        Edge(23, 4, F, 3),
+        Edge(23, 0, F, 3),
+        Edge(16, 0, P, 2),
+        Span(9, 4, 0, 2, T, 2),
+        Edge(4, 0, F, 3),
+    ]
+};
+
+fn apply(t: &Transform, p: Point) -> Point {
+    (t.a * p.0 + t.c * p.1 + t.e, t.b * p.0 + t.d * p.1 + t.f)
+}
+
+fn invert(t: Transform) -> Transform {
+    let det = t.a * t.d - t.b * t.c;
+    Transform {
+        a: t.d / det,
+        b: -t.b / det,
+        c: -t.c / det,
+        d: t.a / det,
+        e: (t.c * t.f - t.d * t.e) / det,
+        f: (t.b * t.e - t.a * t.f) / det,
+    }
+}
+
+/// The similarity taking (0, 0) to `p` and (1, 0) to `q`.
+fn segment(p: Point, q: Point) -> Transform {
+    Transform { a: q.0 - p.0, b: q.1 - p.1, c: p.1 - q.1, d: q.0 - p.0, e: p.0, f: p.1 }
+}
+
+/// The similarity taking `p1` to `p2` and `q1` to `q2`.
+fn match_two(p1: Point, q1: Point, p2: Point, q2: Point) -> Transform {
+    segment(p2, q2).then(invert(segment(p1, q1)))
+}
+
+fn add(p: Point, q: Point) -> Point {
+    (p.0 + q.0, p.1 + q.1)
+}
+
+fn sub(p: Point, q: Point) -> Point {
+    (p.0 - q.0, p.1 - q.1)
+}
+
+/// Where the line through `p1` and `q1` meets the one through `p2` and `q2`.
+fn intersect(p1: Point, q1: Point, p2: Point, q2: Point) -> Point {
+    let d = (q2.1 - p2.1) * (q1.0 - p1.0) - (q2.0 - p2.0) * (q1.1 - p1.1);
+    let u = ((q2.0 - p2.0) * (p1.1 - p2.1) - (q2.1 - p2.1) * (p1.0 - p2.0)) / d;
+    lerp(p1, q1, u)
+}
+
+impl Hat {
+    fn outline(&self) -> Vec<Point> {
+        match self {
+            Hat::Leaf { .. } => hat_outline(),
+            Hat::Super { outline, .. } => outline.clone(),
+        }
+    }
+
+    /// A supertile around `outline`, moved so the outline is centered on the
+    /// origin. The bounding radius allows for hats poking out past it.
+    fn supertile(outline: Vec<Point>, children: Vec<(Transform, Rc<Hat>)>) -> Rc<Hat> {
+        let n = outline.len() as f64;
+        let mid = (outline.iter().map(|p| p.0).sum::<f64>() / n, outline.iter().map(|p| p.1).sum::<f64>() / n);
+        let shift = Transform::translate(-mid.0, -mid.1);
+        let outline: Vec<Point> = outline.iter().map(|p| sub(*p, mid)).collect();
+        let radius = outline.iter().map(|p| p.0.hypot(p.1)).fold(0.0, f64::max) + 6.0;
+        let children = children.into_iter().map(|(t, child)| (shift.then(t), child)).collect();
+        Rc::new(Hat::Super { outline, children, radius })
+    }
+}
+
+/// The four metatiles each hat belongs to, in `Label` order.
+fn metatiles() -> [Rc<Hat>; 4] {
+    let leaf = |label| Rc::new(Hat::Leaf { label, reflected: false });
+    let hat = hat_outline();
+    let half = Transform::scale(0.5, 0.5);
+    // The two hats every T, P and F metatile starts from
+    let pair = |label| {
+        let first = Transform { e: 1.5, f: SQRT3_2, ..half };
+        let turn = Transform { a: 0.5, b: -SQRT3_2, c: SQRT3_2, d: 0.5, e: 0.0, f: 0.0 };
+        let second = Transform::translate(0.0, 2.0 * SQRT3_2).then(turn.then(half));
+        vec![(first, leaf(label)), (second, leaf(label))]
+    };
+
+    let o = [(0.0, 0.0), (4.0, 0.0), (4.5, SQRT3_2), (2.5, 5.0 * SQRT3_2), (1.5, 5.0 * SQRT3_2), (-0.5, SQRT3_2)];
+    let mirror = Transform { a: -0.5, b: SQRT3_2, c: -SQRT3_2, d: -0.5, e: 0.0, f: 0.0 };
+    let h = Hat::supertile(
+        o.to_vec(),
+        vec![
+            (match_two(hat[5], hat[7], o[5], o[0]), leaf(Label::H)),
+            (match_two(hat[9], hat[11], o[1], o[2]), leaf(Label::H)),
+            (match_two(hat[5], hat[7], o[3], o[4]), leaf(Label::H)),
+            (
+                Transform::translate(2.5, SQRT3_2).then(mirror.then(Transform::scale(0.5, -0.5))),
+                Rc::new(Hat::Leaf { label: Label::H, reflected: true }),
+            ),
+        ],
+    );
+    let t = Hat::supertile(
+        vec![(0.0, 0.0), (3.0, 0.0), (1.5, 3.0 * SQRT3_2)],
+        vec![(Transform { e: 0.5, f: SQRT3_2, ..half }, leaf(Label::T))],
+    );
+    let p = Hat::supertile(vec![(0.0, 0.0), (4.0, 0.0), (3.0, 2.0 * SQRT3_2), (-1.0, 2.0 * SQRT3_2)], pair(Label::P));
+    let f = Hat::supertile(
+        vec![(0.0, 0.0), (3.0, 0.0), (3.5, SQRT3_2), (3.0, 2.0 * SQRT3_2), (-1.0, 2.0 * SQRT3_2)],
+        pair(Label::F),
+    );
+    [h, t, p, f]
+}
+
+/// The next generation of the four supertiles: a patch of the current ones
+/// laid out by `RULES`, carved into new H, T, P and F outlines.
+fn inflate(tiles: &[Rc<Hat>; 4]) -> [Rc<Hat>; 4] {
+    let mut patch: Vec<(Transform, Rc<Hat>)> = Vec::with_capacity(RULES.len());
+    let corner = |patch: &[(Transform, Rc<Hat>)], child: usize, i: usize| {
+        let (t, tile) = &patch[child];
+        apply(t, tile.outline()[i])
+    };
+    for rule in &RULES {
+        let (label, p, q, edge) = match *rule {
+            Rule::Root(label) => {
+                patch.push((Transform::IDENTITY, tiles[label as usize].clone()));
+                continue;
+            }
+            Rule::Edge(child, edge, label, to) => {
+                let n = patch[child].1.outline().len();
+                (label, corner(&patch, child, (edge + 1) % n), corner(&patch, child, edge), to)
+            }
+            Rule::Span(from, i, to, j, label, edge) => (label, corner(&patch, to, j), corner(&patch, from, i), edge),
+        };
+        let tile = &tiles[label as usize];
+        let outline = tile.outline();
+        let t = match_two(outline[edge], outline[(edge + 1) % outline.len()], p, q);
+        patch.push((t, tile.clone()));
+    }
+
+    let at = |child, i| corner(&patch, child, i);
+    let turn = |p: Point, around: Point, angle: f64| apply(&Transform::rotate(angle, around.0, around.1), p);
+    let (bps1, bps2) = (at(8, 2), at(21, 2));
+    let rbps = turn(bps2, bps1, -120.0);
+    let (p72, p252) = (at(7, 2), at(25, 2));
+    let llc = intersect(bps1, rbps, at(6, 2), p72);
+    let mut w = sub(at(6, 2), llc);
+
+    let mut h = vec![llc, bps1];
+    w = turn(w, (0.0, 0.0), -60.0);
+    h.push(add(h[1], w));
+    h.push(at(14, 2));
+    w = turn(w, (0.0, 0.0), -60.0);
+    h.push(sub(h[3], w));
+    h.push(at(6, 2));
+
+    let p = vec![p72, add(p72, sub(bps1, llc)), bps1, llc];
+    let f = vec![bps2, at(24, 2), at(25, 0), p252, add(p252, sub(llc, bps1))];
+    let b = add(h[1], sub(h[4], h[5]));
+    let t = vec![b, turn(h[2], b, -60.0), h[2]];
+
+    let take = |children: &[usize]| children.iter().map(|&i| patch[i].clone()).collect();
+    [
+        Hat::supertile(h, take(&[0, 9, 16, 27, 26, 6, 1, 8, 10, 15])),
+        Hat::supertile(t, take(&[11])),
+        Hat::supertile(p, take(&[7, 2, 3, 4, 28])),
+        Hat::supertile(f, take(&[21, 20, 22, 23, 24, 25])),
+    ]
+}
+
+/// Distance from the origin to the nearest edge of `outline`.
+fn inradius(outline: &[Point]) -> f64 {
+    (0..outline.len())
+        .map(|i| {
+            let (p, q) = (outline[i], outline[(i + 1) % outline.len()]);
+            let (dx, dy) = sub(q, p);
+            let t = (-(p.0 * dx + p.1 * dy) / (dx * dx + dy * dy)).clamp(0.0, 1.0);
+            (p.0 + dx * t).hypot(p.1 + dy * t)
+        })
+        .fold(f64::INFINITY, f64::min)
+}
+
+/// Hats about `size` across, from an H supertile inflated until it covers
+/// the canvas with room to shift and turn it at random.
+fn hats(rng: &mut impl Rng, c: &Canvas, size: f64) -> Vec<Tile> {
+    // A hat spans about three units of its grid
+    let scale = size / 3.0;
+    let needed = c.w.hypot(c.h) / 2.0 / scale;
+    let mut tiles = metatiles();
+    // Each generation grows about 2.6 times; the cap only guards huge canvases
+    for _ in 0..12 {
+        if inradius(&tiles[0].outline()) > needed * 1.5 {
+            break;
+        }
+        tiles = inflate(&tiles);
+    }
+
+    let room = (inradius(&tiles[0].outline()) - needed).max(0.0);
+    let (angle, offset) = (rng.gen_range(0.0..2.0 * PI), rng.gen_range(0.0..room));
+    let place = Transform::translate(c.w / 2.0, c.h / 2.0)
+        .then(Transform::rotate(rng.gen_range(0.0..360.0), 0.0, 0.0))
+        .then(Transform::scale(scale, scale))
+        .then(Transform::translate(offset * angle.cos(), offset * angle.sin()));
+
+    let mut out = Vec::new();
+    walk(&tiles[0], Transform::IDENTITY, &place, scale, c, &mut out);
+    out
+}
+
+/// Collect the hats under `tile`, skipping supertiles clear of the canvas.
+/// `t` places `tile` in the tiling and `place` puts the tiling on the canvas.
+fn walk(tile: &Hat, t: Transform, place: &Transform, scale: f64, c: &Canvas, out: &mut Vec<Tile>) {
+    match tile {
+        Hat::Super { children, radius, .. } => {
+            if visible(c, apply(&place.then(t), (0.0, 0.0)), radius * scale, 0.0) {
+                for (child, tile) in children {
+                    walk(tile, t.then(*child), place, scale, c, out);
+                }
+            }
+        }
+        Hat::Leaf { label, reflected } => {
+            let on_canvas = place.then(t);
+            let points: Vec<Point> = hat_outline().iter().map(|p| apply(&on_canvas, *p)).collect();
+            let kind = if *reflected { Kind::Reflected } else { Kind::Label(*label) };
+            // Hats sit at multiples of 60° in the tiling, mirrored or not
+            let turn = (t.b.atan2(t.a) / (PI / 3.0)).round().rem_euclid(6.0) as usize;
+            out.push(Tile { kind, turn, points });
+        }
+    }
+}
@@ -0,0 +1,225 @@
+//! Soft light: either a mesh gradient of large, heavily blurred palette
+//! blobs, like the default wallpapers of current desktop systems, or aurora
+//! curtains hanging in a night sky.
+//!
+//! Both lean on shape blur (`Shape::blur`). A curtain is a row of thin
+//! blurred slices, each fading upwards from a bright lower edge; their
+//! heights and brightness vary smoothly along the curtain, which reads as
+//! rays once the slices melt together.
+
+use std::f64::consts::PI;
+
+use rand::Rng;
+
+use super::scene::{Color, GradientStop, LinearGradient, Node, Paint, Segment, Shape};
+use super::{pick_ramp, Canvas, Palette};
+use crate::services::noise;
+
+type Point = (f64, f64);
+
+const WHITE: Color = Color { r: 255, g: 255, b: 255 };
+const BLACK: Color = Color { r: 0, g: 0, b: 0 };
+
+/// Points along each edge of a curtain's glow.
+const GLOW_STEPS: usize = 48;
+
+pub(super) fn generate(rng: &mut impl Rng, palette: &Palette, c: &Canvas) -> Vec<Node> {
+    if rng.gen_bool(0.55) {
+        mesh(rng, palette, c)
+    } else {
+        curtains(rng, palette, c)
+    }
+}
+
+/// Blobs on a jittered grid over a diagonal base gradient, blurred by about
+/// half their size so neighbors flow into one another.
+fn mesh(rng: &mut impl Rng, palette: &Palette, c: &Canvas) -> Vec<Node> {
+    let bg = Color::hex(palette.bg);
+    let stops = rng.gen_range(3..6);
+    let colors = pick_ramp(rng, palette, stops);
+    let (first, last) = (colors[0].mix(bg, 0.35), colors[colors.len() - 1].mix(bg, 0.35));
+    let mut nodes = vec![Shape::rect(0.0, 0.0, c.w, c.h)
+        .paint(linear((0.0, 0.0), (1.0, 1.0), &[(0.0, first, 1.0), (1.0, last, 1.0)]))
+        .into()];
+
+    let cols = ((c.widths() * 3.0).round() as usize).max(2);
+    let rows = if c.h > c.w { 4 } else { rng.gen_range(2..4) };
+    let (cw, ch) = (c.w / cols as f64, c.h / rows as f64);
+    let offset = rng.gen_range(0..colors.len());
+    for row in 0..rows {
+        for col in 0..cols {
+            let x = (col as f64 + rng.gen_range(0.15..0.85)) * cw;
+            let y = (row as f64 + rng.gen_range(0.15..0.85)) * ch;
+            let r = cw.max(ch) * rng.gen_range(0.6..0.9);
+            let color = colors[(row * cols + col + offset) % colors.len()];
+            nodes.push(
+                Shape::path(blob(rng, (x, y), r, 0.3))
+                    .fill(color)
+                    .opacity(rng.gen_range(0.75..1.0))
+                    .blur(r * 0.5)
+                    .into(),
+            );
+        }
+    }
+
+    // A few pale highlights lift the mesh off the base
+    for _ in 0..c.count(rng.gen_range(1..4)) {
+        let (x, y) = (rng.gen_range(0.0..c.w), rng.gen_range(0.0..c.h));
+        let r = c.unit * rng.gen_range(0.15..0.3);
+        let color = colors[rng.gen_range(0..colors.len())].mix(WHITE, 0.35);
+        nodes.push(
+            Shape::path(blob(rng, (x, y), r, 0.25))
+                .fill(color)
+                .opacity(rng.gen_range(0.35..0.6))
+                .blur(r * 0.6)
+                .into(),
+        );
+    }
+    nodes
+}
+
+/// Night sky, faint stars and dim glows, one to three curtains, and
+/// sometimes a dark ridge along the bottom.
+fn curtains(rng: &mut impl Rng, palette: &Palette, c: &Canvas) -> Vec<Node> {
+    let bg = Color::hex(palette.bg);
+    let stops = rng.gen_range(2..4);
+    let colors = pick_ramp(rng, palette, stops);
+    let mut nodes = vec![Shape::rect(0.0, 0.0, c.w, c.h)
+        .paint(linear(
+            (0.0, 0.0),
+            (0.0, 1.0),
+            &[(0.0, bg.mix(BLACK, 0.55), 1.0), (1.0, bg.mix(colors[0], 0.2), 1.0)],
+        ))
+        .into()];
+
+    for _ in 0..c.scatter(160) {
+        let (x, y) = (rng.gen_range(0.0..c.w), rng.gen_range(0.0..c.h * 0.8));
+        let r = c.px(rng.gen_range(0.5..1.5));
+        nodes.push(Shape::circle(x, y, r).fill(WHITE).opacity(rng.gen_range(0.2..0.8)).into());
+    }
+    for color in colors.iter().take(2) {
+        let (x, y) = (rng.gen_range(0.0..c.w), c.h * rng.gen_range(0.3..0.7));
+        let r = c.unit * rng.gen_range(0.4..0.7);
+        nodes.push(Shape::path(blob(rng, (x, y), r, 0.3)).fill(*color).opacity(0.25).blur(r * 0.6).into());
+    }
+
+    for i in 0..rng.gen_range(1..4) {
+        let low = colors[i % colors.len()];
+        let high = colors[(i + 1) % colors.len()];
+        nodes.extend(curtain(rng, c, low, high));
+    }
+
+    if rng.gen_bool(0.5) {
+        nodes.push(ridge(rng, c, bg.mix(BLACK, 0.6)));
+    }
+    nodes
+}
+
+/// One curtain: thin slices standing on a wavy lower edge, fading from
+/// `low` (brightened) at the edge to `high` at the top, with a soft glow of
+/// the whole curtain behind them.
+fn curtain(rng: &mut impl Rng, c: &Canvas, low: Color, high: Color) -> Vec<Node> {
+    let seed = rng.gen::<u64>();
+    let base = c.h * rng.gen_range(0.45..0.75);
+    let amplitude = c.unit * rng.gen_range(0.04..0.12);
+    let waves = rng.gen_range(0.8..2.2) / c.widths();
+    let (phase, ripple) = (rng.gen_range(0.0..PI * 2.0), rng.gen_range(0.0..PI * 2.0));
+    let tall = c.unit * rng.gen_range(0.25..0.55);
+    let edge = |x: f64| {
+        let t = x / c.w * PI * 2.0 * waves;
+        base + amplitude * ((t + phase).sin() + 0.4 * (t * 2.3 + ripple).sin())
+    };
+    // Heights and brightness drift along the curtain, brightness on a finer
+    // scale so it breaks into rays; fbm is stretched off its middle
+    let drift = |seed: u64, x: f64, scale: f64| {
+        let v = noise::fbm(seed, x / (c.unit * scale), 0.5, 3);
+        ((v - 0.5) * 2.2 + 0.5).clamp(0.0, 1.0)
+    };
+    let top = |x: f64| (x, edge(x) - tall * (0.35 + 0.65 * drift(seed, x, 0.15)));
+
+    let (start, end) = (-c.w * 0.1, c.w * 1.1);
+    let xs: Vec<f64> = (0..=GLOW_STEPS).map(|i| start + (end - start) * i as f64 / GLOW_STEPS as f64).collect();
+    let glow: Vec<Point> = xs.iter().map(|&x| (x, edge(x))).chain(xs.iter().rev().map(|&x| top(x))).collect();
+    let fade = |opacity: f64| {
+        linear(
+            (0.0, 1.0),
+            (0.0, 0.0),
+            &[
+                (0.0, low.mix(WHITE, 0.35), 0.0),
+                (0.06, low.mix(WHITE, 0.25), opacity),
+                (0.35, low, opacity * 0.6),
+                (1.0, high, 0.0),
+            ],
+        )
+    };
+    let mut nodes = vec![Shape::polygon(glow).paint(fade(0.35)).blur(c.unit * 0.04).into()];
+
+    let slice = c.unit * rng.gen_range(0.008..0.016);
+    let count = ((end - start) / slice).ceil() as usize;
+    for i in 0..count {
+        let (xa, xb) = (start + i as f64 * slice, start + (i + 1) as f64 * slice);
+        let (ta, tb) = (top(xa), top(xb));
+        let brightness = 0.3 + 0.7 * drift(seed ^ 1, (xa + xb) / 2.0, 0.04);
+        nodes.push(
+            Shape::polygon(vec![(xa, edge(xa)), (xb, edge(xb)), tb, ta])
+                .paint(fade(brightness))
+                .blur(slice * 0.8)
+                .into(),
+        );
+    }
+    nodes
+}
+
+/// Low hills against the sky, from layered value noise.
+fn ridge(rng: &mut impl Rng, c: &Canvas, color: Color) -> Node {
+    let seed = rng.gen::<u64>();
+    let base = c.h * rng.gen_range(0.86..0.93);
+    let steps = 64;
+    let mut path = vec![Segment::MoveTo(0.0, c.h)];
+    for i in 0..=steps {
+        let x = c.w * i as f64 / steps as f64;
+        let y = base - c.unit * 0.08 * noise::fbm(seed, x / (c.unit * 0.35), 0.0, 4);
+        path.push(Segment::LineTo(x, y));
+    }
+    path.extend([Segment::LineTo(c.w, c.h), Segment::Close]);
+    Shape::path(path).fill(color).into()
+}
+
+/// A closed, smooth, irregular outline around `center`: radii vary by up to
+/// `wobble`, joined by a Catmull-Rom spline.
+fn blob(rng: &mut impl Rng, (cx, cy): Point, r: f64, wobble: f64) -> Vec<Segment> {
+    let n = rng.gen_range(5..8);
+    let turn = rng.gen_range(0.0..PI * 2.0);
+    let points: Vec<Point> = (0..n)
+        .map(|i| {
+            let a = turn + PI * 2.0 * i as f64 / n as f64;
+            let rr = r * (1.0 + rng.gen_range(-wobble..wobble));
+            (cx + a.cos() * rr, cy + a.sin() * rr)
+        })
+        .collect();
+    let mut path = vec![Segment::MoveTo(points[0].0, points[0].1)];
+    for i in 0..n {
+        let (p0, p1) = (points[(i + n - 1) % n], points[i]);
+        let (p2, p3) = (points[(i + 1) % n], points[(i + 2) % n]);
+        path.push(Segment::CubicTo(
+            p1.0 + (p2.0 - p0.0) / 6.0,
+            p1.1 + (p2.1 - p0.1) / 6.0,
+            p2.0 - (p3.0 - p1.0) / 6.0,
+            p2.1 - (p3.1 - p1.1) / 6.0,
+            p2.0,
+            p2.1,
+        ));
+    }
+    path.push(Segment::Close);
+    path
+}
+
+fn linear((x1, y1): Point, (x2, y2): Point, stops: &[(f64, Color, f64)]) -> Paint {
+    Paint::Linear(LinearGradient {
+        x1,
+        y1,
+        x2,
+        y2,
+        stops: stops.iter().map(|&(o, color, a)| GradientStop::new(o, color, a)).collect(),
+    })
+}
@@ -0,0 +1,253 @@
+//! Deep space: fractal-noise nebulae over soft glows, starfields at three
+//! depths, and now and then a shaded planet, sometimes ringed.
+//!
+//! Nebula clouds and planet surfaces are noise textures (`Paint::Noise`),
+//! which the renderer evaluates per pixel alongside the vector shapes, so
+//! they stay sharp and seamless at any output size.
+
+use std::f64::consts::PI;
+
+use rand::Rng;
+
+use super::scene::{
+    Color, GradientStop, Node, NoiseTexture, Paint, RadialGradient, Segment, Shape, Transform,
+};
+use super::{pick, pick_ramp, Canvas, Palette};
+
+const WHITE: Color = Color { r: 255, g: 255, b: 255 };
+const BLACK: Color = Color { r: 0, g: 0, b: 0 };
+
+pub(super) fn generate(rng: &mut impl Rng, palette: &Palette, c: &Canvas) -> Vec<Node> {
+    let bg = Color::hex(palette.bg);
+    let mut nodes = vec![Shape::rect(0.0, 0.0, c.w, c.h)
+        .paint(radial(0.5, 0.5, 0.75, &[(0.0, bg, 1.0), (1.0, bg.mix(BLACK, 0.6), 1.0)]))
+        .into()];
+
+    // Soft glows give the clouds somewhere to gather
+    let stops = rng.gen_range(2..4);
+    let colors = pick_ramp(rng, palette, stops);
+    for color in colors.iter().cycle().take(c.count(rng.gen_range(2..5))) {
+        let (x, y) = (rng.gen_range(0.0..c.w), rng.gen_range(0.0..c.h));
+        let r = c.unit * rng.gen_range(0.4..0.9);
+        nodes.push(
+            Shape::ellipse(x, y, r * rng.gen_range(1.0..1.8), r)
+                .paint(radial(0.5, 0.5, 0.5, &[(0.0, *color, 0.35), (1.0, *color, 0.0)]))
+                .into(),
+        );
+    }
+
+    for (i, &color) in colors.iter().enumerate() {
+        let threshold = rng.gen_range(0.54..0.62);
+        nodes.push(
+            Shape::rect(0.0, 0.0, c.w, c.h)
+                .paint(noise(
+                    rng.gen(),
+                    c.unit * rng.gen_range(0.3..0.7) / (i as f64 * 0.3 + 1.0),
+                    rng.gen_range(0.05..0.2),
+                    &[
+                        (threshold, color, 0.0),
+                        (threshold + 0.1, color, 0.3),
+                        (threshold + 0.25, color.mix(WHITE, 0.35), 0.6),
+                    ],
+                ))
+                .into(),
+        );
+    }
+    // Dark dust lanes cut through the glow
+    let lanes = rng.gen_range(0.55..0.65);
+    nodes.push(
+        Shape::rect(0.0, 0.0, c.w, c.h)
+            .paint(noise(
+                rng.gen(),
+                c.unit * rng.gen_range(0.2..0.4),
+                0.1,
+                &[(lanes, bg.mix(BLACK, 0.7), 0.0), (lanes + 0.15, bg.mix(BLACK, 0.7), 0.45)],
+            ))
+            .into(),
+    );
+
+    nodes.extend(stars(rng, palette, c));
+
+    if rng.gen_bool(0.55) {
+        let r = c.unit * rng.gen_range(0.08..0.3);
+        // Big planets sit towards an edge, leaving the nebula room
+        let (x, y) = if r > c.unit * 0.18 {
+            let side = if rng.gen_bool(0.5) { 0.12 } else { 0.88 };
+            (c.w * side, c.h * rng.gen_range(0.6..0.95))
+        } else {
+            (c.w * rng.gen_range(0.15..0.85), c.h * rng.gen_range(0.2..0.8))
+        };
+        let light = rng.gen_range(0.0..PI * 2.0);
+        let ringed = rng.gen_bool(0.45);
+        nodes.extend(planet(rng, palette, c, (x, y, r), light, ringed));
+
+        if rng.gen_bool(0.4) {
+            let moon_r = r * rng.gen_range(0.12..0.25);
+            let a = rng.gen_range(0.0..PI * 2.0);
+            let d = r * rng.gen_range(2.2..3.2);
+            let moon = (x + a.cos() * d, y + a.sin() * d, moon_r);
+            nodes.extend(planet(rng, palette, c, moon, light, false));
+        }
+    }
+    nodes
+}
+
+fn radial(cx: f64, cy: f64, r: f64, stops: &[(f64, Color, f64)]) -> Paint {
+    Paint::Radial(RadialGradient {
+        cx,
+        cy,
+        r,
+        stops: stops.iter().map(|&(o, color, a)| GradientStop::new(o, color, a)).collect(),
+    })
+}
+
+fn noise(seed: u32, scale: f64, warp: f64, stops: &[(f64, Color, f64)]) -> Paint {
+    Paint::Noise(NoiseTexture {
+        seed,
+        scale,
+        octaves: 5,
+        warp,
+        stops: stops.iter().map(|&(o, color, a)| GradientStop::new(o, color, a)).collect(),
+    })
+}
+
+/// Far, mid and near layers: many faint pinpoints, fewer brighter stars, and
+/// a handful of bright ones with halos and diffraction spikes. Sizes skew
+/// small within each layer; colors are white tinted towards the palette.
+fn stars(rng: &mut impl Rng, palette: &Palette, c: &Canvas) -> Vec<Node> {
+    let mut nodes = Vec::new();
+    let layers = [(900, 0.4, 0.9, 0.25..0.6), (220, 0.8, 1.7, 0.5..0.85), (25, 1.6, 3.0, 0.85..1.0)];
+    for (depth, (count, min, max, brightness)) in layers.into_iter().enumerate() {
+        for _ in 0..c.scatter(count) {
+            let (x, y) = (rng.gen_range(0.0..c.w), rng.gen_range(0.0..c.h));
+            let r = c.px(min + (max - min) * rng.gen_range(0.0f64..1.0).powi(3));
+            let tint = WHITE.mix(pick(rng, palette), rng.gen_range(0.0..0.35));
+            let opacity = rng.gen_range(brightness.clone());
+
+            if depth == 2 {
+                nodes.push(
+                    Shape::circle(x, y, r * 7.0)
+                        .paint(radial(0.5, 0.5, 0.5, &[(0.0, tint, 0.45 * opacity), (1.0, tint, 0.0)]))
+                        .into(),
+                );
+                if rng.gen_bool(0.4) {
+                    let (len, width) = (r * rng.gen_range(6.0..12.0), c.px(0.8));
+                    for (dx, dy) in [(len, 0.0), (0.0, len)] {
+                        nodes.push(
+                            Shape::line(x - dx, y - dy, x + dx, y + dy)
+                                .stroke(tint, width)
+                                .round_cap()
+                                .opacity(0.35 * opacity)
+                                .into(),
+                        );
+                    }
+                }
+            }
+            nodes.push(Shape::circle(x, y, r).fill(tint).opacity(opacity).into());
+        }
+    }
+    nodes
+}
+
+/// A planet lit from `light` (radians): noise surface, sometimes squashed
+/// into gas-giant bands, then atmosphere, terminator shading and rings.
+fn planet(
+    rng: &mut impl Rng,
+    palette: &Palette,
+    c: &Canvas,
+    (x, y, r): (f64, f64, f64),
+    light: f64,
+    ringed: bool,
+) -> Vec<Node> {
+    let stops = rng.gen_range(2..4);
+    let colors = pick_ramp(rng, palette, stops);
+    let tilt = rng.gen_range(-25.0..25.0);
+    let mut nodes = Vec::new();
+
+    // Atmosphere: a thin glow just outside the limb
+    let glow = colors[0].mix(WHITE, 0.3);
+    nodes.push(
+        Shape::circle(x, y, r * 1.12)
+            .paint(radial(0.5, 0.5, 0.5, &[(0.86, glow, 0.0), (0.9, glow, 0.45), (1.0, glow, 0.0)]))
+            .into(),
+    );
+
+    let ring = ringed.then(|| Ring {
+        rx: r * rng.gen_range(1.6..2.3),
+        flatten: rng.gen_range(0.12..0.32),
+        bands: rng.gen_range(3..6),
+        color: pick(rng, palette).mix(WHITE, 0.2),
+    });
+    if let Some(ring) = &ring {
+        nodes.extend(ring.half(c, (x, y), tilt, false));
+    }
+
+    // Gas giants stretch their noise along the bands by drawing a taller
+    // ellipse squashed back into a circle; rocky worlds use it as is
+    let squash = if rng.gen_bool(0.5) { rng.gen_range(0.12..0.3) } else { 1.0 };
+    let surface: Vec<(f64, Color, f64)> = colors
+        .iter()
+        .enumerate()
+        .map(|(i, &color)| (0.3 + 0.4 * i as f64 / (colors.len() - 1) as f64, color, 1.0))
+        .collect();
+    let surface = noise(rng.gen(), r * rng.gen_range(0.25..0.6), rng.gen_range(0.0..0.2), &surface);
+    nodes.push(
+        Shape::ellipse(x, y / squash, r, r / squash)
+            .paint(surface)
+            .transform(Transform::rotate(tilt, x, y).then(Transform::scale(1.0, squash)))
+            .into(),
+    );
+
+    // Lit side towards `light`, night side falling off to near black
+    let (lx, ly) = (0.5 + light.cos() * 0.3, 0.5 + light.sin() * 0.3);
+    nodes.push(
+        Shape::circle(x, y, r)
+            .paint(radial(
+                lx,
+                ly,
+                0.9,
+                &[(0.0, WHITE, 0.18), (0.35, BLACK, 0.0), (0.75, BLACK, 0.75), (1.0, BLACK, 0.92)],
+            ))
+            .into(),
+    );
+
+    if let Some(ring) = &ring {
+        nodes.extend(ring.half(c, (x, y), tilt, true));
+    }
+    nodes
+}
+
+struct Ring {
+    rx: f64,
+    /// Minor to major axis ratio: how edge-on the ring is seen.
+    flatten: f64,
+    bands: usize,
+    color: Color,
+}
+
+impl Ring {
+    /// The far (upper) or near (lower) half of the ring's bands; the planet
+    /// is drawn between the two.
+    fn half(&self, c: &Canvas, (x, y): (f64, f64), tilt: f64, near: bool) -> Vec<Node> {
+        const K: f64 = 0.5523;
+        let sign = if near { 1.0 } else { -1.0 };
+        let band_width = self.rx * 0.45 / self.bands as f64;
+        (0..self.bands)
+            .map(|i| {
+                let rx = self.rx * 0.7 + band_width * (i as f64 + 0.5);
+                let ry = rx * self.flatten * sign;
+                let path = vec![
+                    Segment::MoveTo(x - rx, y),
+                    Segment::CubicTo(x - rx, y + ry * K, x - rx * K, y + ry, x, y + ry),
+                    Segment::CubicTo(x + rx * K, y + ry, x + rx, y + ry * K, x + rx, y),
+                ];
+                let opacity = 0.35 + 0.5 * ((i * 7 + 3) % 5) as f64 / 4.0;
+                Shape::path(path)
+                    .stroke(self.color, (band_width * 0.85).max(c.px(1.0)))
+                    .opacity(opacity * if near { 1.0 } else { 0.8 })
+                    .transform(Transform::rotate(tilt, x, y))
+                    .into()
+            })
+            .collect()
+    }
+}
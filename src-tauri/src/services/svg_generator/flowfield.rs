@@ -0,0 +1,193 @@
+//! Flow-field art: evenly spaced streamlines traced through seeded Perlin
+//! noise, after Jobard and Lefer. New lines start one separation away from
+//! existing ones and stop as they crowd a neighbor, so the field fills evenly.
+
+use std::f64::consts::PI;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use super::scene::{Node, Segment, Shape};
+use super::{pick_ramp, ramp, Canvas, Palette};
+use crate::services::noise;
+
+/// Cap on steps traced in each direction, so lines circling a vortex end.
+const MAX_STEPS: usize = 800;
+
+/// Steps along its own line a point ignores in distance tests: four
+/// separations at the half-separation step.
+const SELF_STEPS: i64 = 8;
+
+pub(super) fn generate(rng: &mut impl Rng, palette: &Palette, c: &Canvas) -> Vec<Node> {
+    let seed = rng.gen::<u64>();
+    let scale = c.unit * rng.gen_range(0.3..0.8);
+    let turns = rng.gen_range(0.5..1.5);
+    let base = rng.gen_range(0.0..PI * 2.0);
+    let angle = move |x: f64, y: f64| {
+        base + noise::perlin_fbm(seed, x / scale, y / scale, 3) * PI * turns
+    };
+
+    let sep = c.unit * rng.gen_range(0.008..0.02) / c.density.sqrt();
+    let step = sep * 0.5;
+    let mut grid = Spacing::new(c, sep);
+
+    // Seed from a shuffled jittered lattice so empty regions get filled too
+    let mut seeds: Vec<(f64, f64)> = Vec::new();
+    let lattice = sep * 4.0;
+    for row in 0..(c.h / lattice).ceil() as usize {
+        for col in 0..(c.w / lattice).ceil() as usize {
+            seeds.push((
+                (col as f64 + rng.gen_range(0.0..1.0)) * lattice,
+                (row as f64 + rng.gen_range(0.0..1.0)) * lattice,
+            ));
+        }
+    }
+    seeds.shuffle(rng);
+
+    let mut lines: Vec<Vec<(f64, f64)>> = Vec::new();
+    let mut queue = Vec::new();
+    while let Some(start) = queue.pop().or_else(|| seeds.pop()) {
+        let inside = (0.0..c.w).contains(&start.0) && (0.0..c.h).contains(&start.1);
+        if !inside || !grid.is_free(start, sep, None) {
+            continue;
+        }
+        let id = lines.len();
+        let line = trace(start, id, &angle, step, &mut grid, c);
+        if (line.len() as f64) * step < sep * 4.0 {
+            grid.remove(&line, id);
+            lines.push(Vec::new());
+            continue;
+        }
+        // Candidates for neighbors one separation to either side
+        for pair in line.windows(2).step_by(3) {
+            let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+            let len = (x1 - x0).hypot(y1 - y0).max(f64::EPSILON);
+            let (nx, ny) = (-(y1 - y0) / len * sep, (x1 - x0) / len * sep);
+            queue.push((x0 + nx, y0 + ny));
+            queue.push((x0 - nx, y0 - ny));
+        }
+        lines.push(line);
+    }
+
+    let stops = rng.gen_range(2..4);
+    let colors = pick_ramp(rng, palette, stops);
+    let by_angle = rng.gen_bool(0.5);
+    let width = sep * rng.gen_range(0.2..0.55);
+    let color_seed = rng.gen::<u64>();
+
+    let mut nodes = Vec::new();
+    for line in lines.into_iter().filter(|l| !l.is_empty()) {
+        let (x, y) = line[line.len() / 2];
+        let t = if by_angle {
+            (angle(x, y) - base).rem_euclid(PI) / PI
+        } else {
+            noise::perlin(color_seed, x / c.unit * 1.5, y / c.unit * 1.5) * 0.6 + 0.5
+        };
+        let mut path = vec![Segment::MoveTo(line[0].0, line[0].1)];
+        path.extend(line[1..].iter().map(|&(x, y)| Segment::LineTo(x, y)));
+        nodes.push(
+            Shape::path(path)
+                .stroke(ramp(&colors, t), width * rng.gen_range(0.7..1.3))
+                .round_cap()
+                .opacity(rng.gen_range(0.75..0.95))
+                .into(),
+        );
+    }
+    nodes
+}
+
+/// Trace both ways from `start` with midpoint steps, registering points as it
+/// goes, until the line leaves the canvas or comes within half a separation of
+/// another line.
+fn trace(
+    start: (f64, f64),
+    id: usize,
+    angle: &impl Fn(f64, f64) -> f64,
+    step: f64,
+    grid: &mut Spacing,
+    c: &Canvas,
+) -> Vec<(f64, f64)> {
+    let test = grid.sep * 0.5;
+    let mut halves = [Vec::new(), Vec::new()];
+    grid.insert(start, id, 0);
+
+    for (half, direction) in halves.iter_mut().zip([1.0, -1.0]) {
+        let (mut x, mut y) = start;
+        for i in 1..=MAX_STEPS {
+            let a = angle(x, y);
+            let (mx, my) = (x + a.cos() * step * 0.5 * direction, y + a.sin() * step * 0.5 * direction);
+            let a = angle(mx, my);
+            let (nx, ny) = (x + a.cos() * step * direction, y + a.sin() * step * direction);
+            let index = i as i64 * direction as i64;
+            if nx < -step || ny < -step || nx > c.w + step || ny > c.h + step {
+                break;
+            }
+            if !grid.is_free((nx, ny), test, Some((id, index))) {
+                break;
+            }
+            grid.insert((nx, ny), id, index);
+            half.push((nx, ny));
+            (x, y) = (nx, ny);
+        }
+    }
+
+    let [forward, backward] = halves;
+    let mut line: Vec<(f64, f64)> = backward.into_iter().rev().collect();
+    line.push(start);
+    line.extend(forward);
+    line
+}
+
+/// Bucket grid of every traced point, for neighbor distance queries.
+struct Spacing {
+    sep: f64,
+    cols: usize,
+    rows: usize,
+    /// Point, owning line and signed step index along it.
+    cells: Vec<Vec<(f64, f64, usize, i64)>>,
+}
+
+impl Spacing {
+    fn new(c: &Canvas, sep: f64) -> Spacing {
+        let cols = (c.w / sep).ceil() as usize + 1;
+        let rows = (c.h / sep).ceil() as usize + 1;
+        Spacing { sep, cols, rows, cells: vec![Vec::new(); cols * rows] }
+    }
+
+    fn cell(&self, x: f64, y: f64) -> (usize, usize) {
+        let col = (x / self.sep).clamp(0.0, (self.cols - 1) as f64) as usize;
+        let row = (y / self.sep).clamp(0.0, (self.rows - 1) as f64) as usize;
+        (col, row)
+    }
+
+    fn insert(&mut self, (x, y): (f64, f64), id: usize, index: i64) {
+        let (col, row) = self.cell(x, y);
+        self.cells[col + row * self.cols].push((x, y, id, index));
+    }
+
+    fn remove(&mut self, line: &[(f64, f64)], id: usize) {
+        for &(x, y) in line {
+            let (col, row) = self.cell(x, y);
+            self.cells[col + row * self.cols].retain(|p| p.2 != id);
+        }
+    }
+
+    /// Whether no point lies within `d` (at most one separation) of (x, y).
+    /// Points of line `own` near `index` along it are the line itself and ignored.
+    fn is_free(&self, (x, y): (f64, f64), d: f64, own: Option<(usize, i64)>) -> bool {
+        let (col, row) = self.cell(x, y);
+        for r in row.saturating_sub(1)..(row + 2).min(self.rows) {
+            for k in col.saturating_sub(1)..(col + 2).min(self.cols) {
+                for &(px, py, id, index) in &self.cells[k + r * self.cols] {
+                    if own.is_some_and(|(own, at)| own == id && (index - at).abs() <= SELF_STEPS) {
+                        continue;
+                    }
+                    if (px - x).powi(2) + (py - y).powi(2) < d * d {
+                        return false;
+                    }
+                }
+            }
+        }
+        true
+    }
+}
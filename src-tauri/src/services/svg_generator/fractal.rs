@@ -0,0 +1,478 @@
+//! Fractals: seeded L-system plants, Sierpinski triangles and carpets, Koch
+//! snowflakes, and the Mandelbrot and Julia sets with smooth palette coloring.
+//!
+//! Every kind has a hard bound on its work (rewritten symbols, subdivision
+//! depth and hole count, escape iterations), so render time stays predictable
+//! at 4K whatever the seed picks.
+
+use std::f64::consts::PI;
+
+use rand::Rng;
+
+use super::scene::{Color, EscapeTime, GradientStop, Node, Paint, Segment, Shape};
+use super::{pick, pick_ramp, ramp, Canvas, Palette};
+
+type Point = (f64, f64);
+
+const WHITE: Color = Color { r: 255, g: 255, b: 255 };
+const BLACK: Color = Color { r: 0, g: 0, b: 0 };
+
+/// Cap on an L-system's rewritten string; one more iteration would usually
+/// multiply it several times over.
+const MAX_SYMBOLS: usize = 300_000;
+/// Cap on the holes cut into a whole canvas of Sierpinski tiles.
+const MAX_HOLES: usize = 60_000;
+
+pub(super) fn generate(rng: &mut impl Rng, palette: &Palette, c: &Canvas) -> Vec<Node> {
+    match rng.gen_range(0..10) {
+        0..=2 => plants(rng, palette, c),
+        3 => sierpinski_triangles(rng, palette, c),
+        4 => sierpinski_carpets(rng, palette, c),
+        5..=6 => snowflakes(rng, palette, c),
+        7..=8 => mandelbrot(rng, palette, c),
+        _ => julia(rng, palette, c),
+    }
+}
+
+/// A bracketed L-system. Symbols with several productions pick one at random
+/// each time they are rewritten, so every plant grown from a preset differs.
+struct LSystem {
+    axiom: &'static str,
+    rules: &'static [(char, &'static [&'static str])],
+    /// Turn angle in degrees.
+    angle: f64,
+    iterations: u32,
+}
+
+const LSYSTEMS: &[LSystem] = &[
+    // Prusinkiewicz & Lindenmayer's fractal plant, mirrored at random
+    LSystem {
+        axiom: "X",
+        rules: &[('X', &["F+[[X]-X]-F[-FX]+X", "F-[[X]+X]+F[+FX]-X"]), ('F', &["FF"])],
+        angle: 25.0,
+        iterations: 6,
+    },
+    // Stochastic branching
+    LSystem {
+        axiom: "F",
+        rules: &[('F', &["F[+F]F[-F]F", "F[+F]F", "F[-F]F"])],
+        angle: 25.7,
+        iterations: 5,
+    },
+    // Bush
+    LSystem { axiom: "F", rules: &[('F', &["FF+[+F-F-F]-[-F+F+F]"])], angle: 22.5, iterations: 4 },
+    // Sticks
+    LSystem {
+        axiom: "X",
+        rules: &[('X', &["F[+X]F[-X]+X", "F[-X]F[+X]-X"]), ('F', &["FF"])],
+        angle: 20.0,
+        iterations: 7,
+    },
+    // Weed
+    LSystem {
+        axiom: "X",
+        rules: &[('X', &["F-[[X]+X]+F[+FX]-X", "F[+X][-X]FX"]), ('F', &["FF"])],
+        angle: 22.5,
+        iterations: 6,
+    },
+];
+
+impl LSystem {
+    fn expand(&self, rng: &mut impl Rng) -> Vec<char> {
+        let mut symbols: Vec<char> = self.axiom.chars().collect();
+        for _ in 0..self.iterations {
+            let mut next = Vec::with_capacity(symbols.len() * 4);
+            for &symbol in &symbols {
+                match self.rules.iter().find(|(s, _)| *s == symbol) {
+                    Some((_, productions)) => {
+                        next.extend(productions[rng.gen_range(0..productions.len())].chars())
+                    }
+                    None => next.push(symbol),
+                }
+            }
+            if next.len() > MAX_SYMBOLS {
+                break;
+            }
+            symbols = next;
+        }
+        symbols
+    }
+}
+
+/// A walked L-system: segments tagged with their branch depth, and the tips
+/// where branches end.
+struct Growth {
+    segments: Vec<(Point, Point, usize)>,
+    tips: Vec<Point>,
+}
+
+/// Turtle interpretation from the origin, heading up with unit steps. Turns
+/// wobble a little so repeated branches do not look stamped.
+fn walk(rng: &mut impl Rng, symbols: &[char], angle: f64) -> Growth {
+    let angle = angle.to_radians();
+    let mut growth = Growth { segments: Vec::new(), tips: Vec::new() };
+    let (mut pos, mut heading, mut depth) = ((0.0, 0.0), -PI / 2.0, 0);
+    let mut stack = Vec::new();
+    for &symbol in symbols {
+        match symbol {
+            'F' => {
+                let next = (pos.0 + heading.cos(), pos.1 + heading.sin());
+                growth.segments.push((pos, next, depth));
+                pos = next;
+            }
+            '+' => heading += angle * rng.gen_range(0.85..1.15),
+            '-' => heading -= angle * rng.gen_range(0.85..1.15),
+            '[' => {
+                stack.push((pos, heading, depth));
+                depth += 1;
+            }
+            ']' => {
+                growth.tips.push(pos);
+                if let Some(saved) = stack.pop() {
+                    (pos, heading, depth) = saved;
+                }
+            }
+            _ => {}
+        }
+    }
+    growth
+}
+
+/// A row of L-system plants growing from the bottom edge, stems thinning and
+/// shifting color with branch depth, tips dotted with blossoms.
+fn plants(rng: &mut impl Rng, palette: &Palette, c: &Canvas) -> Vec<Node> {
+    let system = &LSYSTEMS[rng.gen_range(0..LSYSTEMS.len())];
+    let colors = pick_ramp(rng, palette, 3);
+    let blossom = pick(rng, palette).mix(WHITE, 0.2);
+    let count = c.count(((c.widths() * rng.gen_range(1.0..4.0)).round() as usize).max(1));
+    let mut nodes = Vec::new();
+
+    for i in 0..count {
+        let symbols = system.expand(rng);
+        let growth = walk(rng, &symbols, system.angle);
+        let (x0, y0, x1, y1) = growth.segments.iter().fold(
+            (f64::MAX, f64::MAX, f64::MIN, f64::MIN),
+            |(x0, y0, x1, y1), &((ax, ay), (bx, by), _)| {
+                (x0.min(ax).min(bx), y0.min(ay).min(by), x1.max(ax).max(bx), y1.max(ay).max(by))
+            },
+        );
+        if growth.segments.is_empty() {
+            continue;
+        }
+        // Fit the plant's height (and keep its width within its share of the
+        // row), rooted at the bottom edge
+        let slot = c.w / count as f64;
+        let height = c.h * rng.gen_range(0.45..0.85);
+        let scale = (height / (y1 - y0)).min(slot * 1.6 / (x1 - x0).max(1e-9));
+        let root = (slot * (i as f64 + rng.gen_range(0.35..0.65)), c.h + c.px(2.0));
+        let place = |(x, y): Point| (root.0 + x * scale, root.1 + y * scale);
+
+        let max_depth = growth.segments.iter().map(|s| s.2).max().unwrap_or(0);
+        let trunk = c.px(rng.gen_range(4.0..9.0)) * (scale / c.px(4.0)).clamp(0.5, 1.5);
+        let mut paths: Vec<Vec<Segment>> = vec![Vec::new(); max_depth + 1];
+        for &(a, b, depth) in &growth.segments {
+            let (a, b) = (place(a), place(b));
+            paths[depth].extend([Segment::MoveTo(a.0, a.1), Segment::LineTo(b.0, b.1)]);
+        }
+        for (depth, path) in paths.into_iter().enumerate().filter(|(_, p)| !p.is_empty()) {
+            let t = depth as f64 / max_depth.max(1) as f64;
+            let width = (trunk * 0.68f64.powi(depth as i32)).max(c.px(0.8));
+            nodes.push(Shape::path(path).stroke(ramp(&colors, t), width).round_cap().opacity(0.92).into());
+        }
+
+        // Blossoms at a share of the tips, one path for the lot
+        let r = c.px(rng.gen_range(2.0..4.5));
+        let share = rng.gen_range(0.1..0.5);
+        let mut dots = Vec::new();
+        for &tip in &growth.tips {
+            if rng.gen_bool(share) {
+                let (x, y) = place(tip);
+                dots.extend(circle_segments(x, y, r));
+            }
+        }
+        if !dots.is_empty() {
+            nodes.push(Shape::path(dots).fill(blossom).opacity(0.8).into());
+        }
+    }
+    nodes
+}
+
+/// A circle as four cubic quarter arcs, for batching many into one path.
+fn circle_segments(x: f64, y: f64, r: f64) -> [Segment; 6] {
+    const K: f64 = 0.5523;
+    let k = r * K;
+    [
+        Segment::MoveTo(x + r, y),
+        Segment::CubicTo(x + r, y + k, x + k, y + r, x, y + r),
+        Segment::CubicTo(x - k, y + r, x - r, y + k, x - r, y),
+        Segment::CubicTo(x - r, y - k, x - k, y - r, x, y - r),
+        Segment::CubicTo(x + k, y - r, x + r, y - k, x + r, y),
+        Segment::Close,
+    ]
+}
+
+fn polygon_segments(points: &[Point]) -> Vec<Segment> {
+    let mut path = vec![Segment::MoveTo(points[0].0, points[0].1)];
+    path.extend(points[1..].iter().map(|&(x, y)| Segment::LineTo(x, y)));
+    path.push(Segment::Close);
+    path
+}
+
+/// Subdivision depth whose smallest holes stay a few pixels wide, and whose
+/// total hole count over `tiles` tiles stays within [`MAX_HOLES`].
+fn subdivision_depth(c: &Canvas, size: f64, divisor: f64, holes_per_level: f64, tiles: usize) -> usize {
+    let mut depth = 0;
+    let mut holes = 0.0;
+    let mut level = 1.0;
+    while size / divisor.powi(depth as i32 + 1) > c.px(3.0) {
+        holes += level * tiles as f64;
+        if holes > MAX_HOLES as f64 || depth >= 8 {
+            break;
+        }
+        level *= holes_per_level;
+        depth += 1;
+    }
+    depth
+}
+
+/// A lattice of up and down triangles over the canvas, each a Sierpinski
+/// triangle. Holes are cut in colors running along the ramp by depth.
+fn sierpinski_triangles(rng: &mut impl Rng, palette: &Palette, c: &Canvas) -> Vec<Node> {
+    let stops = rng.gen_range(3..5);
+    let colors = pick_ramp(rng, palette, stops);
+    let side = c.unit * rng.gen_range(0.45..1.0);
+    let h = side * 3f64.sqrt() / 2.0;
+
+    let mut tiles = Vec::new();
+    for row in 0..=(c.h / h).ceil() as i64 {
+        let (top, bottom) = (row as f64 * h, (row + 1) as f64 * h);
+        let shift = if row % 2 == 0 { 0.0 } else { side / 2.0 };
+        for col in -1..=(c.w / side).ceil() as i64 {
+            let x = col as f64 * side + shift;
+            tiles.push([(x, bottom), (x + side / 2.0, top), (x + side, bottom)]);
+            tiles.push([(x + side / 2.0, top), (x + side * 1.5, top), (x + side, bottom)]);
+        }
+    }
+    let depth = subdivision_depth(c, side, 2.0, 3.0, tiles.len());
+
+    let mut base = Vec::new();
+    let mut holes: Vec<Vec<Segment>> = vec![Vec::new(); depth];
+    for tile in &tiles {
+        base.extend(polygon_segments(tile));
+        let mut level = vec![*tile];
+        for path in holes.iter_mut() {
+            let mut next = Vec::with_capacity(level.len() * 3);
+            for [a, b, c] in level {
+                let (ab, bc, ca) = (mid(a, b), mid(b, c), mid(c, a));
+                path.extend(polygon_segments(&[ab, bc, ca]));
+                next.extend([[a, ab, ca], [ab, b, bc], [ca, bc, c]]);
+            }
+            level = next;
+        }
+    }
+
+    let mut nodes = vec![Shape::path(base).fill(colors[0]).into()];
+    let bg = Color::hex(palette.bg);
+    for (d, path) in holes.into_iter().enumerate() {
+        let t = d as f64 / depth.max(1) as f64;
+        nodes.push(Shape::path(path).fill(bg.mix(ramp(&colors[1..], t), 0.25 + 0.5 * t)).into());
+    }
+    nodes
+}
+
+/// Sierpinski carpets tiled over the canvas, holes colored by depth.
+fn sierpinski_carpets(rng: &mut impl Rng, palette: &Palette, c: &Canvas) -> Vec<Node> {
+    let stops = rng.gen_range(3..5);
+    let colors = pick_ramp(rng, palette, stops);
+    // Whole tiles across the short side, centered on the long one
+    let per_unit = rng.gen_range(1..4);
+    let size = c.unit / per_unit as f64;
+    let (cols, rows) = ((c.w / size).ceil() as i64, (c.h / size).ceil() as i64);
+    let (ox, oy) = ((c.w - cols as f64 * size) / 2.0, (c.h - rows as f64 * size) / 2.0);
+    let depth = subdivision_depth(c, size, 3.0, 8.0, (cols * rows) as usize);
+
+    let mut base = Vec::new();
+    let mut holes: Vec<Vec<Segment>> = vec![Vec::new(); depth];
+    for row in 0..rows {
+        for col in 0..cols {
+            let (x, y) = (ox + col as f64 * size, oy + row as f64 * size);
+            // Inset a hair so neighbouring tiles read as separate carpets
+            let inset = c.px(1.0);
+            base.extend(polygon_segments(&square(x + inset, y + inset, size - inset * 2.0)));
+            let mut level = vec![(x, y)];
+            let mut s = size;
+            for path in holes.iter_mut() {
+                let third = s / 3.0;
+                let mut next = Vec::with_capacity(level.len() * 8);
+                for (x, y) in level {
+                    path.extend(polygon_segments(&square(x + third, y + third, third)));
+                    for k in (0..9).filter(|&k| k != 4) {
+                        next.push((x + (k % 3) as f64 * third, y + (k / 3) as f64 * third));
+                    }
+                }
+                level = next;
+                s = third;
+            }
+        }
+    }
+
+    let mut nodes = vec![Shape::path(base).fill(colors[0]).into()];
+    let bg = Color::hex(palette.bg);
+    for (d, path) in holes.into_iter().enumerate() {
+        let t = d as f64 / depth.max(1) as f64;
+        nodes.push(Shape::path(path).fill(bg.mix(ramp(&colors[1..], t), 0.2 + 0.6 * t)).into());
+    }
+    nodes
+}
+
+fn square(x: f64, y: f64, s: f64) -> [Point; 4] {
+    [(x, y), (x + s, y), (x + s, y + s), (x, y + s)]
+}
+
+fn mid(a: Point, b: Point) -> Point {
+    ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0)
+}
+
+/// Nested Koch snowflakes, each smaller and turned against the last, over a
+/// scattering of small ones.
+fn snowflakes(rng: &mut impl Rng, palette: &Palette, c: &Canvas) -> Vec<Node> {
+    let stops = rng.gen_range(3..5);
+    let colors = pick_ramp(rng, palette, stops);
+    let mut nodes = Vec::new();
+
+    for _ in 0..c.scatter(rng.gen_range(6..16)) {
+        let (x, y) = (rng.gen_range(0.0..c.w), rng.gen_range(0.0..c.h));
+        let r = c.unit * rng.gen_range(0.03..0.09);
+        let flake = koch(x, y, r, rng.gen_range(0.0..PI), 3);
+        nodes.push(Shape::path(polygon_segments(&flake)).fill(pick(rng, palette)).opacity(0.25).into());
+    }
+
+    let (cx, cy) = (c.w / 2.0, c.h / 2.0);
+    let rings = rng.gen_range(4..8);
+    let shrink = rng.gen_range(0.68..0.82);
+    let twist = (60.0 / rings as f64).to_radians() * if rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+    let outline = Color::hex(palette.bg).mix(WHITE, 0.6);
+    let mut r = c.unit * rng.gen_range(0.4..0.5);
+    for i in 0..rings {
+        // Five levels hold 3 · 4⁵ points; deeper detail is below a pixel
+        let depth = if r > c.unit * 0.15 { 5 } else { 4 };
+        let flake = polygon_segments(&koch(cx, cy, r, twist * i as f64, depth));
+        let t = i as f64 / (rings - 1) as f64;
+        nodes.push(Shape::path(flake.clone()).fill(ramp(&colors, t)).opacity(0.9).into());
+        nodes.push(Shape::path(flake).stroke(outline, c.px(1.2)).opacity(0.5).into());
+        r *= shrink;
+    }
+    nodes
+}
+
+/// The outline of a Koch snowflake with circumradius `r`: each edge of a
+/// triangle replaced `depth` times by four edges a third as long.
+fn koch(cx: f64, cy: f64, r: f64, rotation: f64, depth: u32) -> Vec<Point> {
+    let mut points: Vec<Point> = (0..3)
+        .map(|i| {
+            let a = rotation - PI / 2.0 + i as f64 * 2.0 * PI / 3.0;
+            (cx + a.cos() * r, cy + a.sin() * r)
+        })
+        .collect();
+    for _ in 0..depth {
+        let mut next = Vec::with_capacity(points.len() * 4);
+        for i in 0..points.len() {
+            let (a, b) = (points[i], points[(i + 1) % points.len()]);
+            let (dx, dy) = ((b.0 - a.0) / 3.0, (b.1 - a.1) / 3.0);
+            let (p, q) = ((a.0 + dx, a.1 + dy), (a.0 + dx * 2.0, a.1 + dy * 2.0));
+            // The bump points outward, which for clockwise points is to the left
+            let (sin, cos) = (-PI / 3.0).sin_cos();
+            let tip = (p.0 + dx * cos - dy * sin, p.1 + dx * sin + dy * cos);
+            next.extend([a, p, tip, q]);
+        }
+        points = next;
+    }
+    points
+}
+
+/// Stops that run from the background through the ramp and back, so the
+/// escape-time color cycle repeats without a seam.
+fn cycle_stops(rng: &mut impl Rng, palette: &Palette) -> Vec<GradientStop> {
+    let stops = rng.gen_range(3..5);
+    let colors = pick_ramp(rng, palette, stops);
+    let dark = Color::hex(palette.bg).mix(BLACK, 0.3);
+    let mut cycle = vec![dark];
+    cycle.extend(colors);
+    cycle.push(WHITE.mix(cycle[cycle.len() - 1], 0.6));
+    cycle.push(dark);
+    let n = (cycle.len() - 1) as f64;
+    cycle.iter().enumerate().map(|(i, &color)| GradientStop::new(i as f64 / n, color, 1.0)).collect()
+}
+
+/// Views onto the Mandelbrot set's boundary: center and span of the short side.
+const MANDELBROT_VIEWS: &[((f64, f64), f64)] = &[
+    ((-0.65, 0.0), 2.6),
+    // Seahorse valley
+    ((-0.7453, 0.1127), 0.012),
+    ((-0.743, 0.126), 0.05),
+    // Elephant valley
+    ((0.2855, 0.0098), 0.025),
+    ((0.275, 0.007), 0.06),
+    // Spirals off the top bulb
+    ((-0.1592, 1.0317), 0.035),
+    ((-0.0452, 0.9868), 0.02),
+    // Minibrot on the needle
+    ((-1.7687, 0.0017), 0.06),
+    // Triple spiral
+    ((-0.0886, 0.6543), 0.01),
+];
+
+fn mandelbrot(rng: &mut impl Rng, palette: &Palette, c: &Canvas) -> Vec<Node> {
+    let (center, span) = MANDELBROT_VIEWS[rng.gen_range(0..MANDELBROT_VIEWS.len())];
+    let span = span * rng.gen_range(0.8..1.25);
+    // Deeper views need more iterations before their detail resolves
+    let depth = (2.6 / span).log10();
+    let (origin, step) = view(c, center, span);
+    let set = EscapeTime {
+        julia: None,
+        origin,
+        step,
+        iterations: (150.0 + 80.0 * depth).round() as u32,
+        cycle: rng.gen_range(12.0..30.0) * (1.0 + depth * 0.5),
+        stops: cycle_stops(rng, palette),
+        inside: Color::hex(palette.bg).mix(BLACK, 0.5),
+    };
+    vec![Shape::rect(0.0, 0.0, c.w, c.h).paint(Paint::EscapeTime(set)).into()]
+}
+
+/// Julia constants near the Mandelbrot boundary, where the sets are connected
+/// but intricate.
+const JULIA_CONSTANTS: &[(f64, f64)] = &[
+    (-0.8, 0.156),
+    (0.285, 0.01),
+    (-0.70176, -0.3842),
+    (0.355, 0.355),
+    (-0.835, -0.2321),
+    (-0.7269, 0.1889),
+    (-0.162, 1.04),
+    (0.3, 0.5),
+];
+
+fn julia(rng: &mut impl Rng, palette: &Palette, c: &Canvas) -> Vec<Node> {
+    let (re, im) = JULIA_CONSTANTS[rng.gen_range(0..JULIA_CONSTANTS.len())];
+    // A small nudge keeps the shape but varies the detail
+    let constant = [re + rng.gen_range(-0.002..0.002), im + rng.gen_range(-0.002..0.002)];
+    let (origin, step) = view(c, (0.0, 0.0), rng.gen_range(2.2..3.0));
+    let set = EscapeTime {
+        julia: Some(constant),
+        origin,
+        step,
+        iterations: 300,
+        cycle: rng.gen_range(10.0..25.0),
+        stops: cycle_stops(rng, palette),
+        inside: Color::hex(palette.bg).mix(BLACK, 0.5),
+    };
+    vec![Shape::rect(0.0, 0.0, c.w, c.h).paint(Paint::EscapeTime(set)).into()]
+}
+
+/// The complex number at the canvas origin and the step per pixel, for a
+/// view centered on `center` with `span` across the short side.
+fn view(c: &Canvas, center: Point, span: f64) -> ([f64; 2], f64) {
+    let step = span / c.unit;
+    ([center.0 - c.w / 2.0 * step, center.1 - c.h / 2.0 * step], step)
+}
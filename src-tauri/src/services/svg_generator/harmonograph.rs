@@ -0,0 +1,180 @@
+//! Harmonographs and spirographs: one continuous line traced by damped
+//! pendulums, or hypotrochoids rolled inside a ring.
+//!
+//! A harmonograph's pen follows two pendulums per axis, each a sine with its
+//! own frequency, phase and damping. Frequencies sit near small whole ratios
+//! and are detuned slightly, so the figure slowly precesses as it decays
+//! towards the center. Damping, ratios, detune and line weight are drawn from
+//! the seed; busier prompts damp less and trace more turns. Curves are
+//! sampled at tens of thousands of points and emitted as cubic Hermite
+//! segments, colored along their length by a palette gradient.
+
+use std::f64::consts::PI;
+
+use rand::Rng;
+
+use super::scene::{Color, Node, Segment, Shape};
+use super::{pick_ramp, ramp, Canvas, Palette};
+
+type Point = (f64, f64);
+
+/// Color runs each curve is split into; each run is one path.
+const BANDS: usize = 72;
+
+/// Samples per turn of a curve's fastest motion. Hermite segments spanning
+/// 15° of a turn are indistinguishable from the curve itself.
+const SAMPLES_PER_TURN: f64 = 24.0;
+
+/// Whole-number frequency ratios between a harmonograph's pendulums.
+const RATIOS: &[(f64, f64)] = &[(1.0, 1.0), (1.0, 2.0), (2.0, 3.0), (3.0, 4.0), (1.0, 3.0), (3.0, 5.0), (2.0, 5.0)];
+
+/// One swinging term: `amplitude · sin(frequency · t + phase) · e^(-damping · t)`,
+/// with `t` in turns of the base pendulum.
+struct Pendulum {
+    amplitude: f64,
+    frequency: f64,
+    phase: f64,
+    damping: f64,
+}
+
+impl Pendulum {
+    fn at(&self, t: f64) -> f64 {
+        self.amplitude * (2.0 * PI * self.frequency * t + self.phase).sin() * (-self.damping * t).exp()
+    }
+}
+
+pub(super) fn generate(rng: &mut impl Rng, palette: &Palette, c: &Canvas) -> Vec<Node> {
+    let stops = rng.gen_range(2..=4);
+    let colors = pick_ramp(rng, palette, stops);
+    let weight = c.dpx(rng.gen_range(0.6..1.8));
+    if rng.gen_bool(0.6) {
+        harmonograph(rng, c, &colors, weight)
+    } else {
+        spirograph(rng, c, &colors, weight)
+    }
+}
+
+/// Two pendulums per axis, with the paper sometimes turning slowly beneath
+/// the pen (a rotary harmonograph).
+fn harmonograph(rng: &mut impl Rng, c: &Canvas, colors: &[Color], weight: f64) -> Vec<Node> {
+    let (a, b) = RATIOS[rng.gen_range(0..RATIOS.len())];
+    let (a, b) = if rng.gen_bool(0.5) { (a, b) } else { (b, a) };
+    // Turns until the swing has decayed to a few percent
+    let turns = rng.gen_range(120.0..360.0) * c.density;
+    let damping = (1.0 / 0.03f64).ln() / turns;
+    let (sx, sy) = (rng.gen_range(0.5..0.85), rng.gen_range(0.5..0.85));
+    let mut pendulum = |frequency: f64, amplitude: f64| Pendulum {
+        amplitude,
+        frequency: frequency * (1.0 + rng.gen_range(-0.003..0.003)),
+        phase: rng.gen_range(0.0..2.0 * PI),
+        damping: damping * rng.gen_range(0.8..1.25),
+    };
+    // Each axis leads with one ratio and carries a little of the other
+    let x = [pendulum(a, sx), pendulum(b, 1.0 - sx)];
+    let y = [pendulum(b, sy), pendulum(a, 1.0 - sy)];
+    let spin = if rng.gen_bool(0.35) { rng.gen_range(-0.004..0.004) * 2.0 * PI } else { 0.0 };
+
+    let (rx, ry) = ((c.w * 0.44).min(c.unit * 0.6), c.unit * 0.44);
+    let (cx, cy) = (c.w / 2.0, c.h / 2.0);
+    let curve = |t: f64| {
+        let (px, py) = (x[0].at(t) + x[1].at(t), y[0].at(t) + y[1].at(t));
+        let (sin, cos) = (spin * t).sin_cos();
+        (cx + rx * (px * cos - py * sin), cy + ry * (px * sin + py * cos))
+    };
+    let samples = (turns * a.max(b) * SAMPLES_PER_TURN) as usize;
+    // The line thins as the swing dies away, like ink from a slowing pen
+    let fade = |t: f64| 0.35 + 0.65 * (-damping * t).exp();
+    trace(&curve, turns, samples, colors, weight, fade)
+}
+
+/// Hypotrochoids: a wheel of radius `q / p` rolling inside a ring of radius
+/// one, the pen at some distance from its hub. Several pens share the wheel.
+fn spirograph(rng: &mut impl Rng, c: &Canvas, colors: &[Color], weight: f64) -> Vec<Node> {
+    let p = rng.gen_range(20..96);
+    let q = loop {
+        let q = rng.gen_range(p / 5..p * 4 / 5).max(1);
+        if gcd(p, q) == 1 {
+            break q;
+        }
+    };
+    let r = q as f64 / p as f64;
+    let pens = rng.gen_range(1..=3);
+    let reaches: Vec<f64> = (0..pens).map(|_| r * rng.gen_range(0.4..1.4)).collect();
+    let scale = c.unit * 0.45 / reaches.iter().map(|d| 1.0 - r + d).fold(0.0, f64::max);
+    let (cx, cy) = (c.w / 2.0, c.h / 2.0);
+    let turn = rng.gen_range(0.0..2.0 * PI);
+
+    let mut nodes = Vec::new();
+    for (i, d) in reaches.iter().enumerate() {
+        // The wheel goes round the ring `q` times before the curve closes
+        let curve = |t: f64| {
+            let angle = 2.0 * PI * t + turn;
+            let roll = angle * (1.0 - r) / r;
+            (
+                cx + scale * ((1.0 - r) * angle.cos() + d * roll.cos()),
+                cy + scale * ((1.0 - r) * angle.sin() - d * roll.sin()),
+            )
+        };
+        let samples = (p as f64 * SAMPLES_PER_TURN) as usize;
+        // Later pens trace thinner, over the earlier ones
+        let width = weight * (1.0 - 0.25 * i as f64);
+        nodes.extend(trace(&curve, q as f64, samples, colors, width, |_| 1.0));
+    }
+    nodes
+}
+
+fn gcd(mut a: u32, mut b: u32) -> u32 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// `curve` over `0..=length`, as cubic Hermite segments between `samples`
+/// evenly spaced points, in `BANDS` runs colored along `colors`. Each run's
+/// width is `weight` scaled by `fade` at its middle.
+fn trace(
+    curve: &impl Fn(f64) -> Point,
+    length: f64,
+    samples: usize,
+    colors: &[Color],
+    weight: f64,
+    fade: impl Fn(f64) -> f64,
+) -> Vec<Node> {
+    let samples = samples.max(BANDS);
+    let dt = length / samples as f64;
+    // Tangents by central differences, scaled to one sample step
+    let h = dt * 1e-3;
+    let velocity = |t: f64| {
+        let (a, b) = (curve(t - h), curve(t + h));
+        ((b.0 - a.0) / (2.0 * h) * dt, (b.1 - a.1) / (2.0 * h) * dt)
+    };
+
+    (0..BANDS)
+        .map(|band| {
+            let (from, to) = (band * samples / BANDS, (band + 1) * samples / BANDS);
+            let start = curve(from as f64 * dt);
+            let mut path = vec![Segment::MoveTo(start.0, start.1)];
+            let (mut p0, mut v0) = (start, velocity(from as f64 * dt));
+            for i in from + 1..=to {
+                let t = i as f64 * dt;
+                let (p1, v1) = (curve(t), velocity(t));
+                path.push(Segment::CubicTo(
+                    p0.0 + v0.0 / 3.0,
+                    p0.1 + v0.1 / 3.0,
+                    p1.0 - v1.0 / 3.0,
+                    p1.1 - v1.1 / 3.0,
+                    p1.0,
+                    p1.1,
+                ));
+                (p0, v0) = (p1, v1);
+            }
+            let middle = (band as f64 + 0.5) / BANDS as f64;
+            Shape::path(path)
+                .stroke(ramp(colors, middle), weight * fade(middle * length))
+                .round_cap()
+                .opacity(0.9)
+                .into()
+        })
+        .collect()
+}
@@ -0,0 +1,244 @@
+//! Crystalline low-poly art: Poisson-disk points triangulated (Delaunay) or
+//! grown into Voronoi cells, each colored from a palette gradient field.
+
+use std::f64::consts::PI;
+
+use rand::Rng;
+
+use super::scene::{Color, Node, Shape};
+use super::{pick_ramp, ramp, Canvas, Palette};
+use crate::services::noise;
+
+pub(super) fn generate(rng: &mut impl Rng, palette: &Palette, c: &Canvas) -> Vec<Node> {
+    let spacing = c.unit * rng.gen_range(0.045..0.09) / c.density.sqrt();
+    // Sample past the edges so every cell touching the canvas is closed
+    let margin = spacing * 3.0;
+    let points = poisson_disk(rng, (-margin, -margin, c.w + margin, c.h + margin), spacing);
+    let mesh = Delaunay::new(&points);
+
+    let stops = rng.gen_range(3..5);
+    let colors = pick_ramp(rng, palette, stops);
+    let angle = rng.gen_range(0.0..PI * 2.0);
+    let (dx, dy) = (angle.cos(), angle.sin());
+    let half_extent = (c.w * dx.abs() + c.h * dy.abs()) / 2.0;
+    let noise_seed = rng.gen::<u64>();
+    let field = |x: f64, y: f64| {
+        let along = ((x - c.w / 2.0) * dx + (y - c.h / 2.0) * dy) / half_extent / 2.0 + 0.5;
+        let warp = noise::fbm(noise_seed, x / c.unit * 2.0, y / c.unit * 2.0, 3) - 0.5;
+        ramp(&colors, along + warp * 0.35)
+    };
+
+    let voronoi = rng.gen_bool(0.35);
+    let wire = rng.gen_bool(0.3);
+    let wire_color = Color::hex(palette.bg).mix(Color { r: 255, g: 255, b: 255 }, 0.8);
+    let cells = if voronoi { mesh.cells(&points) } else { mesh.triangles(&points) };
+
+    let mut nodes = Vec::with_capacity(cells.len());
+    for (site, polygon) in cells {
+        let (sx, sy) = site;
+        let outside = polygon.iter().all(|p| p.0 < 0.0)
+            || polygon.iter().all(|p| p.1 < 0.0)
+            || polygon.iter().all(|p| p.0 > c.w)
+            || polygon.iter().all(|p| p.1 > c.h);
+        if outside {
+            continue;
+        }
+        // Facet lighting: a little random shade on top of the field
+        let shade = rng.gen_range(-0.12..0.12);
+        let base = field(sx, sy);
+        let color = if shade < 0.0 {
+            base.mix(Color { r: 0, g: 0, b: 0 }, -shade)
+        } else {
+            base.mix(Color { r: 255, g: 255, b: 255 }, shade)
+        };
+        // A hairline stroke in the fill color closes anti-aliasing seams between facets
+        let (edge, width) = if wire { (wire_color, c.px(1.2)) } else { (color, c.px(0.8)) };
+        let shape = Shape::polygon(polygon).fill(color).stroke(edge, width);
+        nodes.push(shape.opacity(if wire { 0.92 } else { 1.0 }).into());
+    }
+    nodes
+}
+
+/// Blue-noise points at least `r` apart inside `(x0, y0, x1, y1)` (Bridson).
+fn poisson_disk(
+    rng: &mut impl Rng,
+    (x0, y0, x1, y1): (f64, f64, f64, f64),
+    r: f64,
+) -> Vec<(f64, f64)> {
+    const ATTEMPTS: usize = 30;
+    let cell = r / 2f64.sqrt();
+    let cols = ((x1 - x0) / cell).ceil() as usize + 1;
+    let rows = ((y1 - y0) / cell).ceil() as usize + 1;
+    let mut grid: Vec<Option<usize>> = vec![None; cols * rows];
+    let slot = |x: f64, y: f64| ((x - x0) / cell) as usize + ((y - y0) / cell) as usize * cols;
+
+    let first = (rng.gen_range(x0..x1), rng.gen_range(y0..y1));
+    let mut points = vec![first];
+    let mut active = vec![0];
+    grid[slot(first.0, first.1)] = Some(0);
+
+    while !active.is_empty() {
+        let a = rng.gen_range(0..active.len());
+        let (px, py) = points[active[a]];
+        let mut placed = false;
+
+        for _ in 0..ATTEMPTS {
+            let angle = rng.gen_range(0.0..PI * 2.0);
+            let dist = rng.gen_range(r..r * 2.0);
+            let (x, y) = (px + angle.cos() * dist, py + angle.sin() * dist);
+            if x < x0 || y < y0 || x >= x1 || y >= y1 {
+                continue;
+            }
+            let (gx, gy) = (((x - x0) / cell) as usize, ((y - y0) / cell) as usize);
+            let near = (gy.saturating_sub(2)..(gy + 3).min(rows)).any(|ny| {
+                (gx.saturating_sub(2)..(gx + 3).min(cols)).any(|nx| {
+                    grid[nx + ny * cols].is_some_and(|i| {
+                        let (qx, qy) = points[i];
+                        (qx - x).powi(2) + (qy - y).powi(2) < r * r
+                    })
+                })
+            });
+            if !near {
+                grid[slot(x, y)] = Some(points.len());
+                active.push(points.len());
+                points.push((x, y));
+                placed = true;
+                break;
+            }
+        }
+        if !placed {
+            active.swap_remove(a);
+        }
+    }
+    points
+}
+
+/// A polygon and the point its color is sampled at.
+type Cell = ((f64, f64), Vec<(f64, f64)>);
+
+struct Triangle {
+    v: [usize; 3],
+    /// Circumcenter and squared circumradius.
+    cx: f64,
+    cy: f64,
+    r2: f64,
+}
+
+/// Delaunay triangulation by Bowyer–Watson insertion. The last three vertices
+/// belong to a super-triangle enclosing every point.
+struct Delaunay {
+    triangles: Vec<Triangle>,
+    /// Number of real input points; indices at or past this are super vertices.
+    n: usize,
+}
+
+impl Delaunay {
+    fn new(points: &[(f64, f64)]) -> Delaunay {
+        let n = points.len();
+        let (mut min_x, mut min_y, mut max_x, mut max_y) = (f64::MAX, f64::MAX, f64::MIN, f64::MIN);
+        for &(x, y) in points {
+            min_x = min_x.min(x);
+            min_y = min_y.min(y);
+            max_x = max_x.max(x);
+            max_y = max_y.max(y);
+        }
+        let span = (max_x - min_x).max(max_y - min_y).max(1.0) * 20.0;
+        let (mx, my) = ((min_x + max_x) / 2.0, (min_y + max_y) / 2.0);
+
+        let mut verts = points.to_vec();
+        verts.extend([(mx - span, my - span), (mx + span, my - span), (mx, my + span)]);
+        let mut triangles = vec![Triangle::new(&verts, [n, n + 1, n + 2])];
+
+        // Inserting in sweep order keeps the cavities small and local
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|&a, &b| verts[a].0.total_cmp(&verts[b].0));
+
+        let mut edges: Vec<[usize; 2]> = Vec::new();
+        for i in order {
+            let (x, y) = verts[i];
+            edges.clear();
+            triangles.retain(|t| {
+                if (x - t.cx).powi(2) + (y - t.cy).powi(2) >= t.r2 {
+                    return true;
+                }
+                for e in [[t.v[0], t.v[1]], [t.v[1], t.v[2]], [t.v[2], t.v[0]]] {
+                    // An edge shared by two removed triangles is interior to the cavity
+                    match edges.iter().position(|f| f[0] == e[1] && f[1] == e[0]) {
+                        Some(k) => {
+                            edges.swap_remove(k);
+                        }
+                        None => edges.push(e),
+                    }
+                }
+                false
+            });
+            for e in &edges {
+                triangles.push(Triangle::new(&verts, [e[0], e[1], i]));
+            }
+        }
+        Delaunay { triangles, n }
+    }
+
+    /// Every triangle between real points, keyed by its centroid.
+    fn triangles(&self, points: &[(f64, f64)]) -> Vec<Cell> {
+        self.triangles
+            .iter()
+            .filter(|t| t.v.iter().all(|&v| v < self.n))
+            .map(|t| {
+                let corners: Vec<(f64, f64)> = t.v.iter().map(|&v| points[v]).collect();
+                let cx = corners.iter().map(|p| p.0).sum::<f64>() / 3.0;
+                let cy = corners.iter().map(|p| p.1).sum::<f64>() / 3.0;
+                ((cx, cy), corners)
+            })
+            .collect()
+    }
+
+    /// Voronoi cells of the points off the hull, keyed by their site. Each cell
+    /// joins the circumcenters of the triangles around its site.
+    fn cells(&self, points: &[(f64, f64)]) -> Vec<Cell> {
+        let mut around: Vec<Vec<usize>> = vec![Vec::new(); self.n];
+        let mut on_hull = vec![false; self.n];
+        for (k, t) in self.triangles.iter().enumerate() {
+            let outer = t.v.iter().any(|&v| v >= self.n);
+            for &v in t.v.iter().filter(|&&v| v < self.n) {
+                around[v].push(k);
+                on_hull[v] |= outer;
+            }
+        }
+
+        (0..self.n)
+            .filter(|&i| !on_hull[i] && around[i].len() >= 3)
+            .map(|i| {
+                let (sx, sy) = points[i];
+                let mut corners: Vec<(f64, f64)> = around[i]
+                    .iter()
+                    .map(|&k| (self.triangles[k].cx, self.triangles[k].cy))
+                    .collect();
+                corners.sort_by(|a, b| {
+                    (a.1 - sy).atan2(a.0 - sx).total_cmp(&(b.1 - sy).atan2(b.0 - sx))
+                });
+                ((sx, sy), corners)
+            })
+            .collect()
+    }
+}
+
+impl Triangle {
+    /// Vertices are stored counter-clockwise so shared edges appear reversed.
+    fn new(verts: &[(f64, f64)], mut v: [usize; 3]) -> Triangle {
+        let cross = |v: [usize; 3]| {
+            let (a, b, c) = (verts[v[0]], verts[v[1]], verts[v[2]]);
+            (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)
+        };
+        if cross(v) < 0.0 {
+            v.swap(1, 2);
+        }
+        let (a, b, c) = (verts[v[0]], verts[v[1]], verts[v[2]]);
+        let d = 2.0 * cross(v);
+        let (a2, b2, c2) = (a.0 * a.0 + a.1 * a.1, b.0 * b.0 + b.1 * b.1, c.0 * c.0 + c.1 * c.1);
+        let cx = (a2 * (b.1 - c.1) + b2 * (c.1 - a.1) + c2 * (a.1 - b.1)) / d;
+        let cy = (a2 * (c.0 - b.0) + b2 * (a.0 - c.0) + c2 * (b.0 - a.0)) / d;
+        let r2 = (a.0 - cx).powi(2) + (a.1 - cy).powi(2);
+        Triangle { v, cx, cy, r2 }
+    }
+}
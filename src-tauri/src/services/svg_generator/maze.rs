@@ -0,0 +1,403 @@
+//! Perfect mazes: spanning trees carved through rectangular, hexagonal or
+//! circular (theta) grids by a recursive backtracker or by Wilson's
+//! loop-erased random walks, sometimes with the solution traced on top as a
+//! gradient line.
+//!
+//! Every grid is reduced to cells and the walls between them, so each carver
+//! runs on each grid. Walls are stroked at a thickness in display pixels
+//! (`Canvas::dpx`), keeping them equally fine on high-DPI screens whatever
+//! the cell size.
+
+use std::collections::VecDeque;
+use std::f64::consts::PI;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use super::scene::{Color, Node, Segment, Shape};
+use super::{pick_ramp, ramp, Canvas, Palette};
+
+type Point = (f64, f64);
+
+const WHITE: Color = Color { r: 255, g: 255, b: 255 };
+
+/// Color bands the walls are split into; each band is one path.
+const WALL_BANDS: usize = 12;
+
+/// Distance bands of the flood shading.
+const SHADE_BANDS: usize = 24;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Layout {
+    Rect,
+    Hex,
+    /// Concentric rings, cells splitting in two as the rings grow.
+    Theta,
+}
+
+struct Wall {
+    /// The cells either side; `None` on the outer boundary.
+    cells: (usize, Option<usize>),
+    /// A straight edge or an arc, as a polyline.
+    points: Vec<Point>,
+}
+
+impl Wall {
+    /// Halfway along the wall: where the passage through it is.
+    fn middle(&self) -> Point {
+        let n = self.points.len();
+        if n % 2 == 1 {
+            return self.points[n / 2];
+        }
+        let (a, b) = (self.points[n / 2 - 1], self.points[n / 2]);
+        ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0)
+    }
+}
+
+struct Maze {
+    centers: Vec<Point>,
+    outlines: Vec<Vec<Point>>,
+    walls: Vec<Wall>,
+    /// Neighbor and the wall shared with it, per cell.
+    links: Vec<Vec<(usize, usize)>>,
+}
+
+impl Maze {
+    fn new(centers: Vec<Point>, outlines: Vec<Vec<Point>>, walls: Vec<Wall>) -> Maze {
+        let mut links = vec![Vec::new(); centers.len()];
+        for (i, wall) in walls.iter().enumerate() {
+            if let (a, Some(b)) = wall.cells {
+                links[a].push((b, i));
+                links[b].push((a, i));
+            }
+        }
+        Maze { centers, outlines, walls, links }
+    }
+}
+
+pub(super) fn generate(rng: &mut impl Rng, palette: &Palette, c: &Canvas) -> Vec<Node> {
+    let layout = match rng.gen_range(0..10) {
+        0..=3 => Layout::Rect,
+        4..=6 => Layout::Hex,
+        _ => Layout::Theta,
+    };
+    let size = c.unit * rng.gen_range(0.025..0.06) / c.density.sqrt();
+    let center = (c.w / 2.0, c.h / 2.0);
+    // Theta mazes either sit in a disc or run off every edge
+    let radius = if rng.gen_bool(0.6) { c.unit * 0.46 } else { center.0.hypot(center.1) };
+    let maze = match layout {
+        Layout::Rect => rect(c, size),
+        Layout::Hex => hex(c, size),
+        Layout::Theta => theta(center, radius, size),
+    };
+    let mut open = if rng.gen_bool(0.5) { backtrack(rng, &maze) } else { wilson(rng, &maze) };
+
+    // The solution spans the tree's longest path; theta mazes run from the
+    // center out through a gap in the rim when the rim is in view
+    let start = if layout == Layout::Theta {
+        0
+    } else {
+        let (dist, _) = explore(&maze, &open, rng.gen_range(0..maze.centers.len()));
+        farthest(&dist, 0..maze.centers.len())
+    };
+    let (dist, parent) = explore(&maze, &open, start);
+    let rim = layout == Layout::Theta && radius < c.unit * 0.5;
+    let end = if rim {
+        let outer = maze.walls.iter().filter(|w| w.cells.1.is_none()).map(|w| w.cells.0);
+        let end = farthest(&dist, outer);
+        if let Some(exit) = maze.walls.iter().position(|w| w.cells == (end, None)) {
+            open[exit] = true;
+        }
+        end
+    } else {
+        farthest(&dist, 0..maze.centers.len())
+    };
+
+    let stops = rng.gen_range(2..4);
+    let colors = pick_ramp(rng, palette, stops);
+    let bg = Color::hex(palette.bg);
+    let shaded = rng.gen_bool(0.4);
+    let mut nodes = Vec::new();
+
+    if shaded {
+        let strength = rng.gen_range(0.5..0.85);
+        let tints: Vec<Color> = colors.iter().map(|&color| bg.mix(color, strength)).collect();
+        nodes.extend(shading(&maze, &dist, &tints, c.px(1.0)));
+    }
+
+    // Walls shade along a random direction, or outwards on theta grids
+    let angle = rng.gen_range(0.0..PI);
+    let (ax, ay) = (angle.cos(), angle.sin());
+    let extent = (c.w * ax.abs() + c.h * ay.abs()) / 2.0;
+    let tone = |(x, y): Point| match layout {
+        Layout::Theta => (x - center.0).hypot(y - center.1) / radius,
+        _ => ((x - center.0) * ax + (y - center.1) * ay) / extent * 0.5 + 0.5,
+    };
+    let width = c.dpx(rng.gen_range(1.5..3.5)).min(size * 0.3);
+    let mut bands = vec![Vec::new(); WALL_BANDS];
+    for (wall, _) in maze.walls.iter().zip(&open).filter(|(_, &open)| !open) {
+        let t = tone(wall.middle()).clamp(0.0, 1.0);
+        let band = &mut bands[((t * WALL_BANDS as f64) as usize).min(WALL_BANDS - 1)];
+        band.push(Segment::MoveTo(wall.points[0].0, wall.points[0].1));
+        band.extend(wall.points[1..].iter().map(|&(x, y)| Segment::LineTo(x, y)));
+    }
+    for (i, band) in bands.into_iter().enumerate().filter(|(_, band)| !band.is_empty()) {
+        let color = if shaded { bg } else { ramp(&colors, (i as f64 + 0.5) / WALL_BANDS as f64) };
+        nodes.push(Shape::path(band).stroke(color, width).round_cap().into());
+    }
+
+    if rng.gen_bool(0.6) {
+        let mut cells = vec![end];
+        while let Some(&cell) = cells.last().filter(|&&cell| cell != start) {
+            cells.push(parent[cell]);
+        }
+        let colors: Vec<Color> = pick_ramp(rng, palette, 2).iter().map(|c| c.mix(WHITE, 0.25)).collect();
+        nodes.extend(solution(&maze, &open, &cells, &colors, size * 0.3));
+    }
+    nodes
+}
+
+/// Square cells covering the canvas, the grid centered on it.
+fn rect(c: &Canvas, size: f64) -> Maze {
+    let (cols, rows) = ((c.w / size).ceil() as usize, (c.h / size).ceil() as usize);
+    let (x0, y0) = ((c.w - cols as f64 * size) / 2.0, (c.h - rows as f64 * size) / 2.0);
+    let id = |col: usize, row: usize| row * cols + col;
+    let (mut centers, mut outlines, mut walls) = (Vec::new(), Vec::new(), Vec::new());
+    for row in 0..rows {
+        for col in 0..cols {
+            let (x, y) = (x0 + col as f64 * size, y0 + row as f64 * size);
+            let cell = id(col, row);
+            centers.push((x + size / 2.0, y + size / 2.0));
+            outlines.push(vec![(x, y), (x + size, y), (x + size, y + size), (x, y + size)]);
+
+            let right = (col + 1 < cols).then(|| id(col + 1, row));
+            let below = (row + 1 < rows).then(|| id(col, row + 1));
+            walls.push(Wall { cells: (cell, right), points: vec![(x + size, y), (x + size, y + size)] });
+            walls.push(Wall { cells: (cell, below), points: vec![(x, y + size), (x + size, y + size)] });
+            if col == 0 {
+                walls.push(Wall { cells: (cell, None), points: vec![(x, y), (x, y + size)] });
+            }
+            if row == 0 {
+                walls.push(Wall { cells: (cell, None), points: vec![(x, y), (x + size, y)] });
+            }
+        }
+    }
+    Maze::new(centers, outlines, walls)
+}
+
+/// Pointy-topped hexagons `size` apart, odd rows shifted half a cell right.
+fn hex(c: &Canvas, size: f64) -> Maze {
+    let r = size / 3f64.sqrt();
+    let (dx, dy) = (size, r * 1.5);
+    let (cols, rows) = ((c.w / dx).ceil() as usize + 1, (c.h / dy).ceil() as usize + 1);
+    let x0 = (c.w - (cols as f64 - 0.5) * dx) / 2.0;
+    let y0 = (c.h - (rows - 1) as f64 * dy) / 2.0;
+
+    // Edge k joins corners k and k + 1, clockwise from the top corner:
+    // north-east, east, south-east, south-west, west, north-west
+    let neighbor = |col: usize, row: usize, k: usize| -> Option<usize> {
+        let (col, row, shift) = (col as i64, row as i64, (row % 2) as i64);
+        let (nc, nr) = match k {
+            0 => (col + shift, row - 1),
+            1 => (col + 1, row),
+            2 => (col + shift, row + 1),
+            3 => (col + shift - 1, row + 1),
+            4 => (col - 1, row),
+            _ => (col + shift - 1, row - 1),
+        };
+        let inside = (0..cols as i64).contains(&nc) && (0..rows as i64).contains(&nr);
+        inside.then(|| nr as usize * cols + nc as usize)
+    };
+
+    let (mut centers, mut outlines, mut walls) = (Vec::new(), Vec::new(), Vec::new());
+    for row in 0..rows {
+        for col in 0..cols {
+            let cx = x0 + (col as f64 + (row % 2) as f64 * 0.5) * dx;
+            let cy = y0 + row as f64 * dy;
+            let corners: Vec<Point> = (0..6)
+                .map(|k| {
+                    let a = (k as f64 * 60.0 - 90.0).to_radians();
+                    (cx + a.cos() * r, cy + a.sin() * r)
+                })
+                .collect();
+            let cell = row * cols + col;
+            for k in 0..6 {
+                // Shared edges are added by the cell on their west or north side
+                let other = neighbor(col, row, k);
+                if (1..=3).contains(&k) || other.is_none() {
+                    walls.push(Wall { cells: (cell, other), points: vec![corners[k], corners[(k + 1) % 6]] });
+                }
+            }
+            centers.push((cx, cy));
+            outlines.push(corners);
+        }
+    }
+    Maze::new(centers, outlines, walls)
+}
+
+/// Rings `size` deep around a central disc. Each ring has as many cells as
+/// the one inside it, or twice as many once that keeps cells closer to square.
+fn theta(center: Point, radius: f64, size: f64) -> Maze {
+    let rings = ((radius / size) as usize).max(3);
+    let at = |r: f64, a: f64| (center.0 + a.cos() * r, center.1 + a.sin() * r);
+    // An even number of steps puts a vertex at the arc's midpoint
+    let arc = |r: f64, a0: f64, a1: f64| -> Vec<Point> {
+        let steps = ((a1 - a0) * r / (size * 0.6)).ceil() as usize * 2;
+        (0..=steps).map(|i| at(r, a0 + (a1 - a0) * i as f64 / steps as f64)).collect()
+    };
+
+    let mut centers = vec![center];
+    let mut outlines = vec![arc(size, 0.0, PI * 2.0)];
+    let mut walls = Vec::new();
+    // First cell index and cell count of the previous ring
+    let (mut inner_first, mut inner_count) = (0, 1);
+    for ring in 1..rings {
+        let count = inner_count * ((PI * 2.0 * ring as f64 / inner_count as f64).round() as usize).max(1);
+        let first = centers.len();
+        let (r0, r1) = (ring as f64 * size, (ring + 1) as f64 * size);
+        for j in 0..count {
+            let (a0, a1) = (PI * 2.0 * j as f64 / count as f64, PI * 2.0 * (j + 1) as f64 / count as f64);
+            let cell = first + j;
+            centers.push(at((r0 + r1) / 2.0, (a0 + a1) / 2.0));
+            let mut outline = arc(r0, a0, a1);
+            outline.extend(arc(r1, a0, a1).into_iter().rev());
+            outlines.push(outline);
+
+            let parent = inner_first + j * inner_count / count;
+            walls.push(Wall { cells: (cell, Some(parent)), points: arc(r0, a0, a1) });
+            let next = first + (j + 1) % count;
+            walls.push(Wall { cells: (cell, Some(next)), points: vec![at(r0, a1), at((r0 + r1) / 2.0, a1), at(r1, a1)] });
+            if ring + 1 == rings {
+                walls.push(Wall { cells: (cell, None), points: arc(r1, a0, a1) });
+            }
+        }
+        (inner_first, inner_count) = (first, count);
+    }
+    Maze::new(centers, outlines, walls)
+}
+
+/// Depth-first carving: walk to a random unvisited neighbor, backing up when
+/// there is none. Long winding corridors with few dead ends.
+fn backtrack(rng: &mut impl Rng, maze: &Maze) -> Vec<bool> {
+    let mut open = vec![false; maze.walls.len()];
+    let mut visited = vec![false; maze.centers.len()];
+    let start = rng.gen_range(0..maze.centers.len());
+    visited[start] = true;
+    let mut stack = vec![start];
+    while let Some(&cell) = stack.last() {
+        let next: Vec<(usize, usize)> = maze.links[cell].iter().filter(|(n, _)| !visited[*n]).copied().collect();
+        let Some(&(to, wall)) = next.choose(rng) else {
+            stack.pop();
+            continue;
+        };
+        open[wall] = true;
+        visited[to] = true;
+        stack.push(to);
+    }
+    open
+}
+
+/// Wilson's algorithm: random walks from outside the tree until they hit it,
+/// with loops erased, so every spanning tree is equally likely. Shorter,
+/// busier passages than the backtracker.
+fn wilson(rng: &mut impl Rng, maze: &Maze) -> Vec<bool> {
+    let n = maze.centers.len();
+    let mut open = vec![false; maze.walls.len()];
+    let mut in_tree = vec![false; n];
+    in_tree[rng.gen_range(0..n)] = true;
+    // Last step taken out of each cell; revisiting a cell overwrites it,
+    // which erases the loop
+    let mut step = vec![(0, 0); n];
+    let mut order: Vec<usize> = (0..n).collect();
+    order.shuffle(rng);
+    for start in order {
+        let mut cell = start;
+        while !in_tree[cell] {
+            step[cell] = maze.links[cell][rng.gen_range(0..maze.links[cell].len())];
+            cell = step[cell].0;
+        }
+        let mut cell = start;
+        while !in_tree[cell] {
+            in_tree[cell] = true;
+            let (next, wall) = step[cell];
+            open[wall] = true;
+            cell = next;
+        }
+    }
+    open
+}
+
+/// Breadth-first distances from `from` through open walls, and each cell's
+/// parent on the way back.
+fn explore(maze: &Maze, open: &[bool], from: usize) -> (Vec<usize>, Vec<usize>) {
+    let mut dist = vec![usize::MAX; maze.centers.len()];
+    let mut parent = vec![from; maze.centers.len()];
+    dist[from] = 0;
+    let mut queue = VecDeque::from([from]);
+    while let Some(cell) = queue.pop_front() {
+        for &(next, wall) in &maze.links[cell] {
+            if open[wall] && dist[next] == usize::MAX {
+                dist[next] = dist[cell] + 1;
+                parent[next] = cell;
+                queue.push_back(next);
+            }
+        }
+    }
+    (dist, parent)
+}
+
+fn farthest(dist: &[usize], cells: impl Iterator<Item = usize>) -> usize {
+    cells.max_by_key(|&cell| dist[cell]).unwrap_or(0)
+}
+
+/// Cells tinted by their distance along the maze from the start, one path per
+/// band of distances. A hairline of the fill color closes the anti-aliasing
+/// seams between bands.
+fn shading(maze: &Maze, dist: &[usize], colors: &[Color], seam: f64) -> Vec<Node> {
+    let max = dist.iter().copied().max().unwrap_or(0).max(1) as f64;
+    let mut bands = vec![Vec::new(); SHADE_BANDS];
+    for (outline, &d) in maze.outlines.iter().zip(dist) {
+        let band = &mut bands[((d as f64 / max * SHADE_BANDS as f64) as usize).min(SHADE_BANDS - 1)];
+        band.push(Segment::MoveTo(outline[0].0, outline[0].1));
+        band.extend(outline[1..].iter().map(|&(x, y)| Segment::LineTo(x, y)));
+        band.push(Segment::Close);
+    }
+    bands
+        .into_iter()
+        .enumerate()
+        .filter(|(_, band)| !band.is_empty())
+        .map(|(i, band)| {
+            let t = (i as f64 + 0.5) / SHADE_BANDS as f64;
+            let color = ramp(colors, t);
+            Shape::path(band).fill(color).stroke(color, seam).into()
+        })
+        .collect()
+}
+
+/// The path from start to end through the gaps in the walls, in short runs
+/// colored along the gradient, with a dot at either end.
+fn solution(maze: &Maze, open: &[bool], cells: &[usize], colors: &[Color], width: f64) -> Vec<Node> {
+    let mut points = vec![maze.centers[cells[0]]];
+    for pair in cells.windows(2) {
+        let wall = maze.links[pair[0]].iter().find(|(n, _)| *n == pair[1]).map(|&(_, w)| w);
+        if let Some(wall) = wall.filter(|&w| open[w]) {
+            points.push(maze.walls[wall].middle());
+        }
+        points.push(maze.centers[pair[1]]);
+    }
+
+    let runs = (points.len() - 1).clamp(1, 48);
+    let mut nodes: Vec<Node> = (0..runs)
+        .map(|k| {
+            let (from, to) = (k * (points.len() - 1) / runs, (k + 1) * (points.len() - 1) / runs);
+            let mut path = vec![Segment::MoveTo(points[from].0, points[from].1)];
+            path.extend(points[from + 1..=to].iter().map(|&(x, y)| Segment::LineTo(x, y)));
+            let color = ramp(colors, (k as f64 + 0.5) / runs as f64);
+            Shape::path(path).stroke(color, width).round_cap().into()
+        })
+        .collect();
+    for (point, color) in [(points[0], colors[0]), (points[points.len() - 1], colors[colors.len() - 1])] {
+        nodes.push(Shape::circle(point.0, point.1, width * 1.2).fill(color).into());
+    }
+    nodes
+}
@@ -0,0 +1,1196 @@
+use std::f64::consts::PI;
+use std::path::PathBuf;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::error::AppError;
+use crate::services::raster::{EffectChain, OutputOptions};
+
+mod aperiodic;
+mod aurora;
+mod cosmos;
+mod flowfield;
+mod fractal;
+mod harmonograph;
+mod lowpoly;
+mod maze;
+mod packing;
+mod pixel;
+mod prompt;
+mod render;
+mod retroscape;
+mod skyline;
+pub mod scene;
+pub mod symmetry;
+mod tiling;
+mod topographic;
+mod turing;
+
+use prompt::PromptHints;
+use scene::{
+    Color, Focus, Geometry, GradientStop, Layer, LinearGradient, Node, Paint, RadialGradient,
+    Scene, Segment, Shape, Transform,
+};
+use symmetry::Symmetry;
+
+/// Color palette: a background color and a set of accent colors.
+struct Palette {
+    bg: &'static str,
+    colors: &'static [&'static str],
+}
+
+// --- Named color scheme palettes ---
+
+const DEFAULT_PALETTES: &[Palette] = &[
+    Palette { bg: "#0f172a", colors: &["#3b82f6", "#8b5cf6", "#06b6d4", "#6366f1", "#2dd4bf"] },
+    Palette { bg: "#1a1a2e", colors: &["#e94560", "#533483", "#0f3460", "#16213e", "#e94560"] },
+    Palette { bg: "#0d1117", colors: &["#58a6ff", "#bc8cff", "#7ee787", "#ffa657", "#ff7b72"] },
+    Palette { bg: "#1e1b4b", colors: &["#818cf8", "#c084fc", "#f472b6", "#fb923c", "#a78bfa"] },
+    Palette { bg: "#022c22", colors: &["#34d399", "#6ee7b7", "#a7f3d0", "#059669", "#10b981"] },
+    Palette { bg: "#2d1b69", colors: &["#f97316", "#eab308", "#ef4444", "#ec4899", "#8b5cf6"] },
+    Palette { bg: "#18181b", colors: &["#f4f4f5", "#a1a1aa", "#71717a", "#52525b", "#d4d4d8"] },
+    Palette { bg: "#1c1917", colors: &["#f59e0b", "#d97706", "#b45309", "#fbbf24", "#fde68a"] },
+];
+
+const RETRO_PALETTES: &[Palette] = &[
+    Palette { bg: "#2b1a0e", colors: &["#D94F30", "#E8832A", "#F2B824", "#F5E6C8", "#2A9D8F", "#1D6B8A"] },
+    Palette { bg: "#1a1208", colors: &["#C1440E", "#E77728", "#F4A742", "#F7DC6F", "#8E6C4A", "#5B3A29"] },
+    Palette { bg: "#0f2421", colors: &["#2A9D8F", "#264653", "#E9C46A", "#8AB17D", "#287271", "#5B8E7D"] },
+    Palette { bg: "#0e1b2e", colors: &["#457B9D", "#1D3557", "#A8DADC", "#9DB4C0", "#6B8F71", "#E0AFA0"] },
+    Palette { bg: "#1f1a1e", colors: &["#6D6875", "#B5838D", "#E5989B", "#FFB4A2", "#FFCDB2", "#9E8576"] },
+    Palette { bg: "#1e1a14", colors: &["#CB997E", "#DDBEA9", "#B7B7A4", "#A5A58D", "#6B705C", "#FFE8D6"] },
+    Palette { bg: "#1a1f0e", colors: &["#606C38", "#283618", "#FEFAE0", "#DDA15E", "#BC6C25", "#9B8E7E"] },
+    Palette { bg: "#0e0d26", colors: &["#48BFE3", "#5390D9", "#6930C3", "#7400B8", "#64DFDF", "#80FFDB"] },
+    Palette { bg: "#1e152a", colors: &["#FFD6FF", "#E7C6FF", "#C8B6FF", "#B8C0FF", "#BBD0FF", "#FFFFFC"] },
+];
+
+const NEON_PALETTES: &[Palette] = &[
+    Palette { bg: "#0a0a0a", colors: &["#ff0055", "#00ff87", "#00d4ff", "#ff00ff", "#ffff00"] },
+    Palette { bg: "#050510", colors: &["#ff3366", "#33ffcc", "#3366ff", "#ff66ff", "#66ff33"] },
+    Palette { bg: "#0d0d0d", colors: &["#ff2d00", "#00ffc8", "#7b2dff", "#ff6ec7", "#00ff00"] },
+    Palette { bg: "#080012", colors: &["#fe019a", "#04d9ff", "#39ff14", "#ff6700", "#bc13fe"] },
+];
+
+const CYBERPUNK_PALETTES: &[Palette] = &[
+    Palette { bg: "#0a0014", colors: &["#ff2a6d", "#05d9e8", "#d1f7ff", "#ff6c11", "#01012b"] },
+    Palette { bg: "#0d0221", colors: &["#ff2975", "#00fff1", "#ff00a0", "#ffd319", "#0abdc6"] },
+    Palette { bg: "#120024", colors: &["#f72585", "#4cc9f0", "#7209b7", "#fca311", "#e5e5e5"] },
+    Palette { bg: "#0b0c10", colors: &["#c5c6c7", "#45a29e", "#66fcf1", "#ff003c", "#1f2833"] },
+];
+
+const SYNTHWAVE_PALETTES: &[Palette] = &[
+    Palette { bg: "#0d0028", colors: &["#ff71ce", "#01cdfe", "#b967ff", "#fffb96", "#05ffa1"] },
+    Palette { bg: "#1a0033", colors: &["#f72585", "#b5179e", "#7209b7", "#560bad", "#480ca8"] },
+    Palette { bg: "#0f0326", colors: &["#ff6ad5", "#c774e8", "#ad8cff", "#8795e8", "#94d0ff"] },
+    Palette { bg: "#170040", colors: &["#ff2281", "#ff6e27", "#fff338", "#88e8f2", "#7b68ee"] },
+];
+
+/// Resolve a palette from selected color scheme IDs.
+/// No schemes → random default. One scheme → random from that pool. Multiple → blend.
+/// Schemes named in the prompt stand in when none are selected, and prompt hues or
+/// brightness steer which palette of the pool is picked.
+fn resolve_palette(rng: &mut impl Rng, color_schemes: &[String], hints: &PromptHints) -> Palette {
+    let steered = hints.hue.is_some() || hints.brightness != 0.0;
+    let schemes: Vec<&str> = if color_schemes.is_empty() {
+        hints.schemes.clone()
+    } else {
+        color_schemes.iter().map(|s| s.as_str()).collect()
+    };
+
+    if schemes.is_empty() {
+        let pool: Vec<&'static Palette> = if steered {
+            // A color word should be able to reach any palette we ship
+            ALL_POOLS.iter().flat_map(|pool| pool.iter()).collect()
+        } else {
+            DEFAULT_PALETTES.iter().collect()
+        };
+        let p = choose_palette(rng, &pool, hints);
+        return Palette { bg: p.bg, colors: p.colors };
+    }
+
+    let mut pools: Vec<&[Palette]> = Vec::new();
+    for scheme in schemes {
+        match scheme {
+            "retro" => pools.push(RETRO_PALETTES),
+            "neon" => pools.push(NEON_PALETTES),
+            "cyberpunk" => pools.push(CYBERPUNK_PALETTES),
+            "synthwave" => pools.push(SYNTHWAVE_PALETTES),
+            _ => {}
+        }
+    }
+
+    if pools.is_empty() {
+        let pool: Vec<&'static Palette> = DEFAULT_PALETTES.iter().collect();
+        let p = choose_palette(rng, &pool, hints);
+        return Palette { bg: p.bg, colors: p.colors };
+    }
+
+    if pools.len() == 1 {
+        let pool: Vec<&'static Palette> = pools[0].iter().collect();
+        let p = choose_palette(rng, &pool, hints);
+        return Palette { bg: p.bg, colors: p.colors };
+    }
+
+    // Multiple schemes: pick one palette from each, merge their colors
+    let mut all_colors: Vec<&'static str> = Vec::new();
+    let mut bgs: Vec<&'static str> = Vec::new();
+    for pool in &pools {
+        let pool: Vec<&'static Palette> = pool.iter().collect();
+        let p = choose_palette(rng, &pool, hints);
+        bgs.push(p.bg);
+        all_colors.extend_from_slice(p.colors);
+    }
+
+    let bg = bgs[rng.gen_range(0..bgs.len())];
+    // Leak the combined slice (trivial memory, runs once per generation)
+    let colors: &'static [&'static str] = Box::leak(all_colors.into_boxed_slice());
+
+    Palette { bg, colors }
+}
+
+const ALL_POOLS: &[&[Palette]] = &[
+    DEFAULT_PALETTES,
+    RETRO_PALETTES,
+    NEON_PALETTES,
+    CYBERPUNK_PALETTES,
+    SYNTHWAVE_PALETTES,
+];
+
+/// Pick a palette from a pool. Without prompt hints this is uniform; otherwise one
+/// of the two best matches for the requested hue and brightness, so repeated
+/// generations from the same prompt still vary.
+fn choose_palette(
+    rng: &mut impl Rng,
+    pool: &[&'static Palette],
+    hints: &PromptHints,
+) -> &'static Palette {
+    if hints.hue.is_none() && hints.brightness == 0.0 {
+        return pool[rng.gen_range(0..pool.len())];
+    }
+
+    let mut scored: Vec<(f64, &'static Palette)> =
+        pool.iter().map(|&p| (palette_score(p, hints), p)).collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    let top = scored.len().min(2);
+    scored[rng.gen_range(0..top)].1
+}
+
+fn palette_score(palette: &Palette, hints: &PromptHints) -> f64 {
+    let mut score = 0.0;
+    let accents: Vec<(f64, f64, f64)> = palette.colors.iter().map(|c| hex_to_hsl(c)).collect();
+
+    if let Some(target) = hints.hue {
+        // Weight each accent by how colorful it is, so greys don't count as a hue
+        let mut weight_sum = 0.0;
+        let mut closeness = 0.0;
+        for &(hue, sat, light) in &accents {
+            let weight = sat * (1.0 - (2.0 * light - 1.0).abs()) + 0.01;
+            let diff = (hue - target).abs() % 360.0;
+            let dist = diff.min(360.0 - diff);
+            closeness += weight * (1.0 - dist / 180.0);
+            weight_sum += weight;
+        }
+        score += closeness / weight_sum;
+    }
+
+    if hints.brightness != 0.0 {
+        let (_, _, bg_light) = hex_to_hsl(palette.bg);
+        let mean_light = accents.iter().map(|a| a.2).sum::<f64>() / accents.len() as f64;
+        let target_light = 0.5 + hints.brightness * 0.25;
+        score -= (mean_light - target_light).abs();
+        if hints.brightness < 0.0 {
+            score -= bg_light * -hints.brightness;
+        } else {
+            score += bg_light * hints.brightness;
+        }
+    }
+
+    score
+}
+
+/// Parse "#rrggbb" into (hue degrees, saturation, lightness).
+fn hex_to_hsl(hex: &str) -> (f64, f64, f64) {
+    let hex = hex.trim_start_matches('#');
+    let channel = |i: usize| {
+        u8::from_str_radix(hex.get(i..i + 2).unwrap_or("00"), 16).unwrap_or(0) as f64 / 255.0
+    };
+    let (r, g, b) = (channel(0), channel(2), channel(4));
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let light = (max + min) / 2.0;
+    let delta = max - min;
+    if delta == 0.0 {
+        return (0.0, 0.0, light);
+    }
+    let sat = delta / (1.0 - (2.0 * light - 1.0).abs());
+    let hue = if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    (hue, sat, light)
+}
+
+/// Target surface plus the composition knobs every generator reads.
+///
+/// Element sizes are expressed in `unit` (the short side) rather than the width,
+/// so shapes keep their proportions on portrait and ultrawide canvases, and
+/// stroke widths go through `px` so lines look the same at 1080p and 8K.
+/// Fine detail that should look the same on any screen goes through `dpx`.
+struct Canvas {
+    w: f64,
+    h: f64,
+    /// Reference length: the short side of the canvas.
+    unit: f64,
+    /// Multiplier on element counts; below 1.0 is sparser, above is busier.
+    density: f64,
+    /// Device pixels per logical pixel of the target display, when known.
+    scale: Option<f64>,
+    /// Time of day the prompt asked for, in hours, for scenes under a sky.
+    hour: Option<f64>,
+    /// The canvas is one tile of a repeating pattern, so layouts must wrap
+    /// around its edges.
+    tileable: bool,
+}
+
+impl Canvas {
+    fn new(w: f64, h: f64, density: f64, scale: Option<f64>) -> Canvas {
+        Canvas { w, h, unit: w.min(h), density, scale, hour: None, tileable: false }
+    }
+
+    /// Scale an element count by the density, never dropping below one.
+    fn count(&self, n: usize) -> usize {
+        ((n as f64 * self.density).round() as usize).max(1)
+    }
+
+    /// Like `count`, for elements scattered over the whole surface: the count
+    /// also follows the canvas area, so a 32:9 canvas gets twice the shapes of
+    /// a 16:9 one at the same size rather than the same shapes stretched.
+    fn scatter(&self, n: usize) -> usize {
+        ((n as f64 * self.density * self.frames()).round() as usize).max(1)
+    }
+
+    /// Canvas area measured in 16:9 frames of the short side
+    /// (1.0 for 16:9 and 9:16, 2.0 for 32:9).
+    fn frames(&self) -> f64 {
+        (self.w * self.h) / (self.unit * self.unit * 16.0 / 9.0)
+    }
+
+    /// Canvas width measured in 16:9 frames (1.0 for 16:9, 0.56 for 9:16).
+    fn widths(&self) -> f64 {
+        self.w / (self.unit * 16.0 / 9.0)
+    }
+
+    /// A length tuned on a 1080p canvas, scaled to this canvas' resolution.
+    fn px(&self, len: f64) -> f64 {
+        len * self.unit / 1080.0
+    }
+
+    /// A length in logical pixels of the target display, so hairlines keep
+    /// the same physical thickness on a 4K laptop and a 4K television.
+    /// Falls back to `px` when the display is unknown (custom resolutions).
+    fn dpx(&self, len: f64) -> f64 {
+        match self.scale {
+            Some(scale) => len * scale,
+            None => self.px(len),
+        }
+    }
+}
+
+/// Styles whose layouts wrap around a tileable canvas. The others hang off
+/// the canvas itself (a horizon, a focal point, a field of noise) and can't
+/// repeat, so tiles leave them out.
+const TILEABLE_STYLES: &[&str] =
+    &["geometric", "minimal", "lineart", "abstract", "isometric", "dots", "tiling", "turing"];
+
+/// Tiles are squares of the target's short side divided by this: small
+/// enough to keep the file tiny, large enough that the repeat isn't obvious.
+const TILE_DIVISOR: u32 = 2;
+
+/// What a scene is composed for: the surface it covers, or one tile of it.
+pub struct Target {
+    pub width: u32,
+    pub height: u32,
+    /// The target display's, when known; it only tunes line weights that
+    /// are meant to track the screen's pixel density.
+    pub scale_factor: Option<f64>,
+    /// Compose a small square tile that repeats seamlessly across the
+    /// surface instead of the surface itself.
+    pub tileable: bool,
+}
+
+/// Compose a scene for the selected styles at the given dimensions. Pass it to
+/// [`rasterize`] for a PNG; it is kept so it can be exported as SVG later.
+///
+/// The same seed reproduces the same composition at any resolution with the
+/// same aspect ratio.
+pub fn compose(
+    styles: &[String],
+    color_schemes: &[String],
+    custom_prompt: Option<&str>,
+    seed: u64,
+    target: &Target,
+) -> Scene {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let hints = prompt::interpret(custom_prompt);
+    let palette = resolve_palette(&mut rng, color_schemes, &hints);
+    let (w, h) = if target.tileable {
+        let side = (target.width.min(target.height) / TILE_DIVISOR).max(1) as f64;
+        (side, side)
+    } else {
+        (target.width as f64, target.height as f64)
+    };
+    let canvas = Canvas {
+        hour: hints.hour,
+        tileable: target.tileable,
+        ..Canvas::new(w, h, hints.density(), target.scale_factor.filter(|s| *s > 0.0))
+    };
+
+    let mut cats: Vec<&str> = if styles.is_empty() {
+        vec!["geometric", "gradient"]
+    } else {
+        styles.iter().map(|s| s.as_str()).collect()
+    };
+    for style in &hints.styles {
+        if !cats.contains(style) {
+            cats.push(style);
+        }
+    }
+    let mut symmetry = hints.symmetry;
+    if canvas.tileable {
+        cats.retain(|cat| TILEABLE_STYLES.contains(cat));
+        if cats.is_empty() {
+            cats.push("geometric");
+        }
+        if !symmetry.tiles() {
+            symmetry = Symmetry::None;
+        }
+    }
+
+    let mut layers = Vec::new();
+    for cat in &cats {
+        let (nodes, focus) = generate(cat, &mut rng, &palette, &canvas);
+        layers.push(Layer { style: cat.to_string(), focus, symmetry, nodes });
+    }
+
+    Scene {
+        width: w,
+        height: h,
+        background: Color::hex(palette.bg),
+        layers,
+        tileable: canvas.tileable,
+    }
+}
+
+/// Nodes for one style, plus its focal point for styles laid out around one.
+/// Unknown styles fall back to geometric.
+fn generate(style: &str, rng: &mut impl Rng, palette: &Palette, canvas: &Canvas) -> (Vec<Node>, Option<Focus>) {
+    match style {
+        "geometric" => (gen_geometric(rng, palette, canvas), None),
+        "gradient" => (gen_gradients(rng, palette, canvas), None),
+        "minimal" => (gen_minimal(rng, palette, canvas), None),
+        "lineart" => (gen_lineart(rng, palette, canvas), None),
+        "isometric" => (gen_isometric(rng, palette, canvas), None),
+        "abstract" => (gen_abstract(rng, palette, canvas), None),
+        "waves" => (gen_waves(rng, palette, canvas), None),
+        "dots" => gen_dots(rng, palette, canvas),
+        "silkflow" => (gen_silkflow(rng, palette, canvas), None),
+        "sunburst" => gen_sunburst(rng, palette, canvas),
+        "lowpoly" => (lowpoly::generate(rng, palette, canvas), None),
+        "flowfield" => (flowfield::generate(rng, palette, canvas), None),
+        "topographic" => (topographic::generate(rng, palette, canvas), None),
+        "tiling" => (tiling::generate(rng, palette, canvas), None),
+        "retroscape" => (retroscape::generate(rng, palette, canvas), None),
+        "packing" => (packing::generate(rng, palette, canvas), None),
+        "cosmos" => (cosmos::generate(rng, palette, canvas), None),
+        "fractal" => (fractal::generate(rng, palette, canvas), None),
+        "maze" => (maze::generate(rng, palette, canvas), None),
+        "aurora" => (aurora::generate(rng, palette, canvas), None),
+        "pixel" => (pixel::generate(rng, palette, canvas), None),
+        "aperiodic" => (aperiodic::generate(rng, palette, canvas), None),
+        "harmonograph" => (harmonograph::generate(rng, palette, canvas), None),
+        "turing" => (turing::generate(rng, palette, canvas), None),
+        "skyline" => (skyline::generate(rng, palette, canvas), None),
+        _ => (gen_geometric(rng, palette, canvas), None),
+    }
+}
+
+/// Rasterize a scene to a PNG at its own size, in parallel strips, through the
+/// effect chain. `progress` receives (strips done, total strips).
+pub fn rasterize(
+    scene: &Scene,
+    output_path: &PathBuf,
+    options: &OutputOptions,
+    effects: &EffectChain,
+    progress: &(dyn Fn(u32, u32) + Sync),
+) -> Result<(), AppError> {
+    let (w, h) = (scene.width.round() as u32, scene.height.round() as u32);
+    render::rasterize(scene, w, h, output_path, options, effects, progress)
+}
+
+/// Rasterize a scene edited by the frontend. Layers whose focus was moved are
+/// laid out again around the new point first; the updated scene is returned.
+pub fn render_scene(
+    mut scene: Scene,
+    output_path: &PathBuf,
+    options: &OutputOptions,
+    effects: &EffectChain,
+    progress: &(dyn Fn(u32, u32) + Sync),
+) -> Result<Scene, AppError> {
+    if !(scene.width >= 1.0 && scene.height >= 1.0) {
+        return Err(AppError::Generation(format!(
+            "Invalid scene size {}x{}",
+            scene.width, scene.height
+        )));
+    }
+
+    let (w, h, tileable) = (scene.width, scene.height, scene.tileable);
+    for layer in &mut scene.layers {
+        refocus(layer, w, h, tileable);
+    }
+
+    rasterize(&scene, output_path, options, effects, progress)?;
+    Ok(scene)
+}
+
+/// Re-lay a layer out around its edited focal point.
+fn refocus(layer: &mut Layer, w: f64, h: f64, tileable: bool) {
+    let Some(focus) = layer.focus.as_mut().filter(|f| f.moved()) else {
+        return;
+    };
+    let (old_x, old_y) = (focus.anchor_x, focus.anchor_y);
+    let (new_x, new_y) = (focus.x, focus.y);
+    *focus = Focus::new(new_x, new_y);
+
+    for node in &mut layer.nodes {
+        let Node::Shape(shape) = node else {
+            continue;
+        };
+        match (layer.style.as_str(), &mut shape.geometry) {
+            ("dots", Geometry::Circle { cx, cy, r }) => {
+                // Undo the old falloff to recover the grid's base radius, then reapply
+                let base_r = *r / (0.3 + falloff((*cx, *cy), (old_x, old_y), w, h, tileable) * 1.5);
+                let scale = falloff((*cx, *cy), (new_x, new_y), w, h, tileable);
+                *r = base_r * (0.3 + scale * 1.5);
+                shape.opacity = 0.2 + scale * 0.5;
+            }
+            ("sunburst", Geometry::Polygon { points }) => {
+                // Rays share their apex with the focus
+                for p in points.iter_mut() {
+                    if (p.0 - old_x).abs() < 0.5 && (p.1 - old_y).abs() < 0.5 {
+                        *p = (new_x, new_y);
+                    }
+                }
+            }
+            // Anything else, like the rings beside sunburst rays, has its own center
+            _ => {}
+        }
+    }
+}
+
+// --- Pattern generators ---
+
+fn pick(rng: &mut impl Rng, palette: &Palette) -> Color {
+    Color::hex(palette.colors[rng.gen_range(0..palette.colors.len())])
+}
+
+/// `n` consecutive palette colors from a random starting entry, for ramps.
+fn pick_ramp(rng: &mut impl Rng, palette: &Palette, n: usize) -> Vec<Color> {
+    let start = rng.gen_range(0..palette.colors.len());
+    (0..n)
+        .map(|i| Color::hex(palette.colors[(start + i) % palette.colors.len()]))
+        .collect()
+}
+
+/// Color at `t` (0.0..=1.0) along evenly spaced `colors`.
+fn ramp(colors: &[Color], t: f64) -> Color {
+    if colors.len() < 2 {
+        return colors[0];
+    }
+    let x = t.clamp(0.0, 1.0) * (colors.len() - 1) as f64;
+    let i = (x.floor() as usize).min(colors.len() - 2);
+    colors[i].mix(colors[i + 1], x - i as f64)
+}
+
+/// On a tileable canvas, follows every shape that crosses an edge with copies
+/// of it shifted by whole tiles, so what leaves one side comes back in at the
+/// other. Copies keep their shape's place in the drawing order, which keeps
+/// overlaps the same on both sides of a seam.
+fn wrap(nodes: Vec<Node>, c: &Canvas) -> Vec<Node> {
+    if !c.tileable {
+        return nodes;
+    }
+    // Shifts k with part of lo..hi landing on 0..size after moving k tiles
+    let shifts = |lo: f64, hi: f64, size: f64| {
+        ((-hi / size).floor() as i64 + 1)..=((1.0 - lo / size).ceil() as i64 - 1)
+    };
+    let mut wrapped = Vec::with_capacity(nodes.len());
+    for node in nodes {
+        let Node::Shape(shape) = &node else {
+            wrapped.push(node);
+            continue;
+        };
+        let (left, top, right, bottom) = shape.bounds();
+        let columns = shifts(left, right, c.w);
+        let copies: Vec<Node> = shifts(top, bottom, c.h)
+            .flat_map(|j| columns.clone().map(move |i| (i, j)))
+            .filter(|&shift| shift != (0, 0))
+            .map(|(i, j)| {
+                let moved = Transform::translate(i as f64 * c.w, j as f64 * c.h).then(shape.transform);
+                shape.clone().transform(moved).into()
+            })
+            .collect();
+        wrapped.push(node);
+        wrapped.extend(copies);
+    }
+    wrapped
+}
+
+fn gen_geometric(rng: &mut impl Rng, palette: &Palette, c: &Canvas) -> Vec<Node> {
+    let (w, h, u) = (c.w, c.h, c.unit);
+    let mut nodes = Vec::new();
+    let count = c.scatter(rng.gen_range(15..30));
+
+    for _ in 0..count {
+        let color = pick(rng, palette);
+        let opacity = rng.gen_range(0.15..0.7);
+        let shape_type = rng.gen_range(0..4);
+
+        let shape = match shape_type {
+            0 => {
+                // Triangle
+                let cx = rng.gen_range(0.0..w);
+                let cy = rng.gen_range(0.0..h);
+                let size = rng.gen_range(u * 0.09..u * 0.44);
+                let angle: f64 = rng.gen_range(0.0..PI * 2.0);
+                let points = (0..3)
+                    .map(|i| {
+                        let a = angle + (i as f64) * PI * 2.0 / 3.0;
+                        (cx + a.cos() * size, cy + a.sin() * size)
+                    })
+                    .collect();
+                Shape::polygon(points)
+            }
+            1 => {
+                // Hexagon
+                let cx = rng.gen_range(0.0..w);
+                let cy = rng.gen_range(0.0..h);
+                let size = rng.gen_range(u * 0.05..u * 0.27);
+                let points = (0..6)
+                    .map(|i| {
+                        let a = (i as f64) * PI / 3.0;
+                        (cx + a.cos() * size, cy + a.sin() * size)
+                    })
+                    .collect();
+                Shape::polygon(points)
+            }
+            2 => {
+                // Rectangle
+                let x = rng.gen_range(-w * 0.1..w);
+                let y = rng.gen_range(-h * 0.1..h);
+                let rw = rng.gen_range(u * 0.09..u * 0.53);
+                let rh = rng.gen_range(u * 0.05..u * 0.3);
+                let angle = rng.gen_range(0.0..360.0f64);
+                Shape::rect(x, y, rw, rh)
+                    .transform(Transform::rotate(angle, x + rw / 2.0, y + rh / 2.0))
+            }
+            _ => {
+                // Circle
+                let cx = rng.gen_range(0.0..w);
+                let cy = rng.gen_range(0.0..h);
+                let r = rng.gen_range(u * 0.035..u * 0.27);
+                Shape::circle(cx, cy, r)
+            }
+        };
+        nodes.push(shape.fill(color).opacity(opacity).into());
+    }
+    wrap(nodes, c)
+}
+
+fn gen_gradients(rng: &mut impl Rng, palette: &Palette, c: &Canvas) -> Vec<Node> {
+    let (w, h, u) = (c.w, c.h, c.unit);
+    let mut nodes: Vec<Node> = Vec::new();
+
+    let angle = rng.gen_range(15.0..165.0f64);
+    let rad = angle.to_radians();
+    let stop_count = palette.colors.len().min(5);
+    let stops = palette
+        .colors
+        .iter()
+        .take(stop_count)
+        .enumerate()
+        .map(|(i, &color)| {
+            GradientStop::new(i as f64 / (stop_count - 1) as f64, Color::hex(color), 1.0)
+        })
+        .collect();
+    let main = LinearGradient {
+        x1: 0.5 - 0.5 * rad.cos(),
+        y1: 0.5 - 0.5 * rad.sin(),
+        x2: 0.5 + 0.5 * rad.cos(),
+        y2: 0.5 + 0.5 * rad.sin(),
+        stops,
+    };
+    nodes.push(Shape::rect(0.0, 0.0, w, h).paint(Paint::Linear(main)).opacity(0.7).into());
+
+    let orb_count = rng.gen_range(2..5);
+    for _ in 0..orb_count {
+        let color = pick(rng, palette);
+        let cx_pct = rng.gen_range(15.0..85.0);
+        let cy_pct = rng.gen_range(15.0..85.0);
+        let glow = RadialGradient {
+            cx: cx_pct / 100.0,
+            cy: cy_pct / 100.0,
+            r: 0.5,
+            stops: vec![
+                GradientStop::new(0.0, color, 0.6),
+                GradientStop::new(1.0, color, 0.0),
+            ],
+        };
+
+        let rx = rng.gen_range(w * 0.25..w * 0.6);
+        let ry = rng.gen_range(h * 0.25..h * 0.6);
+        let cx = cx_pct / 100.0 * w;
+        let cy = cy_pct / 100.0 * h;
+        nodes.push(Shape::ellipse(cx, cy, rx, ry).paint(Paint::Radial(glow)).into());
+    }
+
+    let band_count = rng.gen_range(2..4);
+    let perp_angle = angle + 90.0;
+    let perp_rad = perp_angle.to_radians();
+    for i in 0..band_count {
+        let t = (i as f64 + 1.0) / (band_count as f64 + 1.0);
+        let bx = w * t;
+        let by = h * t;
+        let len = (w * w + h * h).sqrt() * 0.6;
+        let bx1 = bx - perp_rad.cos() * len;
+        let by1 = by - perp_rad.sin() * len;
+        let bx2 = bx + perp_rad.cos() * len;
+        let by2 = by + perp_rad.sin() * len;
+        let color = pick(rng, palette);
+        let sw = rng.gen_range(u * 0.035..u * 0.1);
+
+        nodes.push(
+            Shape::line(bx1, by1, bx2, by2)
+                .stroke(color, sw)
+                .opacity(0.15)
+                .into(),
+        );
+    }
+
+    nodes
+}
+
+fn gen_minimal(rng: &mut impl Rng, palette: &Palette, c: &Canvas) -> Vec<Node> {
+    let (w, h, u) = (c.w, c.h, c.unit);
+    let mut nodes = Vec::new();
+    let count = c.count(rng.gen_range(3..6));
+
+    for _ in 0..count {
+        let color = pick(rng, palette);
+        let opacity = rng.gen_range(0.3..0.8);
+        let shape = rng.gen_range(0..3);
+
+        let shape = match shape {
+            0 => {
+                let cx = rng.gen_range(w * 0.2..w * 0.8);
+                let cy = rng.gen_range(h * 0.2..h * 0.8);
+                let r = rng.gen_range(u * 0.09..u * 0.36);
+                Shape::circle(cx, cy, r).fill(color)
+            }
+            1 => {
+                let y = rng.gen_range(h * 0.1..h * 0.9);
+                let x1 = rng.gen_range(0.0..w * 0.3);
+                let x2 = rng.gen_range(w * 0.7..w);
+                Shape::line(x1, y, x2, y).stroke(color, c.px(2.0))
+            }
+            _ => {
+                let size = rng.gen_range(u * 0.035..u * 0.14);
+                let x = rng.gen_range(w * 0.1..w * 0.9 - size);
+                let y = rng.gen_range(h * 0.1..h * 0.9 - size);
+                Shape::rect(x, y, size, size).fill(color)
+            }
+        };
+        nodes.push(shape.opacity(opacity).into());
+    }
+    wrap(nodes, c)
+}
+
+fn gen_lineart(rng: &mut impl Rng, palette: &Palette, c: &Canvas) -> Vec<Node> {
+    let (w, h) = (c.w, c.h);
+    let mut nodes = Vec::new();
+    let count = c.scatter(rng.gen_range(5..12));
+
+    for _ in 0..count {
+        let color = pick(rng, palette);
+        let opacity = rng.gen_range(0.3..0.8);
+        let stroke_w = c.px(rng.gen_range(1.0..4.0));
+        let points = rng.gen_range(3..8);
+
+        let mut path = vec![Segment::MoveTo(rng.gen_range(0.0..w), rng.gen_range(0.0..h))];
+        for _ in 1..points {
+            let cp1x = rng.gen_range(0.0..w);
+            let cp1y = rng.gen_range(0.0..h);
+            let cp2x = rng.gen_range(0.0..w);
+            let cp2y = rng.gen_range(0.0..h);
+            let ex = rng.gen_range(0.0..w);
+            let ey = rng.gen_range(0.0..h);
+            path.push(Segment::CubicTo(cp1x, cp1y, cp2x, cp2y, ex, ey));
+        }
+
+        nodes.push(
+            Shape::path(path)
+                .stroke(color, stroke_w)
+                .round_cap()
+                .opacity(opacity)
+                .into(),
+        );
+    }
+    wrap(nodes, c)
+}
+
+fn gen_isometric(rng: &mut impl Rng, palette: &Palette, c: &Canvas) -> Vec<Node> {
+    let (w, h) = (c.w, c.h);
+    let mut nodes = Vec::new();
+    let mut cell = c.unit * 16.0 / 9.0 / rng.gen_range(8.0..14.0);
+    let fill = (0.6 * c.density).min(0.95);
+    // On a tile the cubes repeat every `period` columns and rows, and the ones
+    // past its edges copy those inside, so overlaps match across the seams
+    let mut period = None;
+    if c.tileable {
+        // Rows are 0.6 cells apart and alternate their offset, so a square
+        // tile takes a multiple of six cells across and five thirds as many rows
+        let across = ((w / cell / 6.0).round() as i32).max(1) * 6;
+        cell = w / across as f64;
+        period = Some((across, across / 3 * 5));
+    }
+    let (cols, rows) = match period {
+        Some((across, down)) => (across + 2, down + 2),
+        None => ((w / cell) as i32 + 2, (h / (cell * 0.6)) as i32 + 2),
+    };
+    let tile: Vec<_> = match period {
+        Some((across, down)) => (0..across * down).map(|_| iso_cube(rng, palette, cell, fill)).collect(),
+        None => Vec::new(),
+    };
+
+    for row in -1..rows {
+        for col in -1..cols {
+            let cube = match period {
+                Some((across, down)) => tile[(row.rem_euclid(down) * across + col.rem_euclid(across)) as usize],
+                None => iso_cube(rng, palette, cell, fill),
+            };
+            let Some((height, color, opacity)) = cube else {
+                continue;
+            };
+            let x = col as f64 * cell + if row % 2 == 0 { 0.0 } else { cell * 0.5 };
+            let y = row as f64 * cell * 0.6;
+
+            let top = vec![
+                (x, y - height),
+                (x + cell * 0.5, y - height + cell * 0.3),
+                (x, y - height + cell * 0.6),
+                (x - cell * 0.5, y - height + cell * 0.3),
+            ];
+            nodes.push(Shape::polygon(top).fill(color).opacity(opacity).into());
+
+            let left = vec![
+                (x - cell * 0.5, y - height + cell * 0.3),
+                (x, y - height + cell * 0.6),
+                (x, y + cell * 0.6 - height + height),
+                (x - cell * 0.5, y + cell * 0.3 - height + height),
+            ];
+            nodes.push(Shape::polygon(left).fill(color).opacity(opacity * 0.7).into());
+        }
+    }
+    nodes
+}
+
+/// Height, color and opacity of an isometric cube, or `None` for a gap;
+/// cubes fill about `fill` of the grid.
+fn iso_cube(rng: &mut impl Rng, palette: &Palette, cell: f64, fill: f64) -> Option<(f64, Color, f64)> {
+    if rng.gen_range(0.0..1.0) > fill {
+        return None;
+    }
+    Some((rng.gen_range(cell * 0.3..cell * 1.2), pick(rng, palette), rng.gen_range(0.3..0.7)))
+}
+
+fn gen_abstract(rng: &mut impl Rng, palette: &Palette, c: &Canvas) -> Vec<Node> {
+    let (w, h, u) = (c.w, c.h, c.unit);
+    let mut nodes = Vec::new();
+    let count = c.scatter(rng.gen_range(6..14));
+
+    for _ in 0..count {
+        let color = pick(rng, palette);
+        let opacity = rng.gen_range(0.15..0.6);
+
+        let cx = rng.gen_range(0.0..w);
+        let cy = rng.gen_range(0.0..h);
+        let r = rng.gen_range(u * 0.09..u * 0.44);
+        let points_count = rng.gen_range(4..8);
+
+        let mut path = Vec::new();
+        let angles: Vec<f64> = (0..points_count)
+            .map(|i| (i as f64 / points_count as f64) * PI * 2.0)
+            .collect();
+
+        for (i, &angle) in angles.iter().enumerate() {
+            let rr = r * rng.gen_range(0.6..1.4);
+            let px = cx + angle.cos() * rr;
+            let py = cy + angle.sin() * rr;
+
+            if i == 0 {
+                path.push(Segment::MoveTo(px, py));
+            } else {
+                let prev_angle = angles[i - 1];
+                let prev_rr = r * rng.gen_range(0.6..1.4);
+                let cp1x = cx + prev_angle.cos() * prev_rr * 1.3;
+                let cp1y = cy + prev_angle.sin() * prev_rr * 1.3;
+                let cp2x = cx + angle.cos() * rr * 1.3;
+                let cp2y = cy + angle.sin() * rr * 1.3;
+                path.push(Segment::CubicTo(cp1x, cp1y, cp2x, cp2y, px, py));
+            }
+        }
+        path.push(Segment::Close);
+
+        nodes.push(Shape::path(path).fill(color).opacity(opacity).into());
+    }
+    wrap(nodes, c)
+}
+
+// --- Sunburst: radiating rays and concentric arcs ---
+
+fn gen_sunburst(rng: &mut impl Rng, palette: &Palette, c: &Canvas) -> (Vec<Node>, Option<Focus>) {
+    let style = rng.gen_range(0..3);
+    match style {
+        0 => {
+            let (nodes, focus) = gen_sunburst_radiating(rng, palette.colors, c);
+            (nodes, Some(focus))
+        }
+        1 => (gen_sunburst_concentric(rng, palette.colors, c), None),
+        _ => {
+            let mut nodes = gen_sunburst_concentric(rng, palette.colors, c);
+            let (rays, focus) = gen_sunburst_radiating(rng, palette.colors, c);
+            nodes.extend(rays);
+            (nodes, Some(focus))
+        }
+    }
+}
+
+fn gen_sunburst_radiating(rng: &mut impl Rng, colors: &[&str], c: &Canvas) -> (Vec<Node>, Focus) {
+    let (w, h) = (c.w, c.h);
+    let mut nodes = Vec::new();
+
+    let origins: [(f64, f64); 5] = [
+        (w * 0.5, h * 1.0),
+        (0.0, h),
+        (w, h),
+        (w * 0.5, h * 0.5),
+        (0.0, h * 0.5),
+    ];
+    let (ox, oy) = origins[rng.gen_range(0..origins.len())];
+
+    let ray_count = c.count(rng.gen_range(12..22));
+    let reach = (w * w + h * h).sqrt() * 1.2;
+
+    for i in 0..ray_count {
+        let color = Color::hex(colors[i % colors.len()]);
+        let a1 = (i as f64 / ray_count as f64) * PI * 2.0;
+        let a2 = ((i as f64 + 0.5) / ray_count as f64) * PI * 2.0;
+
+        let x1 = ox + a1.cos() * reach;
+        let y1 = oy + a1.sin() * reach;
+        let x2 = ox + a2.cos() * reach;
+        let y2 = oy + a2.sin() * reach;
+
+        nodes.push(
+            Shape::polygon(vec![(ox, oy), (x1, y1), (x2, y2)])
+                .fill(color)
+                .opacity(0.85)
+                .into(),
+        );
+    }
+    (nodes, Focus::new(ox, oy))
+}
+
+fn gen_sunburst_concentric(rng: &mut impl Rng, colors: &[&str], c: &Canvas) -> Vec<Node> {
+    let (w, h) = (c.w, c.h);
+    let mut nodes = Vec::new();
+
+    let focal_count = rng.gen_range(2..4);
+    let band_width = rng.gen_range(c.unit * 0.045..c.unit * 0.09);
+    let max_r = (w * w + h * h).sqrt() * 0.5;
+
+    for f in 0..focal_count {
+        let positions: [(f64, f64); 6] = [
+            (-w * 0.1, -h * 0.1),
+            (w * 1.1, -h * 0.1),
+            (-w * 0.1, h * 1.1),
+            (w * 1.1, h * 1.1),
+            (w * 0.5, -h * 0.2),
+            (w * 0.5, h * 1.2),
+        ];
+        let (fx, fy) = positions[rng.gen_range(0..positions.len())];
+        let ring_count = (max_r / band_width) as i32;
+
+        for r_i in 0..ring_count {
+            let r = r_i as f64 * band_width;
+            let color_idx = (f + r_i as usize) % colors.len();
+            let color = Color::hex(colors[color_idx]);
+
+            nodes.push(
+                Shape::circle(fx, fy, r)
+                    .stroke(color, band_width)
+                    .opacity(0.85)
+                    .into(),
+            );
+        }
+    }
+    nodes
+}
+
+// --- Wave generators ---
+
+fn wave_y(x: f64, w: f64, base_y: f64, amplitude: f64, frequency: f64, phase: f64) -> f64 {
+    let t = x / w;
+    base_y
+        + (t * PI * 2.0 * frequency + phase).sin() * amplitude
+        + (t * PI * 2.0 * frequency * 0.6 + phase * 1.7).sin() * amplitude * 0.4
+        + (t * PI * 2.0 * frequency * 2.1 + phase * 0.3).sin() * amplitude * 0.15
+}
+
+fn gen_waves(rng: &mut impl Rng, palette: &Palette, c: &Canvas) -> Vec<Node> {
+    gen_waves_inner(rng, palette.colors, c)
+}
+
+fn gen_waves_inner(rng: &mut impl Rng, colors: &[&str], c: &Canvas) -> Vec<Node> {
+    let (w, h) = (c.w, c.h);
+    let mut nodes = Vec::new();
+    // Keep the wavelength constant in units: a 32:9 canvas shows twice the crests
+    let segments = (80.0 * c.widths()).ceil().max(40.0) as usize;
+
+    let frequency = rng.gen_range(1.2..2.8) * c.widths();
+    let phase = rng.gen_range(0.0..PI * 2.0);
+    let base_amplitude = rng.gen_range(c.unit * 0.1..c.unit * 0.25);
+    let center_y = h * rng.gen_range(0.35..0.65);
+
+    let band_count = c.count(rng.gen_range(8..16)).max(2);
+    let total_thickness = base_amplitude * rng.gen_range(1.2..2.0);
+
+    for i in 0..band_count {
+        let color = Color::hex(colors[i % colors.len()]);
+        let t = i as f64 / (band_count - 1) as f64;
+
+        let spread = (0.5 - t).abs() * 2.0;
+        let band_offset = (t - 0.5) * total_thickness;
+        let band_amp = base_amplitude * (1.0 + spread * 0.3);
+        let band_phase = phase + (t - 0.5) * rng.gen_range(0.1..0.4);
+        let band_base = center_y + band_offset;
+
+        let thickness = total_thickness / band_count as f64
+            * rng.gen_range(0.8..1.4);
+
+        let mut upper_points: Vec<(f64, f64)> = Vec::new();
+        let mut lower_points: Vec<(f64, f64)> = Vec::new();
+
+        for j in 0..=segments {
+            let x = w * j as f64 / segments as f64;
+            let cy = wave_y(x, w, band_base, band_amp, frequency, band_phase);
+            upper_points.push((x, cy - thickness * 0.5));
+            lower_points.push((x, cy + thickness * 0.5));
+        }
+
+        let mut path = vec![Segment::MoveTo(upper_points[0].0, upper_points[0].1)];
+        for &(x, y) in &upper_points[1..] {
+            path.push(Segment::LineTo(x, y));
+        }
+        for &(x, y) in lower_points.iter().rev() {
+            path.push(Segment::LineTo(x, y));
+        }
+        path.push(Segment::Close);
+
+        let opacity = rng.gen_range(0.7..0.95);
+        nodes.push(Shape::path(path).fill(color).opacity(opacity).into());
+    }
+
+    let accent_count = rng.gen_range(3..6);
+    for a in 0..accent_count {
+        let t = (a as f64 + 0.5) / accent_count as f64;
+        let band_offset = (t - 0.5) * total_thickness;
+        let band_phase = phase + (t - 0.5) * 0.2;
+        let band_base = center_y + band_offset;
+        let color = Color::hex(colors[a % colors.len()]);
+
+        let mut path = Vec::new();
+        for j in 0..=segments {
+            let x = w * j as f64 / segments as f64;
+            let y = wave_y(x, w, band_base, base_amplitude, frequency, band_phase);
+            if j == 0 {
+                path.push(Segment::MoveTo(x, y));
+            } else {
+                path.push(Segment::LineTo(x, y));
+            }
+        }
+
+        nodes.push(Shape::path(path).stroke(color, c.px(1.5)).opacity(0.4).into());
+    }
+
+    nodes
+}
+
+fn gen_silkflow(rng: &mut impl Rng, palette: &Palette, c: &Canvas) -> Vec<Node> {
+    let (w, h, u) = (c.w, c.h, c.unit);
+    let mut nodes: Vec<Node> = Vec::new();
+
+    let ci = rng.gen_range(0..palette.colors.len());
+    let colors: Vec<Color> = (0..3)
+        .map(|j| Color::hex(palette.colors[(ci + j) % palette.colors.len()]))
+        .collect();
+
+    let ribbon_count = c.count(rng.gen_range(6..10));
+    let base_flow = rng.gen_range(0.0..PI * 2.0);
+
+    for i in 0..ribbon_count {
+        let color = colors[i % colors.len()];
+
+        let ribbon_width = rng.gen_range(u * 0.1..u * 0.32);
+
+        let flow_angle = base_flow + rng.gen_range(-0.6..0.6);
+        let perp = flow_angle + PI / 2.0;
+
+        let offset = (i as f64 - ribbon_count as f64 / 2.0) * ribbon_width * 0.6;
+        let cx = w * 0.5 + perp.cos() * offset;
+        let cy = h * 0.5 + perp.sin() * offset;
+
+        let reach = (w * w + h * h).sqrt() * 0.6;
+        let sx = cx - flow_angle.cos() * reach;
+        let sy = cy - flow_angle.sin() * reach;
+        let ex = cx + flow_angle.cos() * reach;
+        let ey = cy + flow_angle.sin() * reach;
+
+        let bend1 = rng.gen_range(-u * 0.53..u * 0.53);
+        let bend2 = rng.gen_range(-u * 0.53..u * 0.53);
+        let t1 = 0.33;
+        let t2 = 0.66;
+        let cp1x = sx + (ex - sx) * t1 + perp.cos() * bend1;
+        let cp1y = sy + (ey - sy) * t1 + perp.sin() * bend1;
+        let cp2x = sx + (ex - sx) * t2 + perp.cos() * bend2;
+        let cp2y = sy + (ey - sy) * t2 + perp.sin() * bend2;
+
+        let hw = ribbon_width * 0.5;
+        let hw_start = hw * rng.gen_range(0.4..1.0);
+        let hw_mid1 = hw * rng.gen_range(0.8..1.5);
+        let hw_mid2 = hw * rng.gen_range(0.8..1.5);
+        let hw_end = hw * rng.gen_range(0.4..1.0);
+
+        let us_x = sx + perp.cos() * hw_start;
+        let us_y = sy + perp.sin() * hw_start;
+        let uc1_x = cp1x + perp.cos() * hw_mid1;
+        let uc1_y = cp1y + perp.sin() * hw_mid1;
+        let uc2_x = cp2x + perp.cos() * hw_mid2;
+        let uc2_y = cp2y + perp.sin() * hw_mid2;
+        let ue_x = ex + perp.cos() * hw_end;
+        let ue_y = ey + perp.sin() * hw_end;
+
+        let ls_x = sx - perp.cos() * hw_start;
+        let ls_y = sy - perp.sin() * hw_start;
+        let lc1_x = cp1x - perp.cos() * hw_mid1;
+        let lc1_y = cp1y - perp.sin() * hw_mid1;
+        let lc2_x = cp2x - perp.cos() * hw_mid2;
+        let lc2_y = cp2y - perp.sin() * hw_mid2;
+        let le_x = ex - perp.cos() * hw_end;
+        let le_y = ey - perp.sin() * hw_end;
+
+        let path = vec![
+            Segment::MoveTo(us_x, us_y),
+            Segment::CubicTo(uc1_x, uc1_y, uc2_x, uc2_y, ue_x, ue_y),
+            Segment::LineTo(le_x, le_y),
+            Segment::CubicTo(lc2_x, lc2_y, lc1_x, lc1_y, ls_x, ls_y),
+            Segment::Close,
+        ];
+
+        let next_color = colors[(i + 1) % colors.len()];
+        let gradient = LinearGradient {
+            x1: (sx / w).clamp(0.0, 1.0),
+            y1: (sy / h).clamp(0.0, 1.0),
+            x2: (ex / w).clamp(0.0, 1.0),
+            y2: (ey / h).clamp(0.0, 1.0),
+            stops: vec![
+                GradientStop::new(0.0, color, 0.1),
+                GradientStop::new(0.3, color, 0.6),
+                GradientStop::new(0.7, next_color, 0.6),
+                GradientStop::new(1.0, next_color, 0.1),
+            ],
+        };
+
+        let opacity = rng.gen_range(0.25..0.55);
+        nodes.push(
+            Shape::path(path)
+                .paint(Paint::Linear(gradient))
+                .opacity(opacity)
+                .into(),
+        );
+    }
+
+    let gc = colors[0];
+    let glow = RadialGradient {
+        cx: 0.5,
+        cy: 0.5,
+        r: 0.5,
+        stops: vec![GradientStop::new(0.0, gc, 0.4), GradientStop::new(1.0, gc, 0.0)],
+    };
+    nodes.push(
+        Shape::ellipse(w * 0.5, h * 0.5, w * 0.5, h * 0.5)
+            .paint(Paint::Radial(glow))
+            .into(),
+    );
+
+    nodes
+}
+
+fn gen_dots(rng: &mut impl Rng, palette: &Palette, c: &Canvas) -> (Vec<Node>, Option<Focus>) {
+    let (w, h) = (c.w, c.h);
+    let mut nodes = Vec::new();
+    let mut spacing = rng.gen_range(c.unit * 0.07..c.unit * 0.14) / c.density.sqrt();
+    if c.tileable {
+        // A whole number of dots across the square tile, so the grid repeats
+        spacing = w / (w / spacing).round().max(1.0);
+    }
+    let base_r = spacing * 0.2;
+    let cols = (w / spacing) as i32 + 1;
+    let rows = (h / spacing) as i32 + 1;
+
+    let focus_x = rng.gen_range(w * 0.2..w * 0.8);
+    let focus_y = rng.gen_range(h * 0.2..h * 0.8);
+
+    for row in 0..rows {
+        for col in 0..cols {
+            let x = col as f64 * spacing + spacing * 0.5;
+            let y = row as f64 * spacing + spacing * 0.5;
+            let scale = falloff((x, y), (focus_x, focus_y), w, h, c.tileable);
+            let r = base_r * (0.3 + scale * 1.5);
+            let color = pick(rng, palette);
+            let opacity = 0.2 + scale * 0.5;
+
+            nodes.push(Shape::circle(x, y, r).fill(color).opacity(opacity).into());
+        }
+    }
+    (nodes, Some(Focus::new(focus_x, focus_y)))
+}
+
+/// How near a dot at `at` is to the focus, from 1.0 on it to 0.0 half a
+/// diagonal away. On a tile the distance may run across the edges, so the
+/// falloff repeats with the tile.
+fn falloff(at: (f64, f64), focus: (f64, f64), w: f64, h: f64, tileable: bool) -> f64 {
+    let span = |d: f64, size: f64| {
+        if tileable {
+            let d = d.rem_euclid(size);
+            d.min(size - d)
+        } else {
+            d
+        }
+    };
+    let dist = span(at.0 - focus.0, w).hypot(span(at.1 - focus.1, h));
+    1.0 - (dist / ((w * w + h * h).sqrt() * 0.5)).min(1.0)
+}
@@ -0,0 +1,259 @@
+//! Circle packing: non-overlapping circles of many sizes, either packed
+//! incrementally into the canvas or a circular mask, or grown as an
+//! Apollonian gasket of mutually tangent circles, optionally with smaller
+//! packings nested inside the larger circles.
+
+use rand::Rng;
+
+use super::scene::{Color, Node, Shape};
+use super::{pick, pick_ramp, ramp, Canvas, Palette};
+
+/// Cap on circles per packing, so the finest fills stay renderable at 4K.
+const MAX_CIRCLES: usize = 12_000;
+
+#[derive(Clone, Copy)]
+struct Circle {
+    x: f64,
+    y: f64,
+    r: f64,
+}
+
+/// Where circles may go: the largest radius a circle centered at a point can
+/// have without leaving the region, negative outside it.
+#[derive(Clone, Copy)]
+enum Mask {
+    /// The whole canvas; circles may be cropped by its edges.
+    Canvas,
+    Inside(Circle),
+    Outside(Circle),
+}
+
+impl Mask {
+    fn room(&self, x: f64, y: f64) -> f64 {
+        match *self {
+            Mask::Canvas => f64::MAX,
+            Mask::Inside(c) => c.r - (x - c.x).hypot(y - c.y),
+            Mask::Outside(c) => (x - c.x).hypot(y - c.y) - c.r,
+        }
+    }
+}
+
+pub(super) fn generate(rng: &mut impl Rng, palette: &Palette, c: &Canvas) -> Vec<Node> {
+    let gap = c.px(rng.gen_range(2.0..6.0));
+    let r_min = c.unit * 0.004;
+    let outlined = rng.gen_bool(0.3);
+    let stops = rng.gen_range(3..5);
+    let colors = pick_ramp(rng, palette, stops);
+    let mut nodes = Vec::new();
+
+    let circles = if rng.gen_bool(0.35) {
+        // A gasket in the middle, with incremental packing in the space around it
+        let outer = Circle { x: c.w / 2.0, y: c.h / 2.0, r: c.unit * rng.gen_range(0.42..0.48) };
+        let gasket = apollonian(rng, outer, r_min.max(gap));
+        let ring = c.px(rng.gen_range(2.0..4.0));
+        nodes.push(Shape::circle(outer.x, outer.y, outer.r).stroke(colors[0], ring).opacity(0.9).into());
+        let around = Circle { r: outer.r + gap, ..outer };
+        let mut circles = pack(rng, c, Mask::Outside(around), c.unit * 0.12, r_min, gap);
+        // Gasket circles touch, so they are shrunk by half the gap instead
+        circles.extend(gasket.into_iter().map(|k| Circle { r: k.r - gap / 2.0, ..k }).filter(|k| k.r > 0.0));
+        circles
+    } else {
+        let mask = if rng.gen_bool(0.25) {
+            let r = c.unit * rng.gen_range(0.35..0.46);
+            let mask = Circle { x: c.w / 2.0, y: c.h / 2.0, r };
+            nodes.push(Shape::circle(mask.x, mask.y, r).fill(colors[0]).opacity(0.12).into());
+            Mask::Inside(mask)
+        } else {
+            Mask::Canvas
+        };
+        let r_max = c.unit * rng.gen_range(0.08..0.2);
+        pack(rng, c, mask, r_max, r_min, gap)
+    };
+
+    let nested = rng.gen_bool(0.35);
+    let largest = circles.iter().fold(0.0f64, |m, k| m.max(k.r));
+    for k in circles {
+        let t = (k.r / largest).sqrt();
+        let color = if rng.gen_bool(0.5) { ramp(&colors, t) } else { pick(rng, palette) };
+        if nested && k.r > r_min * 12.0 {
+            // Outline the host and pack a finer layer inside it
+            let ring = (k.r * 0.04).max(c.px(1.5));
+            nodes.push(Shape::circle(k.x, k.y, k.r).stroke(color, ring).opacity(0.9).into());
+            let inner = Circle { r: k.r - ring - gap, ..k };
+            for child in pack(rng, c, Mask::Inside(inner), inner.r * 0.5, r_min, gap) {
+                let color = ramp(&colors, 1.0 - (child.r / inner.r).sqrt());
+                nodes.push(circle(child, color, outlined, c));
+            }
+        } else {
+            nodes.push(circle(k, color, outlined, c));
+        }
+    }
+    nodes
+}
+
+fn circle(k: Circle, color: Color, outlined: bool, c: &Canvas) -> Node {
+    let shape = Shape::circle(k.x, k.y, k.r);
+    if outlined {
+        shape.stroke(color, (k.r * 0.12).clamp(c.px(1.0), c.px(6.0))).opacity(0.9).into()
+    } else {
+        shape.fill(color).opacity(0.92).into()
+    }
+}
+
+/// Incremental packing: random points take the largest radius that fits,
+/// under a cap that shrinks from `r_max` to `r_min` over the attempts, so
+/// large circles settle first and ever smaller ones fill the gaps.
+fn pack(rng: &mut impl Rng, c: &Canvas, mask: Mask, r_max: f64, r_min: f64, gap: f64) -> Vec<Circle> {
+    let (x0, y0, x1, y1) = match mask {
+        Mask::Inside(k) => (k.x - k.r, k.y - k.r, k.x + k.r, k.y + k.r),
+        _ => (0.0, 0.0, c.w, c.h),
+    };
+    let mut grid = Buckets::new((x0, y0, x1, y1), r_max.max(r_min * 4.0) / 2.0);
+    let mut circles = Vec::new();
+    // Attempts follow the area to fill, measured in smallest circles
+    let area = (x1 - x0) * (y1 - y0) / (r_min * r_min);
+    let attempts = (area * 1.5).clamp(200.0, 120_000.0) as usize;
+
+    for i in 0..attempts {
+        if circles.len() >= MAX_CIRCLES {
+            break;
+        }
+        let cap = r_max * (r_min / r_max).powf(i as f64 / attempts as f64);
+        let (x, y) = (rng.gen_range(x0..x1), rng.gen_range(y0..y1));
+        let room = mask.room(x, y) - gap;
+        if room < r_min {
+            continue;
+        }
+        let clear = grid.clearance(&circles, x, y, cap + gap) - gap;
+        let r = clear.min(room).min(cap);
+        if r >= r_min {
+            grid.insert(circles.len(), Circle { x, y, r });
+            circles.push(Circle { x, y, r });
+        }
+    }
+    circles
+}
+
+/// Circles registered in every bucket their disk overlaps.
+struct Buckets {
+    x0: f64,
+    y0: f64,
+    size: f64,
+    cols: usize,
+    rows: usize,
+    cells: Vec<Vec<usize>>,
+}
+
+impl Buckets {
+    fn new((x0, y0, x1, y1): (f64, f64, f64, f64), size: f64) -> Buckets {
+        let cols = ((x1 - x0) / size).ceil() as usize + 1;
+        let rows = ((y1 - y0) / size).ceil() as usize + 1;
+        Buckets { x0, y0, size, cols, rows, cells: vec![Vec::new(); cols * rows] }
+    }
+
+    /// Buckets covering `lo..=hi`, measured from `origin`.
+    fn span(&self, lo: f64, hi: f64, origin: f64, n: usize) -> std::ops::Range<usize> {
+        let a = ((lo - origin) / self.size).floor().clamp(0.0, (n - 1) as f64) as usize;
+        let b = ((hi - origin) / self.size).floor().clamp(0.0, (n - 1) as f64) as usize;
+        a..b + 1
+    }
+
+    fn insert(&mut self, id: usize, k: Circle) {
+        for row in self.span(k.y - k.r, k.y + k.r, self.y0, self.rows) {
+            for col in self.span(k.x - k.r, k.x + k.r, self.x0, self.cols) {
+                self.cells[col + row * self.cols].push(id);
+            }
+        }
+    }
+
+    /// Distance from (x, y) to the nearest circle edge, or `limit` if none is
+    /// closer. Negative inside a circle.
+    fn clearance(&self, circles: &[Circle], x: f64, y: f64, limit: f64) -> f64 {
+        let mut best = limit;
+        for row in self.span(y - limit, y + limit, self.y0, self.rows) {
+            for col in self.span(x - limit, x + limit, self.x0, self.cols) {
+                for &id in &self.cells[col + row * self.cols] {
+                    let k = circles[id];
+                    best = best.min((k.x - x).hypot(k.y - y) - k.r);
+                    if best < 0.0 {
+                        return best;
+                    }
+                }
+            }
+        }
+        best
+    }
+}
+
+/// A circle by signed curvature (negative for the enclosing circle) and its
+/// center scaled by the curvature, the form Descartes' theorem is linear in.
+#[derive(Clone, Copy)]
+struct Tangent {
+    k: f64,
+    kx: f64,
+    ky: f64,
+}
+
+impl Tangent {
+    fn new(x: f64, y: f64, r: f64) -> Tangent {
+        Tangent { k: 1.0 / r, kx: x / r, ky: y / r }
+    }
+
+    /// Of the two circles tangent to `a`, `b` and `c`, the one that is not `d`.
+    fn other(a: Tangent, b: Tangent, c: Tangent, d: Tangent) -> Tangent {
+        Tangent {
+            k: 2.0 * (a.k + b.k + c.k) - d.k,
+            kx: 2.0 * (a.kx + b.kx + c.kx) - d.kx,
+            ky: 2.0 * (a.ky + b.ky + c.ky) - d.ky,
+        }
+    }
+
+    fn circle(self) -> Circle {
+        Circle { x: self.kx / self.k, y: self.ky / self.k, r: 1.0 / self.k }
+    }
+}
+
+/// An Apollonian gasket inside `outer`, down to circles of radius `r_min`.
+/// Two circles split the outer diameter; every curvilinear triangle between
+/// three tangent circles then gets the circle that fills it, recursively.
+fn apollonian(rng: &mut impl Rng, outer: Circle, r_min: f64) -> Vec<Circle> {
+    let r1 = outer.r * rng.gen_range(0.3..0.7);
+    let r2 = outer.r - r1;
+    let a = rng.gen_range(0.0..std::f64::consts::PI * 2.0);
+    let (ux, uy) = (a.cos(), a.sin());
+    let c1 = (outer.x - ux * (outer.r - r1), outer.y - uy * (outer.r - r1));
+    let c2 = (outer.x + ux * (outer.r - r2), outer.y + uy * (outer.r - r2));
+
+    // The third circle's curvature by Descartes, its two mirrored positions by
+    // intersecting the circles of possible centers around c1 and c2, which
+    // lie r1 + r2 apart
+    let (k0, k1, k2) = (-1.0 / outer.r, 1.0 / r1, 1.0 / r2);
+    let k3 = k0 + k1 + k2 + 2.0 * (k0 * k1 + k1 * k2 + k2 * k0).abs().sqrt();
+    let r3 = 1.0 / k3;
+    let (d1, d2) = (r1 + r3, r2 + r3);
+    let along = (d1 * d1 - d2 * d2 + (r1 + r2).powi(2)) / (2.0 * (r1 + r2));
+    let across = (d1 * d1 - along * along).max(0.0).sqrt();
+    let third = |side: f64| {
+        let (x, y) = (c1.0 + ux * along - uy * across * side, c1.1 + uy * along + ux * across * side);
+        Tangent::new(x, y, r3)
+    };
+
+    let t0 = Tangent { k: k0, kx: outer.x * k0, ky: outer.y * k0 };
+    let (t1, t2) = (Tangent::new(c1.0, c1.1, r1), Tangent::new(c2.0, c2.1, r2));
+    let (t3, t4) = (third(1.0), third(-1.0));
+    let mut circles: Vec<Circle> = [t1, t2, t3, t4].iter().map(|t| t.circle()).collect();
+
+    let mut stack = Vec::new();
+    for t in [t3, t4] {
+        stack.extend([(t0, t1, t, t2), (t0, t2, t, t1), (t1, t2, t, t0)]);
+    }
+    while let Some((a, b, c, d)) = stack.pop() {
+        let t = Tangent::other(a, b, c, d);
+        if 1.0 / t.k < r_min || circles.len() >= MAX_CIRCLES {
+            continue;
+        }
+        circles.push(t.circle());
+        stack.extend([(a, b, t, c), (a, c, t, b), (b, c, t, a)]);
+    }
+    circles
+}
@@ -0,0 +1,247 @@
+//! Pixel art: another style rendered onto a coarse grid, reduced to a small
+//! fixed palette and blown up with hard pixel edges.
+//!
+//! The grid cell is a whole divisor of both canvas sides whenever one is
+//! close to the chunkiness asked for, so every cell lands on whole device
+//! pixels and the upscale is exactly nearest-neighbor. Colors are reduced to
+//! the scheme's own palette or a retro one (Game Boy, CGA, PICO-8), through
+//! Floyd–Steinberg error diffusion, an ordered Bayer dither, or nearest
+//! colors alone. The result is emitted as runs of cell-high rectangles.
+
+use rand::Rng;
+
+use super::scene::{Color, Node, Shape};
+use super::{render, Canvas, Palette};
+
+/// Styles that still read at a couple of hundred cells across, weighted
+/// towards landscapes and patterns.
+const SUBJECTS: &[(&str, u32)] = &[
+    ("retroscape", 4),
+    ("cosmos", 2),
+    ("waves", 2),
+    ("tiling", 2),
+    ("aurora", 2),
+    ("sunburst", 1),
+    ("topographic", 1),
+    ("maze", 1),
+    ("lowpoly", 1),
+    ("fractal", 1),
+    ("isometric", 1),
+    ("packing", 1),
+    ("abstract", 1),
+];
+
+/// A fixed palette. Ramps (the Game Boy's four greens) are matched on
+/// lightness alone, stretched over the image's own range.
+struct Retro {
+    colors: &'static [&'static str],
+    ramp: bool,
+}
+
+const GAME_BOY: Retro = Retro { colors: &["#0f380f", "#306230", "#8bac0f", "#9bbc0f"], ramp: true };
+
+const CGA: &[Retro] = &[
+    Retro { colors: &["#000000", "#55ffff", "#ff55ff", "#ffffff"], ramp: false },
+    Retro { colors: &["#000000", "#55ff55", "#ff5555", "#ffff55"], ramp: false },
+];
+
+const PICO_8: Retro = Retro {
+    colors: &[
+        "#000000", "#1d2b53", "#7e2553", "#008751", "#ab5236", "#5f574f", "#c2c3c7", "#fff1e8", "#ff004d", "#ffa300",
+        "#ffec27", "#00e436", "#29adff", "#83769c", "#ff77a8", "#ffccaa",
+    ],
+    ramp: false,
+};
+
+/// 4x4 Bayer threshold matrix.
+const BAYER: [[f32; 4]; 4] = [[0.0, 8.0, 2.0, 10.0], [12.0, 4.0, 14.0, 6.0], [3.0, 11.0, 1.0, 9.0], [15.0, 7.0, 13.0, 5.0]];
+
+/// Per-channel weights for color distances, roughly following how much
+/// each channel contributes to perceived difference.
+const WEIGHTS: [f32; 3] = [2.0, 4.0, 3.0];
+
+/// Share of the quantization error Floyd–Steinberg passes on. Slightly
+/// under one keeps large palettes from speckling flat areas with outliers.
+const BLEED: f32 = 0.8;
+
+#[derive(Clone, Copy)]
+enum Dither {
+    FloydSteinberg,
+    Bayer,
+    Nearest,
+}
+
+pub(super) fn generate(rng: &mut impl Rng, palette: &Palette, c: &Canvas) -> Vec<Node> {
+    let total: u32 = SUBJECTS.iter().map(|(_, w)| w).sum();
+    let mut roll = rng.gen_range(0..total);
+    let subject = SUBJECTS
+        .iter()
+        .find(|(_, w)| {
+            let hit = roll < *w;
+            roll = roll.saturating_sub(*w);
+            hit
+        })
+        .map_or("retroscape", |(s, _)| s);
+
+    let cell = cell_size(c, c.unit / rng.gen_range(90.0..180.0));
+    let (cols, rows) = ((c.w / cell).ceil() as usize, (c.h / cell).ceil() as usize);
+    // Hairlines of the subject come out about half a cell wide rather than
+    // vanishing into their cells
+    let inner = Canvas { hour: c.hour, ..Canvas::new(c.w, c.h, c.density, Some(cell / 2.0)) };
+    let (nodes, _) = super::generate(subject, rng, palette, &inner);
+    let pixels = render::pixels(&nodes, Color::hex(palette.bg), cols, rows, 1.0 / cell);
+
+    let (colors, ramp) = match rng.gen_range(0..10) {
+        0..=2 => scheme(palette),
+        3..=5 => retro(&PICO_8),
+        6..=7 => retro(&GAME_BOY),
+        _ => retro(&CGA[rng.gen_range(0..CGA.len())]),
+    };
+    let dither = match rng.gen_range(0..10) {
+        0..=3 => Dither::FloydSteinberg,
+        4..=7 => Dither::Bayer,
+        _ => Dither::Nearest,
+    };
+    let indices = reduce(&pixels, cols, &colors, ramp, dither);
+    emit(&indices, &colors, cols, cell, c)
+}
+
+/// The grid cell size nearest `target`: the closest whole divisor of both
+/// canvas sides when one is within half again of it, otherwise `target`
+/// rounded, with the last row and column running off the edge.
+fn cell_size(c: &Canvas, target: f64) -> f64 {
+    let (w, h) = (c.w.round().max(1.0) as u64, c.h.round().max(1.0) as u64);
+    let (mut a, mut b) = (w, h);
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    let off = |d: u64| (d as f64 / target).ln().abs();
+    (1..=a)
+        .filter(|d| a % d == 0)
+        .min_by(|x, y| off(*x).total_cmp(&off(*y)))
+        .filter(|d| off(*d) <= 1.5f64.ln())
+        .map_or(target.round().max(1.0), |d| d as f64)
+}
+
+fn scheme(palette: &Palette) -> (Vec<Color>, bool) {
+    let mut colors = vec![Color::hex(palette.bg)];
+    for hex in palette.colors {
+        let color = Color::hex(hex);
+        if !colors.contains(&color) {
+            colors.push(color);
+        }
+    }
+    (colors, false)
+}
+
+fn retro(retro: &Retro) -> (Vec<Color>, bool) {
+    (retro.colors.iter().map(|hex| Color::hex(hex)).collect(), retro.ramp)
+}
+
+fn luma([r, g, b]: [f32; 3]) -> f32 {
+    0.2126 * r + 0.7152 * g + 0.0722 * b
+}
+
+/// Palette indices for each pixel. Matching happens in a key space: plain
+/// RGB, or for ramps a gray level per palette entry by lightness rank, with
+/// the image's lightness stretched between its 2nd and 98th percentiles.
+fn reduce(pixels: &[[f32; 4]], cols: usize, colors: &[Color], ramp: bool, dither: Dither) -> Vec<usize> {
+    let rgb = |c: &Color| [c.r as f32 / 255.0, c.g as f32 / 255.0, c.b as f32 / 255.0];
+    // The background is opaque, so premultiplied channels are straight
+    let mut keys: Vec<[f32; 3]> = pixels.iter().map(|p| [p[0], p[1], p[2]]).collect();
+    let targets: Vec<[f32; 3]> = if ramp {
+        let mut order: Vec<usize> = (0..colors.len()).collect();
+        order.sort_by(|a, b| luma(rgb(&colors[*a])).total_cmp(&luma(rgb(&colors[*b]))));
+        let mut targets = vec![[0.0; 3]; colors.len()];
+        for (rank, &i) in order.iter().enumerate() {
+            targets[i] = [rank as f32 / (colors.len() - 1).max(1) as f32; 3];
+        }
+
+        let mut lumas: Vec<f32> = keys.iter().map(|k| luma(*k)).collect();
+        lumas.sort_by(f32::total_cmp);
+        let lo = lumas[lumas.len() / 50];
+        let hi = lumas[lumas.len() - 1 - lumas.len() / 50].max(lo + 1e-3);
+        for key in &mut keys {
+            *key = [((luma(*key) - lo) / (hi - lo)).clamp(0.0, 1.0); 3];
+        }
+        targets
+    } else {
+        colors.iter().map(rgb).collect()
+    };
+
+    let nearest = |key: [f32; 3]| {
+        let distance = |t: &[f32; 3]| (0..3).map(|i| WEIGHTS[i] * (key[i] - t[i]).powi(2)).sum::<f32>();
+        (0..targets.len()).min_by(|a, b| distance(&targets[*a]).total_cmp(&distance(&targets[*b]))).unwrap_or(0)
+    };
+
+    let rows = keys.len() / cols;
+    match dither {
+        Dither::Nearest => keys.iter().map(|k| nearest(*k)).collect(),
+        Dither::Bayer => {
+            // About one palette step across the threshold range
+            let spread = if ramp { 1.0 / (colors.len() - 1).max(1) as f32 } else { 0.6 / (colors.len() as f32).cbrt() };
+            keys.iter()
+                .enumerate()
+                .map(|(i, key)| {
+                    let t = (BAYER[(i / cols) % 4][(i % cols) % 4] + 0.5) / 16.0 - 0.5;
+                    nearest(key.map(|v| v + t * spread))
+                })
+                .collect()
+        }
+        Dither::FloydSteinberg => {
+            let mut out = vec![0; keys.len()];
+            for y in 0..rows {
+                // Serpentine, so the error does not drift to one side
+                let forward = y % 2 == 0;
+                for step in 0..cols {
+                    let x = if forward { step } else { cols - 1 - step };
+                    let i = y * cols + x;
+                    let key = keys[i].map(|v| v.clamp(-0.5, 1.5));
+                    let index = nearest(key);
+                    out[i] = index;
+                    let error: [f32; 3] = std::array::from_fn(|k| (key[k] - targets[index][k]) * BLEED);
+                    let ahead = if forward { x + 1 } else { x.wrapping_sub(1) };
+                    let behind = if forward { x.wrapping_sub(1) } else { x + 1 };
+                    let mut spread = |x: usize, y: usize, weight: f32| {
+                        if x < cols && y < rows {
+                            let k = &mut keys[y * cols + x];
+                            for (v, e) in k.iter_mut().zip(error) {
+                                *v += e * weight;
+                            }
+                        }
+                    };
+                    spread(ahead, y, 7.0 / 16.0);
+                    spread(behind, y + 1, 3.0 / 16.0);
+                    spread(x, y + 1, 5.0 / 16.0);
+                    spread(ahead, y + 1, 1.0 / 16.0);
+                }
+            }
+            out
+        }
+    }
+}
+
+/// The most common color as one full-canvas rectangle, then every run of
+/// other colors along each row as a rectangle of its own.
+fn emit(indices: &[usize], colors: &[Color], cols: usize, cell: f64, c: &Canvas) -> Vec<Node> {
+    let mut counts = vec![0; colors.len()];
+    for &i in indices {
+        counts[i] += 1;
+    }
+    let common = (0..colors.len()).max_by_key(|i| counts[*i]).unwrap_or(0);
+
+    let mut nodes = vec![Shape::rect(0.0, 0.0, c.w, c.h).fill(colors[common]).into()];
+    for (y, row) in indices.chunks(cols).enumerate() {
+        let mut x = 0;
+        while x < cols {
+            let index = row[x];
+            let run = row[x..].iter().take_while(|i| **i == index).count();
+            if index != common {
+                let (left, top) = (x as f64 * cell, y as f64 * cell);
+                nodes.push(Shape::rect(left, top, run as f64 * cell, cell).fill(colors[index]).into());
+            }
+            x += run;
+        }
+    }
+    nodes
+}
@@ -0,0 +1,178 @@
+//! Offline keyword interpreter for the custom prompt in vector art mode.
+//!
+//! Maps words in the prompt to styles, color schemes, hues and mood so the
+//! same text box steers both the AI providers and the local generators,
+//! without any network call.
+
+use super::symmetry::Symmetry;
+
+/// What the prompt asked for, in terms the vector generators understand.
+#[derive(Debug, Clone, Default)]
+pub struct PromptHints {
+    /// Styles mentioned by the prompt (e.g. "ocean" → "waves"), in order of appearance.
+    pub styles: Vec<&'static str>,
+    /// Color schemes mentioned by the prompt (e.g. "80s" → "synthwave").
+    pub schemes: Vec<&'static str>,
+    /// Preferred accent hue in degrees, averaged over every color word.
+    pub hue: Option<f64>,
+    /// -1.0 (calm, sparse) ..= 1.0 (energetic, busy).
+    pub energy: f64,
+    /// -1.0 (night, deep) ..= 1.0 (bright, airy).
+    pub brightness: f64,
+    /// Time of day in hours (0.0..24.0), from the last time word in the prompt.
+    pub hour: Option<f64>,
+    /// Symmetry laid over every style, from the last symmetry word in the prompt.
+    pub symmetry: Symmetry,
+}
+
+impl PromptHints {
+    /// Multiplier applied to element counts by the generators.
+    pub fn density(&self) -> f64 {
+        if self.energy < 0.0 {
+            1.0 + self.energy * 0.5
+        } else {
+            1.0 + self.energy * 0.6
+        }
+    }
+}
+
+enum Effect {
+    Style(&'static str),
+    Scheme(&'static str),
+    Hue(f64),
+    Energy(f64),
+    Brightness(f64),
+    Hour(f64),
+    Symmetric(Symmetry),
+}
+
+use Effect::*;
+
+const KEYWORDS: &[(&[&str], &[Effect])] = &[
+    // Styles
+    (&["geometric", "geometry", "triangle", "polygon", "hexagon", "shape", "crystal"], &[Style("geometric")]),
+    (&["gradient", "smooth", "blend", "mist", "fog", "haze"], &[Style("gradient"), Energy(-0.2)]),
+    (&["minimal", "minimalist", "simple", "clean", "empty", "sparse"], &[Style("minimal"), Energy(-0.6)]),
+    (&["line", "lineart", "sketch", "wire", "wireframe", "thread", "scribble"], &[Style("lineart")]),
+    (&["isometric", "block", "cube", "3d"], &[Style("isometric")]),
+    (&["abstract", "organic", "blob", "fluid", "liquid"], &[Style("abstract")]),
+    (&["wave", "ocean", "sea", "water", "tide", "river", "lake", "surf"], &[Style("waves"), Hue(205.0)]),
+    (&["dot", "circle", "bubble", "polka", "halftone", "spot"], &[Style("dots")]),
+    (&["silk", "ribbon", "flow", "flowing", "petal", "flower", "fabric", "veil"], &[Style("silkflow")]),
+    (&["sun", "sunburst", "ray", "burst", "radiant", "sunrise", "sunset"], &[Style("sunburst")]),
+    (&["lowpoly", "poly", "facet", "faceted", "mosaic", "shard", "voronoi", "delaunay"], &[Style("lowpoly")]),
+    (&["flowfield", "streamline", "hair", "fur"], &[Style("flowfield")]),
+    (&["topographic", "topography", "topo", "contour", "terrain", "elevation"], &[Style("topographic")]),
+    (&["tiling", "truchet", "girih", "islamic", "moroccan", "arabesque"], &[Style("tiling")]),
+    (&["retroscape", "retrowave"], &[Style("retroscape")]),
+    (&["packing", "packed", "apollonian", "gasket", "foam", "pebble", "froth"], &[Style("packing")]),
+    (&["cosmos", "cosmic", "starfield", "planet", "universe", "astral", "astronomy"], &[Style("cosmos")]),
+    (&["fractal", "mandelbrot", "julia", "sierpinski", "koch", "snowflake", "fern", "recursive"], &[Style("fractal")]),
+    (&["maze", "labyrinth", "puzzle", "corridor", "wilson"], &[Style("maze")]),
+    (&["aurora", "borealis"], &[Style("aurora")]),
+    (&["pixel", "pixelart", "pixelated", "8bit", "16bit", "dither", "dithered", "gameboy", "arcade", "sprite"], &[Style("pixel")]),
+    (&["aperiodic", "penrose", "rhomb", "kite", "monotile", "quasicrystal", "nonrepeating"], &[Style("aperiodic")]),
+    (&["harmonograph", "spirograph", "pendulum", "hypotrochoid", "guilloche", "lissajous"], &[Style("harmonograph")]),
+    (&["turing", "morphogenesis", "zebra", "leopard"], &[Style("turing")]),
+    (&["skyline", "cityscape", "downtown", "skyscraper", "metropolis", "urban", "rooftop", "tower", "city", "building"], &[Style("skyline")]),
+    // Color schemes
+    (&["retro", "vintage", "70s", "seventies", "earthy"], &[Scheme("retro")]),
+    (&["neon", "glow", "glowing", "electric"], &[Scheme("neon"), Energy(0.3)]),
+    (&["cyberpunk", "cyber", "futuristic", "dystopian"], &[Scheme("cyberpunk")]),
+    (&["synthwave", "80s", "eighties", "vaporwave", "outrun"], &[Scheme("synthwave")]),
+    // Hues
+    (&["red", "crimson", "scarlet", "ruby", "blood"], &[Hue(0.0)]),
+    (&["orange", "tangerine", "copper", "rust"], &[Hue(28.0)]),
+    (&["gold", "golden", "amber", "honey", "autumn"], &[Hue(42.0)]),
+    (&["yellow", "lemon"], &[Hue(55.0)]),
+    (&["green", "emerald", "forest", "jungle", "leaf", "moss", "spring"], &[Hue(140.0)]),
+    (&["teal", "turquoise", "aqua", "lagoon"], &[Hue(175.0)]),
+    (&["cyan", "ice", "icy", "frost", "glacier", "winter", "snow"], &[Hue(195.0)]),
+    (&["blue", "azure", "sapphire", "navy", "cobalt", "sky"], &[Hue(220.0)]),
+    (&["indigo"], &[Hue(245.0)]),
+    (&["purple", "violet", "lavender", "amethyst", "galaxy", "nebula"], &[Hue(270.0)]),
+    (&["pink", "magenta", "rose", "fuchsia", "blossom"], &[Hue(325.0)]),
+    (&["fire", "flame", "lava", "volcano", "ember"], &[Hue(15.0), Energy(0.6)]),
+    (&["desert", "sand", "dune", "beach"], &[Hue(35.0), Brightness(0.3)]),
+    // Light
+    (&["night", "midnight", "moon", "moonlit"], &[Brightness(-0.8), Hour(23.5)]),
+    (&["dark", "shadow", "deep", "space"], &[Brightness(-0.8)]),
+    (&["dusk", "twilight", "evening"], &[Brightness(-0.4), Hour(18.6)]),
+    (&["dawn", "morning"], &[Brightness(0.7), Hour(5.6)]),
+    (&["day", "daylight", "sunny", "noon", "midday"], &[Brightness(0.7), Hour(13.0)]),
+    (&["bright", "light", "pastel"], &[Brightness(0.7)]),
+    // Symmetry
+    (&["mirror", "mirrored", "symmetric", "symmetrical", "symmetry"], &[Symmetric(Symmetry::MirrorHorizontal)]),
+    (&["kaleidoscope", "kaleidoscopic", "mandala", "rosette"], &[Symmetric(Symmetry::Kaleidoscope { folds: 6 })]),
+    // Energy
+    (&["calm", "serene", "peaceful", "quiet", "soft", "gentle", "zen", "still", "relaxing", "tranquil"], &[Energy(-0.7)]),
+    (&["energetic", "busy", "dense", "chaos", "chaotic", "wild", "vibrant", "intense", "storm", "explosion"], &[Energy(0.8)]),
+];
+
+/// Interpret a free-form prompt. Unknown words are ignored.
+pub fn interpret(prompt: Option<&str>) -> PromptHints {
+    let mut hints = PromptHints::default();
+    let text = match prompt {
+        Some(t) if !t.trim().is_empty() => t.to_lowercase(),
+        _ => return hints,
+    };
+
+    // Hues are averaged as unit vectors so "red and pink" lands between them
+    // instead of wrapping through green.
+    let mut hue_x = 0.0;
+    let mut hue_y = 0.0;
+
+    for word in text.split(|c: char| !c.is_alphanumeric()) {
+        let Some(effects) = lookup(word) else {
+            continue;
+        };
+        for effect in effects {
+            match *effect {
+                Style(s) => {
+                    if !hints.styles.contains(&s) {
+                        hints.styles.push(s);
+                    }
+                }
+                Scheme(s) => {
+                    if !hints.schemes.contains(&s) {
+                        hints.schemes.push(s);
+                    }
+                }
+                Hue(deg) => {
+                    let rad = deg.to_radians();
+                    hue_x += rad.cos();
+                    hue_y += rad.sin();
+                }
+                Energy(e) => hints.energy += e,
+                Brightness(b) => hints.brightness += b,
+                Hour(h) => hints.hour = Some(h),
+                Symmetric(symmetry) => hints.symmetry = symmetry,
+            }
+        }
+    }
+
+    if hue_x != 0.0 || hue_y != 0.0 {
+        hints.hue = Some(hue_y.atan2(hue_x).to_degrees().rem_euclid(360.0));
+    }
+    hints.energy = hints.energy.clamp(-1.0, 1.0);
+    hints.brightness = hints.brightness.clamp(-1.0, 1.0);
+    hints
+}
+
+fn lookup(word: &str) -> Option<&'static [Effect]> {
+    if word.is_empty() {
+        return None;
+    }
+    let find = |w: &str| {
+        KEYWORDS
+            .iter()
+            .find(|(words, _)| words.contains(&w))
+            .map(|(_, effects)| *effects)
+    };
+    // Cheap plural handling: "waves" → "wave", "bubbles" → "bubble"
+    find(word).or_else(|| {
+        word.strip_suffix('s')
+            .filter(|stem| stem.len() > 2)
+            .and_then(find)
+    })
+}
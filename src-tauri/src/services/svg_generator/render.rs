@@ -0,0 +1,852 @@
+//! Direct rasterization of a `Scene`, without an SVG round trip.
+//!
+//! tiny-skia supplies anti-aliased coverage masks; paints are evaluated and
+//! composited in floating point so smooth gradients keep more than 8 bits of
+//! precision until the final quantization.
+
+use std::f64::consts::LN_2;
+use std::path::PathBuf;
+
+use resvg::tiny_skia;
+
+use super::scene::{
+    Color, EscapeTime, Geometry, GradientStop, LineCap, Node, NoiseTexture, Paint, ScalarField, Scene,
+    Segment, Shape, Transform,
+};
+use crate::error::AppError;
+use crate::services::noise;
+use crate::services::raster::{self, EffectChain, OutputOptions, Tile};
+
+/// Rasterize `scene` to a PNG at `width`x`height`, streaming rows into the
+/// encoder as strips finish. `progress` is called with (strips done, total).
+pub fn rasterize(
+    scene: &Scene,
+    width: u32,
+    height: u32,
+    output_path: &PathBuf,
+    options: &OutputOptions,
+    effects: &EffectChain,
+    progress: &(dyn Fn(u32, u32) + Sync),
+) -> Result<(), AppError> {
+    let sx = width as f64 / scene.width;
+    let sy = height as f64 / scene.height;
+    // Symmetric layers only need the nodes that reach into their domain
+    let layers: Vec<(Vec<&Node>, Option<Cut>)> = scene
+        .layers
+        .iter()
+        .map(|layer| match layer.symmetry.orbit(scene.width, scene.height) {
+            Some(orbit) => {
+                let domain = polygon(&orbit.domain);
+                let reach = domain.as_ref().map(|d| d.bounds());
+                let nodes = layer.nodes.iter().filter(|n| reach.is_some_and(|r| reaches(n, r))).collect();
+                (nodes, domain.map(|domain| Cut { domain, copies: orbit.copies }))
+            }
+            None => (layer.nodes.iter().collect(), None),
+        })
+        .collect();
+
+    raster::write_png(
+        output_path,
+        width,
+        height,
+        options,
+        effects,
+        |top, rows| {
+            let mut tile = Tile::filled(width, rows, top, premultiply(scene.background, 1.0));
+            let base = Transform::translate(0.0, -(top as f64)).then(Transform::scale(sx, sy));
+            for (nodes, cut) in &layers {
+                match cut {
+                    Some(cut) => {
+                        let mut layer = Tile::filled(width, rows, top, [0.0; 4]);
+                        let mut weights = vec![0.0; layer.pixels.len()];
+                        for copy in &cut.copies {
+                            render_copy(nodes, &cut.domain, &mut layer, &mut weights, base.then(*copy));
+                        }
+                        for ((dst, src), weight) in tile.pixels.iter_mut().zip(&layer.pixels).zip(weights) {
+                            if weight > 0.0 {
+                                blend(dst, src.map(|v| v / weight));
+                            }
+                        }
+                    }
+                    None => {
+                        for node in nodes {
+                            render_node(node, &mut tile, base);
+                        }
+                    }
+                }
+            }
+            Ok(tile)
+        },
+        progress,
+    )
+}
+
+/// Render `nodes` over `background` into a `width`x`height` buffer of
+/// premultiplied RGBA, at `scale` buffer pixels per scene unit. For styles
+/// that work on pixels of their own before emitting a scene.
+pub(super) fn pixels(nodes: &[Node], background: Color, width: usize, height: usize, scale: f64) -> Vec<[f32; 4]> {
+    let mut tile = Tile::filled(width as u32, height as u32, 0, premultiply(background, 1.0));
+    for node in nodes {
+        render_node(node, &mut tile, Transform::scale(scale, scale));
+    }
+    tile.pixels
+}
+
+fn render_node(node: &Node, tile: &mut Tile, base: Transform) {
+    match node {
+        Node::Shape(shape) => render_shape(shape, tile, base),
+        Node::Group(group) => {
+            let ts = base.then(group.transform);
+            if group.opacity >= 1.0 {
+                for child in &group.children {
+                    render_node(child, tile, ts);
+                }
+                return;
+            }
+
+            // Group opacity applies to the composited result, so render into a layer
+            let mut layer = Tile::filled(tile.width, tile.height, tile.top, [0.0; 4]);
+            for child in &group.children {
+                render_node(child, &mut layer, ts);
+            }
+            let opacity = group.opacity.clamp(0.0, 1.0) as f32;
+            for (dst, src) in tile.pixels.iter_mut().zip(&layer.pixels) {
+                blend(dst, src.map(|c| c * opacity));
+            }
+        }
+    }
+}
+
+/// A symmetric layer's domain, in the layer's coordinates, and the
+/// transforms placing each copy of it.
+struct Cut {
+    domain: tiny_skia::Path,
+    copies: Vec<Transform>,
+}
+
+/// Adds `nodes`, placed by `ts`, to `layer` through the coverage of
+/// `domain`, and that coverage to `weights`. Copies of one domain partition
+/// the canvas, so dividing the sum by the weights blends copies along their
+/// shared edges, where compositing them would leave a seam. Only the pixels
+/// under the domain are drawn, into a buffer of their own.
+fn render_copy(nodes: &[&Node], domain: &tiny_skia::Path, layer: &mut Tile, weights: &mut [f32], ts: Transform) {
+    let Some(device) = domain.clone().transform(to_skia_transform(ts)) else {
+        return;
+    };
+    let bounds = device.bounds();
+    let x0 = (bounds.left().floor() as i64).max(0);
+    let y0 = (bounds.top().floor() as i64).max(0);
+    let x1 = (bounds.right().ceil() as i64).min(layer.width as i64);
+    let y1 = (bounds.bottom().ceil() as i64).min(layer.height as i64);
+    if x0 >= x1 || y0 >= y1 {
+        return;
+    }
+
+    let (width, height) = ((x1 - x0) as u32, (y1 - y0) as u32);
+    let Some(mut mask) = tiny_skia::Mask::new(width, height) else {
+        return;
+    };
+    mask.fill_path(
+        &device,
+        tiny_skia::FillRule::Winding,
+        true,
+        tiny_skia::Transform::from_translate(-x0 as f32, -y0 as f32),
+    );
+    let mut part = Tile::filled(width, height, layer.top + y0 as u32, [0.0; 4]);
+    let local = Transform::translate(-x0 as f64, -y0 as f64).then(ts);
+    for node in nodes {
+        render_node(node, &mut part, local);
+    }
+
+    for (row, (pixels, coverage)) in
+        part.pixels.chunks_exact(width as usize).zip(mask.data().chunks_exact(width as usize)).enumerate()
+    {
+        let start = (y0 as usize + row) * layer.width as usize + x0 as usize;
+        for (col, (src, &cov)) in pixels.iter().zip(coverage).enumerate() {
+            let c = cov as f32 / 255.0;
+            weights[start + col] += c;
+            for (d, s) in layer.pixels[start + col].iter_mut().zip(src) {
+                *d += s * c;
+            }
+        }
+    }
+}
+
+/// Whether `node` can leave paint inside `rect`, its stroke and blur included.
+/// Groups are always assumed to.
+fn reaches(node: &Node, rect: tiny_skia::Rect) -> bool {
+    let Node::Shape(shape) = node else {
+        return true;
+    };
+    let Some(path) = build_path(&shape.geometry).and_then(|p| p.transform(to_skia_transform(shape.transform)))
+    else {
+        return false;
+    };
+    let b = path.bounds();
+    let scale = (shape.transform.a * shape.transform.d - shape.transform.b * shape.transform.c).abs().sqrt();
+    let margin = (shape.stroke.as_ref().map_or(0.0, |s| s.width * scale) + shape.blur.max(0.0) * 3.0) as f32 + 1.0;
+    b.left() - margin < rect.right()
+        && b.right() + margin > rect.left()
+        && b.top() - margin < rect.bottom()
+        && b.bottom() + margin > rect.top()
+}
+
+fn render_shape(shape: &Shape, tile: &mut Tile, base: Transform) {
+    let Some(path) = build_path(&shape.geometry) else {
+        return;
+    };
+    let ts = base.then(shape.transform);
+    let opacity = shape.opacity.clamp(0.0, 1.0);
+    // The blur is given in the units the shape is placed in
+    let blur = shape.blur.max(0.0) * (base.a * base.d - base.b * base.c).abs().sqrt();
+
+    // A line encloses no area, so SVG never fills it
+    if !matches!(shape.geometry, Geometry::Line { .. }) {
+        if let Some(source) = Source::new(&shape.fill, path.bounds(), ts, opacity) {
+            fill(tile, &path, ts, &source, blur);
+        }
+    }
+
+    if let Some(stroke) = &shape.stroke {
+        let style = tiny_skia::Stroke {
+            width: stroke.width as f32,
+            line_cap: match stroke.cap {
+                LineCap::Butt => tiny_skia::LineCap::Butt,
+                LineCap::Round => tiny_skia::LineCap::Round,
+            },
+            ..Default::default()
+        };
+        let res_scale = tiny_skia::PathStroker::compute_resolution_scale(&to_skia_transform(ts));
+        if let Some(outline) = path.stroke(&style, res_scale) {
+            fill(tile, &outline, ts, &Source::Solid(premultiply(stroke.color, opacity)), blur);
+        }
+    }
+}
+
+/// Composite `source` through the anti-aliased coverage of `path`, blurred
+/// by a Gaussian of `blur` device pixels. The mask only spans the path's
+/// bounds within the tile.
+fn fill(tile: &mut Tile, path: &tiny_skia::Path, ts: Transform, source: &Source, blur: f64) {
+    let Some(device) = path.clone().transform(to_skia_transform(ts)) else {
+        return;
+    };
+    if blur > 0.0 {
+        fill_blurred(tile, &device, source, blur);
+        return;
+    }
+    let bounds = device.bounds();
+    let x0 = (bounds.left().floor() as i64).max(0);
+    let y0 = (bounds.top().floor() as i64).max(0);
+    let x1 = (bounds.right().ceil() as i64 + 1).min(tile.width as i64);
+    let y1 = (bounds.bottom().ceil() as i64 + 1).min(tile.height as i64);
+    if x0 >= x1 || y0 >= y1 {
+        return;
+    }
+
+    let mask_width = (x1 - x0) as usize;
+    let Some(mut mask) = tiny_skia::Mask::new(mask_width as u32, (y1 - y0) as u32) else {
+        return;
+    };
+    mask.fill_path(
+        &device,
+        tiny_skia::FillRule::Winding,
+        true,
+        tiny_skia::Transform::from_translate(-x0 as f32, -y0 as f32),
+    );
+
+    let lattice = Lattice::new(source, (x0, y0, x1, y1));
+    for (row, coverage) in mask.data().chunks_exact(mask_width).enumerate() {
+        let y = y0 as usize + row;
+        let start = y * tile.width as usize + x0 as usize;
+        for (col, &cov) in coverage.iter().enumerate() {
+            if cov == 0 {
+                continue;
+            }
+            let x = x0 as usize + col;
+            let c = cov as f32 / 255.0;
+            let src = match &lattice {
+                Some(lattice) => source.shade(lattice.at(col, row)),
+                None => source.sample(x as f64 + 0.5, y as f64 + 0.5),
+            };
+            blend(&mut tile.pixels[start + col], src.map(|v| v * c));
+        }
+    }
+}
+
+/// `fill` through coverage blurred by a Gaussian of `sigma` pixels. The
+/// mask spans everything within three sigma of the tile, in cells of up to
+/// a quarter sigma, and is upsampled bilinearly: blurred coverage has no
+/// detail a coarser mask would lose, and wide blurs stay cheap per strip.
+fn fill_blurred(tile: &mut Tile, device: &tiny_skia::Path, source: &Source, sigma: f64) {
+    let reach = (sigma * 3.0).ceil();
+    let (w, h) = (tile.width as f64, tile.height as f64);
+    // Coverage that can reach the tile, then the tile pixels it reaches
+    let b = device.bounds();
+    let (sx0, sy0) = ((b.left() as f64).max(-reach).floor(), (b.top() as f64).max(-reach).floor());
+    let (sx1, sy1) = ((b.right() as f64).min(w + reach).ceil(), (b.bottom() as f64).min(h + reach).ceil());
+    let (x0, y0) = ((sx0 - reach).max(0.0) as usize, (sy0 - reach).max(0.0) as usize);
+    let (x1, y1) = ((sx1 + reach).min(w).max(0.0) as usize, (sy1 + reach).min(h).max(0.0) as usize);
+    if sx0 >= sx1 || sy0 >= sy1 || x0 >= x1 || y0 >= y1 {
+        return;
+    }
+
+    let step = (sigma / 4.0).floor().max(1.0);
+    let (gx, gy) = (sx0 - reach, sy0 - reach);
+    let cols = ((sx1 - sx0 + reach * 2.0) / step).ceil() as usize + 1;
+    let rows = ((sy1 - sy0 + reach * 2.0) / step).ceil() as usize + 1;
+    let Some(mut mask) = tiny_skia::Mask::new(cols as u32, rows as u32) else {
+        return;
+    };
+    let to_mask = tiny_skia::Transform::from_translate(-gx as f32, -gy as f32)
+        .post_scale(1.0 / step as f32, 1.0 / step as f32);
+    mask.fill_path(device, tiny_skia::FillRule::Winding, true, to_mask);
+    let mut coverage: Vec<f32> = mask.data().iter().map(|&v| v as f32 / 255.0).collect();
+    gaussian(&mut coverage, cols, sigma / step);
+
+    // Mask coordinates of a pixel center: cell index and fraction towards the next
+    let cell = |p: usize, origin: f64, last: usize| {
+        let v = ((p as f64 + 0.5 - origin) / step - 0.5).clamp(0.0, last as f64);
+        let i = (v.floor() as usize).min(last.saturating_sub(1));
+        (i, (v - i as f64) as f32)
+    };
+    let lattice = Lattice::new(source, (x0 as i64, y0 as i64, x1 as i64, y1 as i64));
+    for y in y0..y1 {
+        let (r, fy) = cell(y, gy, rows - 1);
+        let (above, below) = (&coverage[r * cols..], &coverage[(r + 1).min(rows - 1) * cols..]);
+        for x in x0..x1 {
+            let (c, fx) = cell(x, gx, cols - 1);
+            let c1 = (c + 1).min(cols - 1);
+            let top = above[c] + (above[c1] - above[c]) * fx;
+            let bottom = below[c] + (below[c1] - below[c]) * fx;
+            let cov = top + (bottom - top) * fy;
+            if cov < 1.0 / 1024.0 {
+                continue;
+            }
+            let src = match &lattice {
+                Some(lattice) => source.shade(lattice.at(x - x0, y - y0)),
+                None => source.sample(x as f64 + 0.5, y as f64 + 0.5),
+            };
+            blend(&mut tile.pixels[y * tile.width as usize + x], src.map(|v| v * cov));
+        }
+    }
+}
+
+/// Separable Gaussian blur of a row-major buffer `cols` wide, treating
+/// everything outside it as zero.
+fn gaussian(values: &mut [f32], cols: usize, sigma: f64) {
+    let radius = (sigma * 3.0).ceil() as usize;
+    let kernel: Vec<f32> = (0..=radius * 2)
+        .map(|i| (-((i as f64 - radius as f64).powi(2)) / (2.0 * sigma * sigma)).exp() as f32)
+        .collect();
+    let total: f32 = kernel.iter().sum();
+    let kernel: Vec<f32> = kernel.iter().map(|k| k / total).collect();
+    let rows = values.len() / cols;
+
+    let mut pass = vec![0.0; values.len()];
+    for row in 0..rows {
+        let line = &values[row * cols..(row + 1) * cols];
+        for (x, out) in pass[row * cols..(row + 1) * cols].iter_mut().enumerate() {
+            let lo = x.saturating_sub(radius);
+            let hi = (x + radius).min(cols - 1);
+            *out = (lo..=hi).map(|i| line[i] * kernel[i + radius - x]).sum();
+        }
+    }
+    for x in 0..cols {
+        for y in 0..rows {
+            let lo = y.saturating_sub(radius);
+            let hi = (y + radius).min(rows - 1);
+            values[y * cols + x] = (lo..=hi).map(|i| pass[i * cols + x] * kernel[i + radius - y]).sum();
+        }
+    }
+}
+
+/// Source-over with premultiplied colors.
+fn blend(dst: &mut [f32; 4], src: [f32; 4]) {
+    let keep = 1.0 - src[3];
+    for (d, s) in dst.iter_mut().zip(src) {
+        *d = s + *d * keep;
+    }
+}
+
+/// A fill evaluated per pixel, in tile coordinates.
+enum Source {
+    Solid([f32; 4]),
+    Gradient {
+        /// Maps tile pixels into the gradient's bounding-box units.
+        inverse: Transform,
+        shape: GradientShape,
+        /// Offsets with straight-alpha RGBA in 0.0..=1.0.
+        stops: Vec<(f64, [f64; 4])>,
+        opacity: f64,
+    },
+    Noise {
+        /// Maps tile pixels back into user space, where the texture is laid out.
+        inverse: Transform,
+        seed: u64,
+        scale: f64,
+        octaves: u32,
+        warp: f64,
+        stops: Vec<(f64, [f64; 4])>,
+        opacity: f64,
+    },
+    EscapeTime {
+        /// Maps tile pixels back into user space, where the set is laid out.
+        inverse: Transform,
+        julia: Option<(f64, f64)>,
+        origin: (f64, f64),
+        step: f64,
+        iterations: u32,
+        cycle: f64,
+        stops: Vec<(f64, [f64; 4])>,
+        inside: [f32; 4],
+        opacity: f64,
+    },
+    Field {
+        /// Maps tile pixels into grid coordinates, with value `i` centered on `i`.
+        inverse: Transform,
+        cols: usize,
+        rows: usize,
+        values: Vec<f32>,
+        wrap: bool,
+        stops: Vec<(f64, [f64; 4])>,
+        opacity: f64,
+    },
+}
+
+enum GradientShape {
+    Linear { x1: f64, y1: f64, dx: f64, dy: f64 },
+    Radial { cx: f64, cy: f64, r: f64 },
+}
+
+impl Source {
+    /// Gradients are laid out in the path's bounding box, as with SVG's
+    /// default `objectBoundingBox` units.
+    fn new(paint: &Paint, bounds: tiny_skia::Rect, ts: Transform, opacity: f64) -> Option<Source> {
+        let (shape, stops) = match paint {
+            Paint::None => return None,
+            Paint::Solid(color) => return Some(Source::Solid(premultiply(*color, opacity))),
+            Paint::Linear(g) => (
+                GradientShape::Linear { x1: g.x1, y1: g.y1, dx: g.x2 - g.x1, dy: g.y2 - g.y1 },
+                &g.stops,
+            ),
+            Paint::Radial(g) => (GradientShape::Radial { cx: g.cx, cy: g.cy, r: g.r }, &g.stops),
+            Paint::Noise(n) => return Source::noise(n, ts, opacity),
+            Paint::EscapeTime(e) => return Source::escape_time(e, ts, opacity),
+            Paint::Field(f) => return Source::scalar_field(f, ts, opacity),
+        };
+
+        let degenerate = stops.len() < 2
+            || match shape {
+                GradientShape::Linear { dx, dy, .. } => dx == 0.0 && dy == 0.0,
+                GradientShape::Radial { r, .. } => r <= 0.0,
+            };
+        let bbox = Transform {
+            a: bounds.width() as f64,
+            b: 0.0,
+            c: 0.0,
+            d: bounds.height() as f64,
+            e: bounds.x() as f64,
+            f: bounds.y() as f64,
+        };
+
+        match invert(ts.then(bbox)) {
+            Some(inverse) if !degenerate => Some(Source::Gradient {
+                inverse,
+                shape,
+                stops: stops.iter().map(|s| (s.offset, straight(s))).collect(),
+                opacity,
+            }),
+            // Degenerate gradients (one stop, zero-size box) paint their first stop
+            _ => stops
+                .first()
+                .map(|s| Source::Solid(premultiply(s.color, s.opacity * opacity))),
+        }
+    }
+
+    fn noise(texture: &NoiseTexture, ts: Transform, opacity: f64) -> Option<Source> {
+        let first = texture.stops.first()?;
+        match invert(ts) {
+            Some(inverse) if texture.stops.len() >= 2 && texture.scale > 0.0 => Some(Source::Noise {
+                inverse,
+                seed: texture.seed as u64,
+                scale: texture.scale,
+                // Finer octaves than a pixel only cost time
+                octaves: texture.octaves.clamp(1, 8),
+                warp: texture.warp,
+                stops: texture.stops.iter().map(|s| (s.offset, straight(s))).collect(),
+                opacity,
+            }),
+            _ => Some(Source::Solid(premultiply(first.color, first.opacity * opacity))),
+        }
+    }
+
+    fn escape_time(set: &EscapeTime, ts: Transform, opacity: f64) -> Option<Source> {
+        let inside = premultiply(set.inside, opacity);
+        match invert(ts) {
+            Some(inverse) if !set.stops.is_empty() && set.cycle > 0.0 => Some(Source::EscapeTime {
+                inverse,
+                julia: set.julia.map(|[re, im]| (re, im)),
+                origin: (set.origin[0], set.origin[1]),
+                step: set.step,
+                iterations: set.iterations.min(MAX_ITERATIONS),
+                cycle: set.cycle,
+                stops: set.stops.iter().map(|s| (s.offset, straight(s))).collect(),
+                inside,
+                opacity,
+            }),
+            _ => Some(Source::Solid(inside)),
+        }
+    }
+
+    fn scalar_field(field: &ScalarField, ts: Transform, opacity: f64) -> Option<Source> {
+        let first = field.stops.first()?;
+        let (cols, rows) = (field.cols as f64, field.rows as f64);
+        let grid = Transform {
+            a: cols / field.width,
+            b: 0.0,
+            c: 0.0,
+            d: rows / field.height,
+            e: -field.x * cols / field.width - 0.5,
+            f: -field.y * rows / field.height - 0.5,
+        };
+        let inverse = invert(ts).filter(|_| field.width > 0.0 && field.height > 0.0);
+        match (inverse, field.levels()) {
+            (Some(inverse), Some(values)) => Some(Source::Field {
+                inverse: grid.then(inverse),
+                cols: field.cols as usize,
+                rows: field.rows as usize,
+                values,
+                wrap: field.wrap,
+                stops: field.stops.iter().map(|s| (s.offset, straight(s))).collect(),
+                opacity,
+            }),
+            _ => Some(Source::Solid(premultiply(first.color, first.opacity * opacity))),
+        }
+    }
+
+    fn sample(&self, x: f64, y: f64) -> [f32; 4] {
+        match self {
+            Source::Solid(color) => *color,
+            Source::Gradient { inverse, shape, stops, opacity } => {
+                let u = inverse.a * x + inverse.c * y + inverse.e;
+                let v = inverse.b * x + inverse.d * y + inverse.f;
+                let t = match *shape {
+                    GradientShape::Linear { x1, y1, dx, dy } => {
+                        ((u - x1) * dx + (v - y1) * dy) / (dx * dx + dy * dy)
+                    }
+                    GradientShape::Radial { cx, cy, r } => (u - cx).hypot(v - cy) / r,
+                };
+                let [r, g, b, a] = interpolate(stops, t.clamp(0.0, 1.0));
+                let a = a * opacity;
+                [(r * a) as f32, (g * a) as f32, (b * a) as f32, a as f32]
+            }
+            Source::Noise { .. } => self.shade(self.field(x, y)),
+            Source::Field { inverse, cols, rows, values, wrap, stops, opacity } => {
+                let u = inverse.a * x + inverse.c * y + inverse.e;
+                let v = inverse.b * x + inverse.d * y + inverse.f;
+                let [r, g, b, a] = interpolate(stops, bicubic(values, *cols, *rows, *wrap, u, v));
+                let a = a * opacity;
+                [(r * a) as f32, (g * a) as f32, (b * a) as f32, a as f32]
+            }
+            Source::EscapeTime { inverse, julia, origin, step, iterations, cycle, stops, inside, opacity } => {
+                let at = |x: f64, y: f64| {
+                    let u = inverse.a * x + inverse.c * y + inverse.e;
+                    let v = inverse.b * x + inverse.d * y + inverse.f;
+                    escape(*julia, (origin.0 + u * step, origin.1 + v * step), *iterations)
+                };
+                let shade = |count: Option<f64>| match count {
+                    Some(count) => {
+                        let [r, g, b, a] = interpolate(stops, (count / cycle).rem_euclid(1.0));
+                        let a = a * opacity;
+                        [(r * a) as f32, (g * a) as f32, (b * a) as f32, a as f32]
+                    }
+                    None => *inside,
+                };
+                let count = at(x, y);
+                // Past the first cycle the colors can change faster than the
+                // pixels, so those pixels are averaged over a rotated grid
+                match count {
+                    Some(n) if n > *cycle => {
+                        const GRID: [(f64, f64); 4] = [(-0.125, -0.375), (0.375, -0.125), (0.125, 0.375), (-0.375, 0.125)];
+                        let mut sum = [0.0f32; 4];
+                        for (dx, dy) in GRID {
+                            let color = shade(at(x + dx, y + dy));
+                            for (s, c) in sum.iter_mut().zip(color) {
+                                *s += c / 4.0;
+                            }
+                        }
+                        sum
+                    }
+                    _ => shade(count),
+                }
+            }
+        }
+    }
+
+    /// The noise value at a tile pixel, 0.5 on average. Zero for other sources.
+    fn field(&self, x: f64, y: f64) -> f64 {
+        let Source::Noise { inverse, seed, scale, octaves, warp, .. } = self else {
+            return 0.0;
+        };
+        let u = (inverse.a * x + inverse.c * y + inverse.e) / scale;
+        let v = (inverse.b * x + inverse.d * y + inverse.f) / scale;
+        let (du, dv) = if *warp > 0.0 {
+            (
+                noise::perlin(seed ^ 0x5eed_0001, u, v) * warp,
+                noise::perlin(seed ^ 0x5eed_0002, u, v) * warp,
+            )
+        } else {
+            (0.0, 0.0)
+        };
+        0.5 + noise::perlin_fbm(*seed, u + du, v + dv, *octaves)
+    }
+
+    /// A noise value mapped through the stops, premultiplied.
+    fn shade(&self, t: f64) -> [f32; 4] {
+        let Source::Noise { stops, opacity, .. } = self else {
+            return [0.0; 4];
+        };
+        let [r, g, b, a] = interpolate(stops, t.clamp(0.0, 1.0));
+        let a = a * opacity;
+        [(r * a) as f32, (g * a) as f32, (b * a) as f32, a as f32]
+    }
+}
+
+/// Keeps a single escape-time fill from running away at 4K.
+const MAX_ITERATIONS: u32 = 1000;
+
+/// Smooth escape count of z ↦ z² + c, or `None` if the orbit stays bounded
+/// for `iterations` steps. The Mandelbrot set starts from z = 0 with c at
+/// `point`; a Julia set starts from z at `point` with its constant c.
+fn escape(julia: Option<(f64, f64)>, point: (f64, f64), iterations: u32) -> Option<f64> {
+    const BAILOUT: f64 = 256.0 * 256.0;
+    let ((mut zr, mut zi), (cr, ci)) = match julia {
+        Some(c) => (point, c),
+        None => {
+            // The main cardioid and the period-2 bulb hold most of the interior
+            let (x, y) = point;
+            let q = (x - 0.25).powi(2) + y * y;
+            if q * (q + x - 0.25) <= 0.25 * y * y || (x + 1.0).powi(2) + y * y <= 0.0625 {
+                return None;
+            }
+            ((0.0, 0.0), point)
+        }
+    };
+
+    // An orbit that comes back to a saved point is periodic, so it never
+    // escapes; saving at doubling intervals catches cycles of any length
+    let (mut saved, mut save_at) = ((zr, zi), 8);
+    for n in 0..iterations {
+        let (zr2, zi2) = (zr * zr, zi * zi);
+        if zr2 + zi2 > BAILOUT {
+            let log_modulus = (zr2 + zi2).ln() / 2.0;
+            return Some(n as f64 + 1.0 - (log_modulus / LN_2).ln() / LN_2);
+        }
+        zi = 2.0 * zr * zi + ci;
+        zr = zr2 - zi2 + cr;
+        if (zr - saved.0).abs() < 1e-13 && (zi - saved.1).abs() < 1e-13 {
+            return None;
+        }
+        if n == save_at {
+            saved = (zr, zi);
+            save_at *= 2;
+        }
+    }
+    None
+}
+
+/// Noise values sampled every `step` pixels over a fill's bounds and
+/// interpolated in between. The finest octave still spans several lattice
+/// cells, so this looks the same as per-pixel evaluation at a fraction of
+/// the cost, which full-canvas textures would otherwise dominate.
+struct Lattice {
+    step: usize,
+    cols: usize,
+    values: Vec<f64>,
+}
+
+impl Lattice {
+    /// Only for noise sources coarse enough to benefit.
+    fn new(source: &Source, (x0, y0, x1, y1): (i64, i64, i64, i64)) -> Option<Lattice> {
+        let Source::Noise { inverse, scale, octaves, .. } = source else {
+            return None;
+        };
+        // Wavelength of the finest octave in pixels, over a sixth of which
+        // bilinear interpolation stays within a few percent
+        let user_per_pixel = (inverse.a * inverse.d - inverse.b * inverse.c).abs().sqrt();
+        let finest = scale / user_per_pixel / (1u64 << (octaves - 1)) as f64;
+        let step = (finest / 6.0).floor().min(8.0) as usize;
+        if step < 2 {
+            return None;
+        }
+        let cols = (x1 - x0) as usize / step + 2;
+        let rows = (y1 - y0) as usize / step + 2;
+        let mut values = Vec::with_capacity(cols * rows);
+        for row in 0..rows {
+            for col in 0..cols {
+                let (x, y) = (x0 as f64 + (col * step) as f64 + 0.5, y0 as f64 + (row * step) as f64 + 0.5);
+                values.push(source.field(x, y));
+            }
+        }
+        Some(Lattice { step, cols, values })
+    }
+
+    /// The interpolated value at an offset from the bounds' top-left pixel.
+    fn at(&self, col: usize, row: usize) -> f64 {
+        let (cx, cy) = (col / self.step, row / self.step);
+        let fx = (col % self.step) as f64 / self.step as f64;
+        let fy = (row % self.step) as f64 / self.step as f64;
+        let i = cx + cy * self.cols;
+        let top = self.values[i] + (self.values[i + 1] - self.values[i]) * fx;
+        let bottom = self.values[i + self.cols] + (self.values[i + self.cols + 1] - self.values[i + self.cols]) * fx;
+        top + (bottom - top) * fy
+    }
+}
+
+/// Catmull-Rom interpolation of a row-major grid at `(x, y)` in grid
+/// coordinates, repeating the edge values outwards, or the whole grid when
+/// it `wrap`s.
+fn bicubic(values: &[f32], cols: usize, rows: usize, wrap: bool, x: f64, y: f64) -> f64 {
+    let (x0, y0) = (x.floor(), y.floor());
+    let weights = |t: f64| {
+        [
+            ((-0.5 * t + 1.0) * t - 0.5) * t,
+            (1.5 * t - 2.5) * t * t + 1.0,
+            ((-1.5 * t + 2.0) * t + 0.5) * t,
+            (0.5 * t - 0.5) * t * t,
+        ]
+    };
+    let (wx, wy) = (weights(x - x0), weights(y - y0));
+    let index = |i: f64, n: usize| {
+        if wrap {
+            i.rem_euclid(n as f64) as usize
+        } else {
+            i.clamp(0.0, (n - 1) as f64) as usize
+        }
+    };
+    let mut sum = 0.0;
+    for (j, wy) in wy.iter().enumerate() {
+        let row = index(y0 - 1.0 + j as f64, rows) * cols;
+        let line: f64 = wx
+            .iter()
+            .enumerate()
+            .map(|(i, wx)| wx * values[row + index(x0 - 1.0 + i as f64, cols)] as f64)
+            .sum();
+        sum += wy * line;
+    }
+    sum
+}
+
+fn straight(stop: &GradientStop) -> [f64; 4] {
+    let c = stop.color;
+    [
+        c.r as f64 / 255.0,
+        c.g as f64 / 255.0,
+        c.b as f64 / 255.0,
+        stop.opacity.clamp(0.0, 1.0),
+    ]
+}
+
+/// Color at `t` along the stops, padding past either end.
+fn interpolate(stops: &[(f64, [f64; 4])], t: f64) -> [f64; 4] {
+    let (first, last) = (stops[0], stops[stops.len() - 1]);
+    if t <= first.0 {
+        return first.1;
+    }
+    for pair in stops.windows(2) {
+        let ((o0, c0), (o1, c1)) = (pair[0], pair[1]);
+        if t <= o1 {
+            let f = if o1 > o0 { (t - o0) / (o1 - o0) } else { 1.0 };
+            return [0, 1, 2, 3].map(|i| c0[i] + (c1[i] - c0[i]) * f);
+        }
+    }
+    last.1
+}
+
+fn build_path(geometry: &Geometry) -> Option<tiny_skia::Path> {
+    match geometry {
+        Geometry::Rect { x, y, width, height } => {
+            let rect = tiny_skia::Rect::from_xywh(*x as f32, *y as f32, *width as f32, *height as f32)?;
+            Some(tiny_skia::PathBuilder::from_rect(rect))
+        }
+        Geometry::Circle { cx, cy, r } => {
+            tiny_skia::PathBuilder::from_circle(*cx as f32, *cy as f32, *r as f32)
+        }
+        Geometry::Ellipse { cx, cy, rx, ry } => {
+            let rect = tiny_skia::Rect::from_xywh(
+                (cx - rx) as f32,
+                (cy - ry) as f32,
+                (rx * 2.0) as f32,
+                (ry * 2.0) as f32,
+            )?;
+            tiny_skia::PathBuilder::from_oval(rect)
+        }
+        Geometry::Line { x1, y1, x2, y2 } => {
+            let mut pb = tiny_skia::PathBuilder::new();
+            pb.move_to(*x1 as f32, *y1 as f32);
+            pb.line_to(*x2 as f32, *y2 as f32);
+            pb.finish()
+        }
+        Geometry::Polygon { points } => {
+            let mut pb = tiny_skia::PathBuilder::new();
+            for (i, &(x, y)) in points.iter().enumerate() {
+                if i == 0 {
+                    pb.move_to(x as f32, y as f32);
+                } else {
+                    pb.line_to(x as f32, y as f32);
+                }
+            }
+            pb.close();
+            pb.finish()
+        }
+        Geometry::Path { segments } => {
+            let mut pb = tiny_skia::PathBuilder::new();
+            for seg in segments {
+                match *seg {
+                    Segment::MoveTo(x, y) => pb.move_to(x as f32, y as f32),
+                    Segment::LineTo(x, y) => pb.line_to(x as f32, y as f32),
+                    Segment::CubicTo(x1, y1, x2, y2, x, y) => pb.cubic_to(
+                        x1 as f32, y1 as f32, x2 as f32, y2 as f32, x as f32, y as f32,
+                    ),
+                    Segment::Close => pb.close(),
+                }
+            }
+            pb.finish()
+        }
+    }
+}
+
+fn polygon(points: &[(f64, f64)]) -> Option<tiny_skia::Path> {
+    build_path(&Geometry::Polygon { points: points.to_vec() })
+}
+
+fn premultiply(color: Color, alpha: f64) -> [f32; 4] {
+    let a = alpha.clamp(0.0, 1.0) as f32;
+    [
+        color.r as f32 / 255.0 * a,
+        color.g as f32 / 255.0 * a,
+        color.b as f32 / 255.0 * a,
+        a,
+    ]
+}
+
+fn invert(t: Transform) -> Option<Transform> {
+    let det = t.a * t.d - t.b * t.c;
+    if det.abs() < 1e-12 {
+        return None;
+    }
+    Some(Transform {
+        a: t.d / det,
+        b: -t.b / det,
+        c: -t.c / det,
+        d: t.a / det,
+        e: (t.c * t.f - t.d * t.e) / det,
+        f: (t.b * t.e - t.a * t.f) / det,
+    })
+}
+
+fn to_skia_transform(t: Transform) -> tiny_skia::Transform {
+    tiny_skia::Transform::from_row(
+        t.a as f32, t.b as f32, t.c as f32, t.d as f32, t.e as f32, t.f as f32,
+    )
+}
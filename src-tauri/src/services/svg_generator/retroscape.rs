@@ -0,0 +1,283 @@
+//! Synthwave landscapes: a striped sun setting behind midpoint-displacement
+//! mountains, over a neon grid floor running to a vanishing point, under a
+//! starry gradient sky.
+//!
+//! The horizon sits at a fixed fraction of the height and every size follows
+//! the short side, so portrait and ultrawide canvases get the same scene with
+//! more sky or more floor rather than a stretched one.
+
+use rand::Rng;
+
+use super::scene::{Color, GradientStop, LinearGradient, Node, Paint, RadialGradient, Segment, Shape};
+use super::{pick, pick_ramp, Canvas, Palette};
+
+const WHITE: Color = Color { r: 255, g: 255, b: 255 };
+
+pub(super) fn generate(rng: &mut impl Rng, palette: &Palette, c: &Canvas) -> Vec<Node> {
+    let bg = Color::hex(palette.bg);
+    let horizon = c.h * rng.gen_range(0.56..0.66);
+    let cx = c.w / 2.0;
+    let sun_r = c.unit * rng.gen_range(0.2..0.3);
+    let sun_y = horizon - sun_r * rng.gen_range(0.05..0.45);
+    let sun_colors = pick_ramp(rng, palette, 2);
+    let glow = pick(rng, palette);
+    let grid_color = pick(rng, palette);
+
+    let mut nodes = Vec::new();
+    nodes.push(sky(bg, glow, c.w, horizon));
+    nodes.extend(stars(rng, c, horizon));
+    nodes.push(
+        Shape::circle(cx, sun_y, sun_r * 1.9)
+            .paint(Paint::Radial(RadialGradient {
+                cx: 0.5,
+                cy: 0.5,
+                r: 0.5,
+                stops: vec![
+                    GradientStop::new(0.0, sun_colors[1], 0.45),
+                    GradientStop::new(1.0, sun_colors[1], 0.0),
+                ],
+            }))
+            .into(),
+    );
+    nodes.push(sun(rng, cx, sun_y, sun_r, &sun_colors));
+    nodes.extend(mountains(rng, palette, c, horizon, sun_r));
+    nodes.extend(floor(rng, c, bg, glow, grid_color, horizon));
+
+    // Haze along the horizon hides where grid lines crowd together
+    let haze = c.unit * 0.04;
+    nodes.push(
+        Shape::rect(0.0, horizon - haze, c.w, haze * 2.0)
+            .paint(Paint::Linear(LinearGradient {
+                x1: 0.0,
+                y1: 0.0,
+                x2: 0.0,
+                y2: 1.0,
+                stops: vec![
+                    GradientStop::new(0.0, glow, 0.0),
+                    GradientStop::new(0.5, glow, 0.35),
+                    GradientStop::new(1.0, glow, 0.0),
+                ],
+            }))
+            .into(),
+    );
+    nodes.push(
+        Shape::line(0.0, horizon, c.w, horizon)
+            .stroke(grid_color.mix(WHITE, 0.4), c.px(2.0))
+            .opacity(0.9)
+            .into(),
+    );
+    nodes
+}
+
+fn sky(bg: Color, glow: Color, w: f64, horizon: f64) -> Node {
+    Shape::rect(0.0, 0.0, w, horizon)
+        .paint(Paint::Linear(LinearGradient {
+            x1: 0.0,
+            y1: 0.0,
+            x2: 0.0,
+            y2: 1.0,
+            stops: vec![
+                GradientStop::new(0.0, bg, 1.0),
+                GradientStop::new(0.55, bg.mix(glow, 0.2), 1.0),
+                GradientStop::new(1.0, bg.mix(glow, 0.55), 1.0),
+            ],
+        }))
+        .into()
+}
+
+/// Stars fading out towards the horizon, a few of them bright with a halo.
+fn stars(rng: &mut impl Rng, c: &Canvas, horizon: f64) -> Vec<Node> {
+    let mut nodes = Vec::new();
+    for _ in 0..c.scatter(220) {
+        let (x, y) = (rng.gen_range(0.0..c.w), rng.gen_range(0.0..horizon * 0.9));
+        let fade = 1.0 - y / horizon;
+        let bright = rng.gen_bool(0.05);
+        let r = c.px(if bright { rng.gen_range(1.6..2.4) } else { rng.gen_range(0.5..1.4) });
+        if bright {
+            nodes.push(Shape::circle(x, y, r * 4.0).fill(WHITE).opacity(0.08 * fade).into());
+        }
+        let opacity = rng.gen_range(0.4..0.95) * fade;
+        nodes.push(Shape::circle(x, y, r).fill(WHITE).opacity(opacity).into());
+    }
+    nodes
+}
+
+/// The sun as horizontal slices, the gaps between them widening downwards.
+/// All slices share one path so a single gradient spans the whole disc.
+fn sun(rng: &mut impl Rng, cx: f64, cy: f64, r: f64, colors: &[Color]) -> Node {
+    let stripes = rng.gen_range(5..9);
+    // Filled bands as (top, bottom); the upper part of the disc is solid
+    let mut bands = Vec::new();
+    let mut top = cy - r;
+    let start = cy - r * rng.gen_range(0.0..0.3);
+    let pitch = (cy + r - start) / stripes as f64;
+    for k in 0..stripes {
+        let gap = pitch * (0.12 + 0.55 * k as f64 / stripes as f64);
+        let bottom = start + pitch * k as f64;
+        bands.push((top, bottom));
+        top = bottom + gap;
+    }
+
+    // Sampled by angle from the top, so the curved cap stays round
+    let angle = |y: f64| ((cy - y) / r).clamp(-1.0, 1.0).acos();
+    let mut path = Vec::new();
+    for (top, bottom) in bands.into_iter().filter(|(t, b)| b > t) {
+        const STEPS: usize = 24;
+        let (a0, a1) = (angle(top), angle(bottom));
+        let side: Vec<(f64, f64)> = (0..=STEPS)
+            .map(|i| {
+                let a = a0 + (a1 - a0) * i as f64 / STEPS as f64;
+                (r * a.sin(), cy - r * a.cos())
+            })
+            .collect();
+        path.push(Segment::MoveTo(cx + side[0].0, side[0].1));
+        path.extend(side[1..].iter().map(|&(dx, y)| Segment::LineTo(cx + dx, y)));
+        path.extend(side.iter().rev().map(|&(dx, y)| Segment::LineTo(cx - dx, y)));
+        path.push(Segment::Close);
+    }
+
+    Shape::path(path)
+        .paint(Paint::Linear(LinearGradient {
+            x1: 0.0,
+            y1: 0.0,
+            x2: 0.0,
+            y2: 1.0,
+            stops: vec![
+                GradientStop::new(0.0, colors[0].mix(WHITE, 0.25), 1.0),
+                GradientStop::new(1.0, colors[1], 1.0),
+            ],
+        }))
+        .into()
+}
+
+/// Ridges from far to near, each darker, lower and rimmed in neon. Most
+/// scenes open a valley around the sun.
+fn mountains(rng: &mut impl Rng, palette: &Palette, c: &Canvas, horizon: f64, sun_r: f64) -> Vec<Node> {
+    let bg = Color::hex(palette.bg);
+    let layers = rng.gen_range(2..4);
+    let valley = rng.gen_bool(0.75);
+    let rim = pick(rng, palette);
+    let mut nodes = Vec::new();
+
+    for l in 0..layers {
+        let depth = l as f64 / layers as f64;
+        let peak = c.unit * rng.gen_range(0.12..0.22) * (1.0 - depth * 0.45);
+        let roughness = rng.gen_range(0.85..1.3);
+        let heights = midpoint_displacement(rng, 9, roughness);
+        let valley_width = sun_r * rng.gen_range(1.1..1.4);
+        let n = heights.len() - 1;
+        let margin = c.w * 0.02;
+        let x_at = |i: usize| -margin + (c.w + margin * 2.0) * i as f64 / n as f64;
+
+        let mut ridge = Vec::with_capacity(n + 1);
+        for (i, &height) in heights.iter().enumerate() {
+            let x = x_at(i);
+            let envelope = if valley {
+                let d = ((x - c.w / 2.0).abs() / valley_width).min(1.0);
+                0.08 + 0.92 * d * d * (3.0 - 2.0 * d)
+            } else {
+                1.0
+            };
+            ridge.push((x, horizon - peak * height * envelope));
+        }
+
+        let mut points = ridge.clone();
+        points.extend([(x_at(n), horizon + 1.0), (x_at(0), horizon + 1.0)]);
+        let fill = bg.mix(rim, 0.22 - depth * 0.12).mix(Color { r: 0, g: 0, b: 0 }, depth * 0.35);
+        nodes.push(Shape::polygon(points).fill(fill).into());
+
+        let mut edge = vec![Segment::MoveTo(ridge[0].0, ridge[0].1)];
+        edge.extend(ridge[1..].iter().map(|&(x, y)| Segment::LineTo(x, y)));
+        nodes.push(
+            Shape::path(edge)
+                .stroke(rim, c.px(1.5 + depth * 1.5))
+                .opacity(0.5 + depth * 0.4)
+                .into(),
+        );
+    }
+    nodes
+}
+
+/// `2^levels + 1` heights in 0.0..=1.0. Displacement shrinks by `2^-roughness`
+/// per level; lower roughness keeps more jagged detail.
+fn midpoint_displacement(rng: &mut impl Rng, levels: u32, roughness: f64) -> Vec<f64> {
+    let n = 1usize << levels;
+    let mut heights = vec![0.0; n + 1];
+    heights[0] = rng.gen_range(0.2..0.8);
+    heights[n] = rng.gen_range(0.2..0.8);
+    let mut step = n;
+    let mut amplitude = 0.6;
+    while step > 1 {
+        let half = step / 2;
+        for i in (half..n).step_by(step) {
+            let mid = (heights[i - half] + heights[i + half]) / 2.0;
+            heights[i] = mid + rng.gen_range(-amplitude..amplitude);
+        }
+        amplitude *= 0.5f64.powf(roughness);
+        step = half;
+    }
+
+    let (min, max) = heights.iter().fold((f64::MAX, f64::MIN), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+    let span = (max - min).max(f64::EPSILON);
+    heights.iter().map(|v| (v - min) / span).collect()
+}
+
+/// The floor and its perspective grid: rows recede as 1/depth towards the
+/// horizon, columns fan out from the vanishing point. Fog in the sky's horizon
+/// color covers the far rows, where lines would otherwise merge into a band.
+fn floor(rng: &mut impl Rng, c: &Canvas, bg: Color, glow: Color, grid: Color, horizon: f64) -> Vec<Node> {
+    let depth_h = c.h - horizon;
+    let floor = Shape::rect(0.0, horizon, c.w, depth_h)
+        .paint(Paint::Linear(LinearGradient {
+            x1: 0.0,
+            y1: 0.0,
+            x2: 0.0,
+            y2: 1.0,
+            stops: vec![
+                GradientStop::new(0.0, bg.mix(grid, 0.2), 1.0),
+                GradientStop::new(1.0, bg, 1.0),
+            ],
+        }));
+
+    let mut path = Vec::new();
+    // Rows: the nearest sits at the bottom edge, a random phase shifts the rest
+    let spacing = rng.gen_range(0.25..0.5);
+    let phase = rng.gen_range(0.0..1.0);
+    let min_gap = c.px(4.0);
+    for k in 0.. {
+        let y = horizon + depth_h / (1.0 + (k as f64 + phase) * spacing);
+        let next = horizon + depth_h / (1.0 + (k as f64 + 1.0 + phase) * spacing);
+        if y - next < min_gap {
+            break;
+        }
+        path.extend([Segment::MoveTo(0.0, y), Segment::LineTo(c.w, y)]);
+    }
+    // Columns, evenly spaced along the bottom edge and well past the sides
+    let gap = c.unit * rng.gen_range(0.07..0.12);
+    let cx = c.w / 2.0;
+    let reach = (cx * 8.0 / gap).ceil() as i64;
+    for i in -reach..=reach {
+        let x = cx + i as f64 * gap;
+        path.extend([Segment::MoveTo(cx, horizon), Segment::LineTo(x, c.h)]);
+    }
+
+    let width = c.px(rng.gen_range(1.5..2.5));
+    vec![
+        floor.into(),
+        Shape::path(path.clone()).stroke(grid, width * 4.0).opacity(0.15).into(),
+        Shape::path(path).stroke(grid.mix(WHITE, 0.2), width).opacity(0.9).into(),
+        Shape::rect(0.0, horizon, c.w, depth_h * 0.2)
+            .paint(Paint::Linear(LinearGradient {
+                x1: 0.0,
+                y1: 0.0,
+                x2: 0.0,
+                y2: 1.0,
+                stops: vec![
+                    GradientStop::new(0.0, bg.mix(glow, 0.55), 1.0),
+                    GradientStop::new(0.4, bg.mix(glow, 0.4), 0.6),
+                    GradientStop::new(1.0, bg.mix(glow, 0.3), 0.0),
+                ],
+            }))
+            .into(),
+    ]
+}
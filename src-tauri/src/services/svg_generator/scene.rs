@@ -0,0 +1,936 @@
+//! Structured scene model emitted by the vector generators.
+//!
+//! Generators build shapes, paths, gradients and groups instead of SVG text.
+//! The scene is rendered directly for PNG output (see `render`) and only
+//! serialized to SVG when exporting. It also round-trips through JSON so the
+//! frontend can edit a composition and send it back to `render_scene`.
+
+use std::fmt::Write;
+
+use base64::Engine;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::symmetry::{Orbit, Symmetry};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Color {
+    /// Parse "#rrggbb" (the leading '#' is optional).
+    pub fn parse(hex: &str) -> Option<Color> {
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        if hex.len() != 6 {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+        Some(Color { r: channel(0)?, g: channel(2)?, b: channel(4)? })
+    }
+
+    /// Parse a palette entry. Malformed input reads as black.
+    pub fn hex(hex: &str) -> Color {
+        Self::parse(hex).unwrap_or(Color { r: 0, g: 0, b: 0 })
+    }
+
+    /// Linear blend towards `other`; `t` is clamped to 0.0..=1.0.
+    pub fn mix(self, other: Color, t: f64) -> Color {
+        let t = t.clamp(0.0, 1.0);
+        let lerp = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+        Color { r: lerp(self.r, other.r), g: lerp(self.g, other.g), b: lerp(self.b, other.b) }
+    }
+
+    pub fn to_hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+// Colors travel as "#rrggbb" strings, which is what the frontend edits.
+impl Serialize for Color {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_hex())
+    }
+}
+
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Color::parse(&s)
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid color \"{}\"", s)))
+    }
+}
+
+fn one() -> f64 {
+    1.0
+}
+
+fn is_zero(value: &f64) -> bool {
+    *value == 0.0
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+/// 2D affine transform, same layout as SVG's `matrix(a b c d e f)`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Transform {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub e: f64,
+    pub f: f64,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Transform {
+    pub const IDENTITY: Transform = Transform { a: 1.0, b: 0.0, c: 0.0, d: 1.0, e: 0.0, f: 0.0 };
+
+    pub fn translate(tx: f64, ty: f64) -> Transform {
+        Transform { e: tx, f: ty, ..Self::IDENTITY }
+    }
+
+    pub fn scale(sx: f64, sy: f64) -> Transform {
+        Transform { a: sx, d: sy, ..Self::IDENTITY }
+    }
+
+    /// Rotation by `deg` degrees around (cx, cy), like SVG's `rotate(deg cx cy)`.
+    pub fn rotate(deg: f64, cx: f64, cy: f64) -> Transform {
+        let (sin, cos) = deg.to_radians().sin_cos();
+        Transform {
+            a: cos,
+            b: sin,
+            c: -sin,
+            d: cos,
+            e: cx - cos * cx + sin * cy,
+            f: cy - sin * cx - cos * cy,
+        }
+    }
+
+    /// `self` applied after `inner`.
+    pub fn then(self, inner: Transform) -> Transform {
+        Transform {
+            a: self.a * inner.a + self.c * inner.b,
+            b: self.b * inner.a + self.d * inner.b,
+            c: self.a * inner.c + self.c * inner.d,
+            d: self.b * inner.c + self.d * inner.d,
+            e: self.a * inner.e + self.c * inner.f + self.e,
+            f: self.b * inner.e + self.d * inner.f + self.f,
+        }
+    }
+
+    /// The point (x, y) mapped through `self`.
+    pub fn apply(&self, x: f64, y: f64) -> (f64, f64) {
+        (self.a * x + self.c * y + self.e, self.b * x + self.d * y + self.f)
+    }
+
+    pub fn is_identity(&self) -> bool {
+        *self == Self::IDENTITY
+    }
+}
+
+/// Path segments in absolute coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Segment {
+    MoveTo(f64, f64),
+    LineTo(f64, f64),
+    CubicTo(f64, f64, f64, f64, f64, f64),
+    Close,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Geometry {
+    Rect { x: f64, y: f64, width: f64, height: f64 },
+    Circle { cx: f64, cy: f64, r: f64 },
+    Ellipse { cx: f64, cy: f64, rx: f64, ry: f64 },
+    Line { x1: f64, y1: f64, x2: f64, y2: f64 },
+    Polygon { points: Vec<(f64, f64)> },
+    Path { segments: Vec<Segment> },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GradientStop {
+    /// 0.0 ..= 1.0 along the gradient.
+    pub offset: f64,
+    pub color: Color,
+    #[serde(default = "one")]
+    pub opacity: f64,
+}
+
+impl GradientStop {
+    pub fn new(offset: f64, color: Color, opacity: f64) -> GradientStop {
+        GradientStop { offset, color, opacity }
+    }
+}
+
+/// Coordinates are fractions of the painted shape's bounding box, matching
+/// SVG's default `gradientUnits="objectBoundingBox"`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LinearGradient {
+    pub x1: f64,
+    pub y1: f64,
+    pub x2: f64,
+    pub y2: f64,
+    pub stops: Vec<GradientStop>,
+}
+
+/// Center and radius are fractions of the painted shape's bounding box.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RadialGradient {
+    pub cx: f64,
+    pub cy: f64,
+    pub r: f64,
+    pub stops: Vec<GradientStop>,
+}
+
+/// Domain-warped fractal Perlin noise, mapped through `stops` by its value
+/// (0.0..=1.0, centered on 0.5). Unlike gradients it is laid out in user
+/// space, so shapes sharing a seed continue one texture. The renderer
+/// evaluates it per pixel; SVG export approximates it with `feTurbulence`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NoiseTexture {
+    /// Kept to 32 bits so it survives the frontend's JSON numbers.
+    pub seed: u32,
+    /// Feature size in user units.
+    pub scale: f64,
+    pub octaves: u32,
+    /// Domain warp strength, in multiples of `scale`.
+    #[serde(default)]
+    pub warp: f64,
+    pub stops: Vec<GradientStop>,
+}
+
+/// The Mandelbrot set, or a Julia set, colored by smooth (fractional) escape
+/// count: the stops repeat every `cycle` iterations, so they should end on
+/// the color they start with. Laid out in user space like [`NoiseTexture`].
+/// SVG has no equivalent; exports paint the first stop, the color of the
+/// points that escape soonest.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EscapeTime {
+    /// The constant of a Julia set; `None` for the Mandelbrot set.
+    #[serde(default)]
+    pub julia: Option<[f64; 2]>,
+    /// The complex number at the user-space origin.
+    pub origin: [f64; 2],
+    /// Distance in the complex plane per user unit.
+    pub step: f64,
+    pub iterations: u32,
+    pub cycle: f64,
+    pub stops: Vec<GradientStop>,
+    /// Paint for points that never escape.
+    pub inside: Color,
+}
+
+/// A grid of precomputed values (0.0..=1.0) stretched over a user-space
+/// rectangle and mapped through `stops`, for fields too costly to evaluate
+/// per pixel, such as simulations. The renderer upsamples it bicubically,
+/// padding past the edges, or continuing from the opposite edge for fields
+/// that `wrap`; SVG export embeds it as a PNG image at grid resolution.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScalarField {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    pub cols: u32,
+    pub rows: u32,
+    /// Row-major, with 65535 for 1.0.
+    #[serde(with = "packed")]
+    pub values: Vec<u16>,
+    pub stops: Vec<GradientStop>,
+    /// The grid repeats: its last column continues into its first, and its
+    /// last row into its first.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub wrap: bool,
+}
+
+impl ScalarField {
+    /// The values as 0.0..=1.0, or `None` when they don't fill the grid.
+    pub fn levels(&self) -> Option<Vec<f32>> {
+        let expected = self.cols as usize * self.rows as usize;
+        if expected == 0 || self.values.len() != expected {
+            return None;
+        }
+        Some(self.values.iter().map(|&v| v as f32 / u16::MAX as f32).collect())
+    }
+}
+
+// Field values travel as base64 of little-endian pairs: a few hundred
+// kilobytes instead of megabytes of JSON numbers.
+mod packed {
+    use base64::Engine;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(values: &[u16], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let bytes: Vec<u8> = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+        serializer.serialize_str(&base64::engine::general_purpose::STANDARD.encode(bytes))
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<u16>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(s)
+            .map_err(|e| serde::de::Error::custom(format!("invalid field values: {}", e)))?;
+        Ok(bytes.chunks_exact(2).map(|pair| u16::from_le_bytes([pair[0], pair[1]])).collect())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum Paint {
+    None,
+    Solid(Color),
+    Linear(LinearGradient),
+    Radial(RadialGradient),
+    Noise(NoiseTexture),
+    EscapeTime(EscapeTime),
+    Field(ScalarField),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LineCap {
+    Butt,
+    Round,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Stroke {
+    pub color: Color,
+    pub width: f64,
+    pub cap: LineCap,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Shape {
+    pub geometry: Geometry,
+    pub fill: Paint,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stroke: Option<Stroke>,
+    /// Applied to fill and stroke alike.
+    #[serde(default = "one")]
+    pub opacity: f64,
+    /// Gaussian blur standard deviation, in the units the shape is placed in
+    /// (its own transform does not scale it). Softens the shape's edges; the
+    /// paint inside is left as is.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub blur: f64,
+    #[serde(default, skip_serializing_if = "Transform::is_identity")]
+    pub transform: Transform,
+}
+
+impl Shape {
+    pub fn new(geometry: Geometry) -> Shape {
+        Shape {
+            geometry,
+            fill: Paint::None,
+            stroke: None,
+            opacity: 1.0,
+            blur: 0.0,
+            transform: Transform::IDENTITY,
+        }
+    }
+
+    pub fn circle(cx: f64, cy: f64, r: f64) -> Shape {
+        Shape::new(Geometry::Circle { cx, cy, r })
+    }
+
+    pub fn ellipse(cx: f64, cy: f64, rx: f64, ry: f64) -> Shape {
+        Shape::new(Geometry::Ellipse { cx, cy, rx, ry })
+    }
+
+    pub fn rect(x: f64, y: f64, width: f64, height: f64) -> Shape {
+        Shape::new(Geometry::Rect { x, y, width, height })
+    }
+
+    pub fn line(x1: f64, y1: f64, x2: f64, y2: f64) -> Shape {
+        Shape::new(Geometry::Line { x1, y1, x2, y2 })
+    }
+
+    pub fn polygon(points: Vec<(f64, f64)>) -> Shape {
+        Shape::new(Geometry::Polygon { points })
+    }
+
+    pub fn path(segments: Vec<Segment>) -> Shape {
+        Shape::new(Geometry::Path { segments })
+    }
+
+    pub fn fill(mut self, color: Color) -> Shape {
+        self.fill = Paint::Solid(color);
+        self
+    }
+
+    pub fn paint(mut self, paint: Paint) -> Shape {
+        self.fill = paint;
+        self
+    }
+
+    pub fn stroke(mut self, color: Color, width: f64) -> Shape {
+        self.stroke = Some(Stroke { color, width, cap: LineCap::Butt });
+        self
+    }
+
+    pub fn round_cap(mut self) -> Shape {
+        if let Some(stroke) = &mut self.stroke {
+            stroke.cap = LineCap::Round;
+        }
+        self
+    }
+
+    pub fn opacity(mut self, opacity: f64) -> Shape {
+        self.opacity = opacity;
+        self
+    }
+
+    pub fn blur(mut self, blur: f64) -> Shape {
+        self.blur = blur;
+        self
+    }
+
+    pub fn transform(mut self, transform: Transform) -> Shape {
+        self.transform = transform;
+        self
+    }
+
+    /// Box the shape paints into as (left, top, right, bottom), with its
+    /// transform, stroke and blur. Loose around curves, whose control points
+    /// it takes in.
+    pub fn bounds(&self) -> (f64, f64, f64, f64) {
+        let corners = |l: f64, t: f64, r: f64, b: f64| vec![(l, t), (r, t), (r, b), (l, b)];
+        let points = match &self.geometry {
+            Geometry::Rect { x, y, width, height } => corners(*x, *y, x + width, y + height),
+            Geometry::Circle { cx, cy, r } => corners(cx - r, cy - r, cx + r, cy + r),
+            Geometry::Ellipse { cx, cy, rx, ry } => corners(cx - rx, cy - ry, cx + rx, cy + ry),
+            Geometry::Line { x1, y1, x2, y2 } => vec![(*x1, *y1), (*x2, *y2)],
+            Geometry::Polygon { points } => points.clone(),
+            Geometry::Path { segments } => segments
+                .iter()
+                .flat_map(|segment| match *segment {
+                    Segment::MoveTo(x, y) | Segment::LineTo(x, y) => vec![(x, y)],
+                    Segment::CubicTo(x1, y1, x2, y2, x, y) => vec![(x1, y1), (x2, y2), (x, y)],
+                    Segment::Close => vec![],
+                })
+                .collect(),
+        };
+        let ts = self.transform;
+        let scale = (ts.a * ts.d - ts.b * ts.c).abs().sqrt();
+        let margin = self.stroke.as_ref().map_or(0.0, |s| s.width * scale / 2.0) + self.blur.max(0.0) * 3.0;
+        points.iter().map(|&(x, y)| ts.apply(x, y)).fold(
+            (f64::MAX, f64::MAX, f64::MIN, f64::MIN),
+            |(l, t, r, b), (x, y)| (l.min(x - margin), t.min(y - margin), r.max(x + margin), b.max(y + margin)),
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Group {
+    /// Applied to the composited children, not to each child separately.
+    #[serde(default = "one")]
+    pub opacity: f64,
+    #[serde(default, skip_serializing_if = "Transform::is_identity")]
+    pub transform: Transform,
+    pub children: Vec<Node>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Node {
+    Shape(Shape),
+    Group(Group),
+}
+
+impl From<Shape> for Node {
+    fn from(shape: Shape) -> Node {
+        Node::Shape(shape)
+    }
+}
+
+impl From<Group> for Node {
+    fn from(group: Group) -> Node {
+        Node::Group(group)
+    }
+}
+
+/// The output of one style generator.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Layer {
+    pub style: String,
+    /// Focal point of radial styles (dots, sunburst). Moving it and calling
+    /// `render_scene` re-lays the layer out around the new point.
+    #[serde(default)]
+    pub focus: Option<Focus>,
+    /// Copies of part of the layer that replace the whole of it when drawn.
+    #[serde(default, skip_serializing_if = "Symmetry::is_none")]
+    pub symmetry: Symmetry,
+    pub nodes: Vec<Node>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Focus {
+    pub x: f64,
+    pub y: f64,
+    /// Where the nodes were last laid out for; differs from (x, y) after an edit.
+    pub anchor_x: f64,
+    pub anchor_y: f64,
+}
+
+impl Focus {
+    pub fn new(x: f64, y: f64) -> Focus {
+        Focus { x, y, anchor_x: x, anchor_y: y }
+    }
+
+    pub fn moved(&self) -> bool {
+        self.x != self.anchor_x || self.y != self.anchor_y
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Scene {
+    pub width: f64,
+    pub height: f64,
+    pub background: Color,
+    pub layers: Vec<Layer>,
+    /// The scene is one tile of a repeating pattern: whatever leaves an edge
+    /// comes back in at the opposite one.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub tileable: bool,
+}
+
+impl Scene {
+    /// Lays `symmetry` over every layer, replacing any the prompt asked for.
+    /// Tiles keep only symmetries that repeat with them.
+    pub fn symmetrize(&mut self, symmetry: Symmetry) {
+        if self.tileable && !symmetry.tiles() {
+            return;
+        }
+        for layer in &mut self.layers {
+            layer.symmetry = symmetry;
+        }
+    }
+
+    pub fn to_svg(&self) -> String {
+        let mut writer = SvgWriter::default();
+        for (index, layer) in self.layers.iter().enumerate() {
+            // Styles come back from the frontend, so they may hold anything
+            let id = format!("layer{}-{}", index, id_safe(&layer.style));
+            writeln!(writer.body, r#"  <g id="{id}">"#).unwrap();
+            match layer.symmetry.orbit(self.width, self.height) {
+                Some(orbit) => writer.orbit(&id, layer, &orbit),
+                None => {
+                    for node in &layer.nodes {
+                        writer.node(node, 2);
+                    }
+                }
+            }
+            writer.body.push_str("  </g>\n");
+        }
+
+        let mut svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {w} {h}" width="{w}" height="{h}">
+  <rect width="{w}" height="{h}" fill="{bg}"/>
+"#,
+            w = self.width,
+            h = self.height,
+            bg = self.background.to_hex(),
+        );
+        if !writer.defs.is_empty() {
+            svg.push_str("  <defs>\n");
+            svg.push_str(&writer.defs);
+            svg.push_str("  </defs>\n");
+        }
+        svg.push_str(&writer.body);
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+/// `text` with everything but ASCII letters, digits, `-` and `_` replaced,
+/// so it can go into an id and a `url(#…)` reference as it is.
+fn id_safe(text: &str) -> String {
+    text.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
+
+#[derive(Default)]
+struct SvgWriter {
+    defs: String,
+    body: String,
+    next_id: usize,
+}
+
+impl SvgWriter {
+    /// The layer's domain as a clipped group in the defs, placed once per copy.
+    fn orbit(&mut self, layer_id: &str, layer: &Layer, orbit: &Orbit) {
+        let id = format!("{layer_id}-domain");
+        // Anti-aliased clips would each half-cover the mirror lines, leaving a
+        // seam; crisp ones grown by a sliver cover them once, past rounding
+        let points: Vec<String> =
+            inflate(&orbit.domain, 0.05).iter().map(|(x, y)| format!("{:.3},{:.3}", x, y)).collect();
+        writeln!(
+            self.defs,
+            r#"    <clipPath id="{id}-clip"><polygon points="{}" shape-rendering="crispEdges"/></clipPath>"#,
+            points.join(" ")
+        )
+        .unwrap();
+
+        let body = std::mem::take(&mut self.body);
+        for node in &layer.nodes {
+            self.node(node, 3);
+        }
+        let domain = std::mem::replace(&mut self.body, body);
+        writeln!(self.defs, r#"    <g id="{id}" clip-path="url(#{id}-clip)">"#).unwrap();
+        self.defs.push_str(&domain);
+        self.defs.push_str("    </g>\n");
+
+        for copy in &orbit.copies {
+            write!(self.body, r##"    <use href="#{id}""##).unwrap();
+            write_transform(&mut self.body, copy);
+            self.body.push_str("/>\n");
+        }
+    }
+
+    fn node(&mut self, node: &Node, depth: usize) {
+        match node {
+            Node::Shape(shape) => self.shape(shape, depth),
+            Node::Group(group) => {
+                let indent = "  ".repeat(depth);
+                write!(self.body, "{}<g", indent).unwrap();
+                if group.opacity < 1.0 {
+                    write!(self.body, r#" opacity="{:.2}""#, group.opacity).unwrap();
+                }
+                write_transform(&mut self.body, &group.transform);
+                self.body.push_str(">\n");
+                for child in &group.children {
+                    self.node(child, depth + 1);
+                }
+                writeln!(self.body, "{}</g>", indent).unwrap();
+            }
+        }
+    }
+
+    fn shape(&mut self, shape: &Shape, depth: usize) {
+        // A wrapping group carries the blur, leaving the shape's own filter
+        // slot for its paint and keeping the blur out of its transform
+        if shape.blur > 0.0 {
+            let id = self.blur_filter(shape.blur);
+            let indent = "  ".repeat(depth);
+            writeln!(self.body, r#"{}<g filter="url(#{})">"#, indent, id).unwrap();
+            self.shape(&Shape { blur: 0.0, ..shape.clone() }, depth + 1);
+            writeln!(self.body, "{}</g>", indent).unwrap();
+            return;
+        }
+
+        let fill = self.paint(&shape.fill);
+        let filter = match &shape.fill {
+            Paint::Noise(noise) => Some(self.noise_filter(noise)),
+            _ => None,
+        };
+        let b = &mut self.body;
+        b.push_str(&"  ".repeat(depth));
+
+        match &shape.geometry {
+            Geometry::Rect { x, y, width, height } => write!(
+                b,
+                r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}""#,
+                x, y, width, height
+            ),
+            Geometry::Circle { cx, cy, r } => {
+                write!(b, r#"<circle cx="{:.1}" cy="{:.1}" r="{:.1}""#, cx, cy, r)
+            }
+            Geometry::Ellipse { cx, cy, rx, ry } => write!(
+                b,
+                r#"<ellipse cx="{:.1}" cy="{:.1}" rx="{:.1}" ry="{:.1}""#,
+                cx, cy, rx, ry
+            ),
+            Geometry::Line { x1, y1, x2, y2 } => write!(
+                b,
+                r#"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}""#,
+                x1, y1, x2, y2
+            ),
+            Geometry::Polygon { points } => {
+                let points: Vec<String> =
+                    points.iter().map(|(x, y)| format!("{:.1},{:.1}", x, y)).collect();
+                write!(b, r#"<polygon points="{}""#, points.join(" "))
+            }
+            Geometry::Path { segments } => {
+                write!(b, r#"<path d="{}""#, path_data(segments))
+            }
+        }
+        .unwrap();
+
+        write!(b, r#" fill="{}""#, fill).unwrap();
+        if let Some(filter) = &filter {
+            write!(b, r#" filter="url(#{})""#, filter).unwrap();
+        }
+        if let Some(stroke) = &shape.stroke {
+            write!(
+                b,
+                r#" stroke="{}" stroke-width="{:.1}""#,
+                stroke.color.to_hex(),
+                stroke.width
+            )
+            .unwrap();
+            if stroke.cap == LineCap::Round {
+                b.push_str(r#" stroke-linecap="round""#);
+            }
+        }
+        if shape.opacity < 1.0 {
+            write!(b, r#" opacity="{:.2}""#, shape.opacity).unwrap();
+        }
+        write_transform(b, &shape.transform);
+        b.push_str("/>\n");
+    }
+
+    /// Returns the value for a `fill` attribute, emitting gradient defs as needed.
+    fn paint(&mut self, paint: &Paint) -> String {
+        match paint {
+            Paint::None => "none".to_string(),
+            Paint::Solid(color) => color.to_hex(),
+            Paint::Linear(g) => {
+                let id = self.gradient_id();
+                writeln!(
+                    self.defs,
+                    r#"    <linearGradient id="{}" x1="{:.3}" y1="{:.3}" x2="{:.3}" y2="{:.3}">"#,
+                    id, g.x1, g.y1, g.x2, g.y2
+                )
+                .unwrap();
+                write_stops(&mut self.defs, &g.stops);
+                self.defs.push_str("    </linearGradient>\n");
+                format!("url(#{})", id)
+            }
+            Paint::Radial(g) => {
+                let id = self.gradient_id();
+                writeln!(
+                    self.defs,
+                    r#"    <radialGradient id="{}" cx="{:.3}" cy="{:.3}" r="{:.3}">"#,
+                    id, g.cx, g.cy, g.r
+                )
+                .unwrap();
+                write_stops(&mut self.defs, &g.stops);
+                self.defs.push_str("    </radialGradient>\n");
+                format!("url(#{})", id)
+            }
+            // Any opaque fill: the noise filter keeps only the shape's alpha
+            Paint::Noise(_) => "#000000".to_string(),
+            Paint::EscapeTime(e) => e.stops.first().map_or(e.inside, |s| s.color).to_hex(),
+            Paint::Field(field) => match field_png(field) {
+                Some(png) => {
+                    self.next_id += 1;
+                    let id = format!("field{}", self.next_id);
+                    writeln!(
+                        self.defs,
+                        r#"    <pattern id="{}" patternUnits="userSpaceOnUse" x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}">
+      <image width="{:.1}" height="{:.1}" preserveAspectRatio="none" href="data:image/png;base64,{}"/>
+    </pattern>"#,
+                        id,
+                        field.x,
+                        field.y,
+                        field.width,
+                        field.height,
+                        field.width,
+                        field.height,
+                        base64::engine::general_purpose::STANDARD.encode(png)
+                    )
+                    .unwrap();
+                    format!("url(#{})", id)
+                }
+                None => field.stops.first().map_or(Color::hex("#000000"), |s| s.color).to_hex(),
+            },
+        }
+    }
+
+    /// Emits a filter painting the shape with turbulence mapped through the
+    /// texture's stops, and returns its id. The turbulence differs from the
+    /// renderer's noise, so exports match in character rather than detail.
+    fn noise_filter(&mut self, noise: &NoiseTexture) -> String {
+        self.next_id += 1;
+        let id = format!("noise{}", self.next_id);
+        // Sample the stops into transfer tables indexed by the noise value
+        let samples: Vec<[f64; 4]> = (0..=16).map(|i| sample_stops(&noise.stops, i as f64 / 16.0)).collect();
+        let table = |channel: usize| {
+            let values: Vec<String> = samples.iter().map(|s| format!("{:.3}", s[channel])).collect();
+            values.join(" ")
+        };
+        writeln!(
+            self.defs,
+            r#"    <filter id="{id}" x="0" y="0" width="1" height="1" color-interpolation-filters="sRGB">
+      <feTurbulence type="fractalNoise" baseFrequency="{freq:.5}" numOctaves="{octaves}" seed="{seed}"/>
+      <feColorMatrix type="matrix" values="1 0 0 0 0  1 0 0 0 0  1 0 0 0 0  1 0 0 0 0"/>
+      <feComponentTransfer>
+        <feFuncR type="table" tableValues="{r}"/>
+        <feFuncG type="table" tableValues="{g}"/>
+        <feFuncB type="table" tableValues="{b}"/>
+        <feFuncA type="table" tableValues="{a}"/>
+      </feComponentTransfer>
+      <feComposite operator="in" in2="SourceGraphic"/>
+    </filter>"#,
+            freq = 1.0 / noise.scale.max(1e-6),
+            octaves = noise.octaves.max(1),
+            seed = noise.seed,
+            r = table(0),
+            g = table(1),
+            b = table(2),
+            a = table(3),
+        )
+        .unwrap();
+        id
+    }
+
+    /// Emits a Gaussian blur filter and returns its id. The filter region
+    /// grows past the bounding box so wide blurs are not cut off.
+    fn blur_filter(&mut self, blur: f64) -> String {
+        self.next_id += 1;
+        let id = format!("blur{}", self.next_id);
+        writeln!(
+            self.defs,
+            r#"    <filter id="{}" x="-100%" y="-100%" width="300%" height="300%">
+      <feGaussianBlur stdDeviation="{:.1}"/>
+    </filter>"#,
+            id, blur
+        )
+        .unwrap();
+        id
+    }
+
+    fn gradient_id(&mut self) -> String {
+        self.next_id += 1;
+        format!("grad{}", self.next_id)
+    }
+}
+
+/// Straight RGBA in 0.0..=1.0 at `t` along `stops`, padding past either end.
+fn sample_stops(stops: &[GradientStop], t: f64) -> [f64; 4] {
+    let channels = |s: &GradientStop| {
+        [s.color.r as f64 / 255.0, s.color.g as f64 / 255.0, s.color.b as f64 / 255.0, s.opacity]
+    };
+    let Some(first) = stops.first() else {
+        return [0.0; 4];
+    };
+    if t <= first.offset {
+        return channels(first);
+    }
+    for pair in stops.windows(2) {
+        if t <= pair[1].offset {
+            let span = pair[1].offset - pair[0].offset;
+            let f = if span > 0.0 { (t - pair[0].offset) / span } else { 1.0 };
+            let (a, b) = (channels(&pair[0]), channels(&pair[1]));
+            return [0, 1, 2, 3].map(|i| a[i] + (b[i] - a[i]) * f);
+        }
+    }
+    channels(&stops[stops.len() - 1])
+}
+
+/// `field` mapped through its stops as an RGBA PNG, one pixel per value.
+fn field_png(field: &ScalarField) -> Option<Vec<u8>> {
+    let levels = field.levels()?;
+    if field.stops.is_empty() {
+        return None;
+    }
+    let pixels: Vec<u8> = levels
+        .iter()
+        .flat_map(|&t| sample_stops(&field.stops, t as f64).map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8))
+        .collect();
+    let mut png = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png, field.cols, field.rows);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header().ok()?.write_image_data(&pixels).ok()?;
+    Some(png)
+}
+
+fn write_stops(out: &mut String, stops: &[GradientStop]) {
+    for stop in stops {
+        write!(
+            out,
+            r#"      <stop offset="{:.3}" stop-color="{}""#,
+            stop.offset,
+            stop.color.to_hex()
+        )
+        .unwrap();
+        if stop.opacity < 1.0 {
+            write!(out, r#" stop-opacity="{:.2}""#, stop.opacity).unwrap();
+        }
+        out.push_str("/>\n");
+    }
+}
+
+/// Convex `points` with every edge moved `d` outwards.
+fn inflate(points: &[(f64, f64)], d: f64) -> Vec<(f64, f64)> {
+    let n = points.len();
+    let area: f64 = (0..n)
+        .map(|i| {
+            let (p, q) = (points[i], points[(i + 1) % n]);
+            p.0 * q.1 - q.0 * p.1
+        })
+        .sum();
+    // Outward normal of the edge from point i to the next
+    let normal = |i: usize| {
+        let (p, q) = (points[i], points[(i + 1) % n]);
+        let (dx, dy) = (q.0 - p.0, q.1 - p.1);
+        let len = dx.hypot(dy).max(f64::EPSILON) * area.signum();
+        (dy / len, -dx / len)
+    };
+    (0..n)
+        .map(|i| {
+            let (a, b) = (normal((i + n - 1) % n), normal(i));
+            let k = d / (1.0 + a.0 * b.0 + a.1 * b.1).max(0.1);
+            (points[i].0 + (a.0 + b.0) * k, points[i].1 + (a.1 + b.1) * k)
+        })
+        .collect()
+}
+
+fn write_transform(out: &mut String, t: &Transform) {
+    if !t.is_identity() {
+        write!(
+            out,
+            r#" transform="matrix({:.4} {:.4} {:.4} {:.4} {:.1} {:.1})""#,
+            t.a, t.b, t.c, t.d, t.e, t.f
+        )
+        .unwrap();
+    }
+}
+
+fn path_data(segments: &[Segment]) -> String {
+    let mut d = String::new();
+    for seg in segments {
+        if !d.is_empty() {
+            d.push(' ');
+        }
+        match *seg {
+            Segment::MoveTo(x, y) => write!(d, "M {:.1} {:.1}", x, y),
+            Segment::LineTo(x, y) => write!(d, "L {:.1} {:.1}", x, y),
+            Segment::CubicTo(x1, y1, x2, y2, x, y) => write!(
+                d,
+                "C {:.1} {:.1}, {:.1} {:.1}, {:.1} {:.1}",
+                x1, y1, x2, y2, x, y
+            ),
+            Segment::Close => write!(d, "Z"),
+        }
+        .unwrap();
+    }
+    d
+}
@@ -0,0 +1,385 @@
+//! City skylines: layers of procedural buildings receding into haze under a
+//! sky lit for the time of day.
+//!
+//! Each layer is one silhouette of towers with setbacks, spires, slanted
+//! roofs and antennas, paler and finer the further back it stands, so the
+//! layers read as parallax depth. Windows are lit floor by floor. The time of
+//! day named in the prompt ("night", "dusk", "dawn", "day") sets the sky
+//! gradient, where the sun or moon stands, how many windows are lit and
+//! whether neon signs and aircraft beacons glow; without one the seed picks
+//! an hour, mostly after dark.
+
+use std::f64::consts::PI;
+
+use rand::Rng;
+
+use super::scene::{Color, GradientStop, LinearGradient, Node, Paint, RadialGradient, Segment, Shape};
+use super::{pick, Canvas, Palette};
+
+const WHITE: Color = Color { r: 255, g: 255, b: 255 };
+const BLACK: Color = Color { r: 0, g: 0, b: 0 };
+
+/// Daylight at an hour: `day` runs from night (0.0) to full day (1.0) and
+/// `twilight` peaks at 1.0 while the sun crosses the horizon.
+struct Light {
+    /// Height of the sun, -1.0 at midnight to 1.0 at noon.
+    elevation: f64,
+    day: f64,
+    twilight: f64,
+}
+
+impl Light {
+    /// The sun rises at six and sets at eighteen.
+    fn at(hour: f64) -> Light {
+        let elevation = ((hour - 6.0) / 12.0 * PI).sin();
+        let day = smoothstep(-0.1, 0.45, elevation);
+        let twilight = (-(elevation / 0.25).powi(2)).exp();
+        Light { elevation, day, twilight }
+    }
+}
+
+/// Sky colors from the zenith down to the horizon.
+struct Sky {
+    top: Color,
+    middle: Color,
+    horizon: Color,
+}
+
+pub(super) fn generate(rng: &mut impl Rng, palette: &Palette, c: &Canvas) -> Vec<Node> {
+    let hour = c.hour.unwrap_or_else(|| random_hour(rng));
+    let light = Light::at(hour);
+    let bg = Color::hex(palette.bg);
+    // The warmest accent tints sunsets and beacons, the coolest the day sky
+    let accents: Vec<Color> = palette.colors.iter().map(|hex| Color::hex(hex)).collect();
+    let warm = *accents.iter().max_by_key(|c| c.r as i32 - c.b as i32).unwrap_or(&WHITE);
+    let cool = *accents.iter().max_by_key(|c| c.b as i32 - c.r as i32).unwrap_or(&WHITE);
+    let glow = pick(rng, palette);
+
+    let night_horizon = bg.mix(glow, 0.4);
+    let day_top = cool.mix(WHITE, 0.3);
+    let top = bg.mix(day_top, light.day);
+    let horizon = night_horizon
+        .mix(cool.mix(WHITE, 0.75), light.day)
+        .mix(warm.mix(WHITE, 0.2), light.twilight * 0.75);
+    let sky = Sky { top, middle: top.mix(horizon, 0.55).mix(warm, light.twilight * 0.25), horizon };
+
+    let mut nodes = vec![Shape::rect(0.0, 0.0, c.w, c.h)
+        .paint(Paint::Linear(LinearGradient {
+            x1: 0.0,
+            y1: 0.0,
+            x2: 0.0,
+            y2: 1.0,
+            stops: vec![
+                GradientStop::new(0.0, sky.top, 1.0),
+                GradientStop::new(0.45, sky.middle, 1.0),
+                GradientStop::new(0.85, sky.horizon, 1.0),
+            ],
+        }))
+        .into()];
+    nodes.extend(stars(rng, c, 1.0 - light.day));
+    nodes.extend(sun_and_moon(c, hour, &light, warm, cool));
+
+    let layers = rng.gen_range(3..=5);
+    let downtown = c.w * rng.gen_range(0.25..0.75);
+    let spread = c.w * rng.gen_range(0.25..0.5);
+    let dark = bg.mix(BLACK, 0.55).mix(cool, light.day * 0.15);
+    let lights: Vec<Color> = (0..rng.gen_range(2..=3))
+        .map(|_| pick(rng, palette).mix(WHITE, 0.25).mix(sky.horizon, light.day * 0.7))
+        .collect();
+    for l in 0..layers {
+        let depth = l as f64 / (layers - 1) as f64;
+        let layer = Layer {
+            depth,
+            scale: 0.4 + 0.6 * depth,
+            color: sky.horizon.mix(dark, 0.3 + 0.7 * depth),
+            reach: c.h * (0.62 - 0.3 * depth),
+            downtown,
+            spread,
+        };
+        nodes.extend(layer.draw(rng, c, &lights, warm, &light));
+        // Low haze between layers, thickest along the ground
+        if l + 1 < layers {
+            nodes.push(haze(c, sky.horizon, 0.25 * (1.0 - depth)));
+        }
+    }
+    nodes
+}
+
+/// Mostly night, sometimes dusk or dawn, seldom full day.
+fn random_hour(rng: &mut impl Rng) -> f64 {
+    match rng.gen_range(0..10) {
+        0..=5 => rng.gen_range(21.0..29.0) % 24.0,
+        6..=7 => rng.gen_range(18.2..19.4),
+        8 => rng.gen_range(4.8..6.2),
+        _ => rng.gen_range(9.0..16.0),
+    }
+}
+
+fn smoothstep(edge0: f64, edge1: f64, x: f64) -> f64 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// Stars fading towards the horizon and out with the daylight.
+fn stars(rng: &mut impl Rng, c: &Canvas, visibility: f64) -> Vec<Node> {
+    if visibility < 0.1 {
+        return Vec::new();
+    }
+    let limit = c.h * 0.7;
+    (0..c.scatter(180))
+        .map(|_| {
+            let (x, y) = (rng.gen_range(0.0..c.w), rng.gen_range(0.0..limit));
+            let r = c.px(rng.gen_range(0.5..1.6));
+            let opacity = rng.gen_range(0.3..0.9) * visibility * (1.0 - y / limit);
+            Shape::circle(x, y, r).fill(WHITE).opacity(opacity).into()
+        })
+        .collect()
+}
+
+/// The sun crosses from left to right between six and eighteen, the moon
+/// through the night opposite it.
+fn sun_and_moon(c: &Canvas, hour: f64, light: &Light, warm: Color, cool: Color) -> Vec<Node> {
+    let place = |progress: f64, elevation: f64| {
+        (c.w * (0.12 + 0.76 * progress), c.h * (0.8 - 0.7 * elevation))
+    };
+    let disc = |(x, y): (f64, f64), r: f64, color: Color, halo: f64| -> [Node; 2] {
+        [
+            Shape::circle(x, y, r * 4.0)
+                .paint(Paint::Radial(RadialGradient {
+                    cx: 0.5,
+                    cy: 0.5,
+                    r: 0.5,
+                    stops: vec![
+                        GradientStop::new(0.0, color, halo),
+                        GradientStop::new(1.0, color, 0.0),
+                    ],
+                }))
+                .into(),
+            Shape::circle(x, y, r).fill(color).into(),
+        ]
+    };
+
+    // The sun stays up through twilight, its halo showing over the rooftops
+    if light.elevation > -0.2 {
+        let color = warm.mix(WHITE, 0.3 + 0.5 * light.day);
+        disc(place((hour - 6.0) / 12.0, light.elevation), c.unit * 0.05, color, 0.5).into()
+    } else {
+        let color = WHITE.mix(cool, 0.15);
+        disc(place(((hour - 18.0) / 12.0).rem_euclid(1.0), -light.elevation), c.unit * 0.035, color, 0.25).into()
+    }
+}
+
+/// A gradient of `color` rising from the bottom edge.
+fn haze(c: &Canvas, color: Color, opacity: f64) -> Node {
+    Shape::rect(0.0, c.h * 0.4, c.w, c.h * 0.6)
+        .paint(Paint::Linear(LinearGradient {
+            x1: 0.0,
+            y1: 0.0,
+            x2: 0.0,
+            y2: 1.0,
+            stops: vec![GradientStop::new(0.0, color, 0.0), GradientStop::new(1.0, color, opacity)],
+        }))
+        .into()
+}
+
+/// One row of buildings. `depth` runs from the furthest layer (0.0) to the
+/// nearest (1.0), which is the lowest but has the largest buildings.
+struct Layer {
+    depth: f64,
+    /// Size of buildings and windows relative to the nearest layer.
+    scale: f64,
+    color: Color,
+    /// Height of the tallest buildings, reached downtown.
+    reach: f64,
+    downtown: f64,
+    spread: f64,
+}
+
+impl Layer {
+    fn draw(&self, rng: &mut impl Rng, c: &Canvas, lights: &[Color], beacon: Color, light: &Light) -> Vec<Node> {
+        let night = 1.0 - light.day;
+        let mut outline = Vec::new();
+        let mut masts = Vec::new();
+        // Lit windows by light color, bright then dim
+        let mut windows = vec![Vec::new(); lights.len() * 2];
+        let mut glowing = Vec::new();
+
+        let mut x = -c.unit * rng.gen_range(0.0..0.05);
+        while x < c.w {
+            let w = c.unit * rng.gen_range(0.035..0.1) * self.scale;
+            let center = x + w / 2.0;
+            let envelope = 0.35 + 0.65 * (-((center - self.downtown) / self.spread).powi(2)).exp();
+            let mut height = self.reach * envelope * rng.gen_range(0.45..1.0);
+            // Now and then a landmark tower stands over its neighbors
+            if rng.gen_bool(0.08) {
+                height *= 1.35;
+            }
+            let height = height.max(c.unit * 0.04 * self.scale);
+            let top = c.h - height;
+            rect(&mut outline, x, top, w, height + 1.0);
+
+            let peak = roof(rng, &mut outline, x, top, w, height);
+            if let Some((px, py)) = peak.filter(|_| height > self.reach * 0.55) {
+                if rng.gen_bool(0.4) {
+                    let mast = py - height * rng.gen_range(0.08..0.2);
+                    masts.extend([Segment::MoveTo(px, py), Segment::LineTo(px, mast)]);
+                    if night > 0.3 {
+                        glowing.extend(beacon_light(c, px, mast, beacon, self.scale * night));
+                    }
+                }
+            }
+
+            self.windows(rng, c, &mut windows, (x, top, w, height), night);
+            if self.depth >= 0.5 && rng.gen_bool(0.15 * night) {
+                glowing.extend(sign(rng, c, lights, (x, top, w, height), self.scale));
+            }
+
+            x += w + if rng.gen_bool(0.4) { 0.0 } else { w * rng.gen_range(0.0..0.25) };
+        }
+
+        let mut nodes: Vec<Node> = vec![Shape::path(outline).fill(self.color).into()];
+        if !masts.is_empty() {
+            nodes.push(Shape::path(masts).stroke(self.color, c.px(2.0) * self.scale).into());
+        }
+        // Windows further back fade into the haze
+        let opacity = 0.9 * (0.45 + 0.55 * self.depth);
+        for (i, path) in windows.into_iter().enumerate() {
+            if !path.is_empty() {
+                let dim = if i % 2 == 1 { 0.45 } else { 1.0 };
+                nodes.push(Shape::path(path).fill(lights[i / 2]).opacity(opacity * dim).into());
+            }
+        }
+        nodes.extend(glowing);
+        nodes
+    }
+
+    /// A grid of windows on the building at `(x, top, w, height)`, lit
+    /// floor by floor: busy floors are mostly lit, quiet ones only here and there.
+    fn windows(
+        &self,
+        rng: &mut impl Rng,
+        c: &Canvas,
+        windows: &mut [Vec<Segment>],
+        (x, top, w, height): (f64, f64, f64, f64),
+        night: f64,
+    ) {
+        let pitch = (c.px(15.0) * self.scale).max(4.0);
+        let (pitch_x, pitch_y) = (pitch, pitch * 1.25);
+        let cols = ((w - pitch * 0.5) / pitch_x).floor() as usize;
+        let floors = ((height - pitch) / pitch_y).floor() as usize;
+        if cols == 0 || floors == 0 {
+            return;
+        }
+        let occupancy = rng.gen_range(0.15..0.85) * (0.15 + 0.85 * night);
+        let light = rng.gen_range(0..windows.len() / 2);
+        let (ww, wh) = (pitch_x * 0.5, pitch_y * 0.55);
+        let left = x + (w - cols as f64 * pitch_x) / 2.0 + (pitch_x - ww) / 2.0;
+        for floor in 0..floors {
+            let busy = rng.gen_bool(occupancy);
+            let y = top + pitch * 0.75 + floor as f64 * pitch_y;
+            for col in 0..cols {
+                let lit = if busy { rng.gen_bool(0.8) } else { rng.gen_bool(occupancy * 0.15) };
+                if lit {
+                    let dim = rng.gen_bool(0.25) as usize;
+                    rect(&mut windows[light * 2 + dim], left + col as f64 * pitch_x, y, ww, wh);
+                }
+            }
+        }
+    }
+}
+
+/// Adds a roof to the building whose flat top is at `top`, and returns the
+/// point an antenna would stand on, if the roof has room for one.
+fn roof(
+    rng: &mut impl Rng,
+    outline: &mut Vec<Segment>,
+    x: f64,
+    top: f64,
+    w: f64,
+    height: f64,
+) -> Option<(f64, f64)> {
+    let center = x + w / 2.0;
+    match rng.gen_range(0..10) {
+        // Flat, sometimes with a plant room
+        0..=4 => {
+            if rng.gen_bool(0.3) {
+                let bw = w * rng.gen_range(0.3..0.5);
+                let bh = w * rng.gen_range(0.15..0.3);
+                let bx = x + rng.gen_range(0.0..w - bw);
+                rect(outline, bx, top - bh, bw, bh + 1.0);
+                return Some((bx + bw / 2.0, top - bh));
+            }
+            Some((center, top))
+        }
+        // Setbacks narrowing towards the top
+        5..=6 => {
+            let (mut tx, mut tw, mut ty) = (x, w, top);
+            for _ in 0..rng.gen_range(1..=2) {
+                let inset = tw * rng.gen_range(0.12..0.2);
+                let th = height * rng.gen_range(0.08..0.18);
+                (tx, tw, ty) = (tx + inset, tw - inset * 2.0, ty - th);
+                rect(outline, tx, ty, tw, th + 1.0);
+            }
+            Some((tx + tw / 2.0, ty))
+        }
+        // A spire, too slender for an antenna
+        7..=8 => {
+            let inset = w * rng.gen_range(0.1..0.3);
+            let apex = top - height * rng.gen_range(0.15..0.35);
+            outline.extend([
+                Segment::MoveTo(x + inset, top + 1.0),
+                Segment::LineTo(center, apex),
+                Segment::LineTo(x + w - inset, top + 1.0),
+                Segment::Close,
+            ]);
+            None
+        }
+        // Slanted, rising to one side
+        _ => {
+            let rise = w * rng.gen_range(0.2..0.45);
+            let (left, right) = if rng.gen_bool(0.5) { (rise, 0.0) } else { (0.0, rise) };
+            outline.extend([
+                Segment::MoveTo(x, top + 1.0),
+                Segment::LineTo(x, top - left),
+                Segment::LineTo(x + w, top - right),
+                Segment::LineTo(x + w, top + 1.0),
+                Segment::Close,
+            ]);
+            None
+        }
+    }
+}
+
+/// A red aircraft warning light with a soft halo, dimmed by `strength`.
+fn beacon_light(c: &Canvas, x: f64, y: f64, color: Color, strength: f64) -> [Node; 2] {
+    let r = c.px(2.5) * strength.max(0.4);
+    [
+        Shape::circle(x, y, r * 4.0).fill(color).opacity(0.25 * strength).into(),
+        Shape::circle(x, y, r).fill(color.mix(WHITE, 0.3)).opacity(strength.min(1.0)).into(),
+    ]
+}
+
+/// A vertical neon sign down one edge of a building, over a blurred glow.
+fn sign(rng: &mut impl Rng, c: &Canvas, lights: &[Color], (x, top, w, height): (f64, f64, f64, f64), scale: f64) -> [Node; 2] {
+    let color = lights[rng.gen_range(0..lights.len())];
+    let sw = (w * rng.gen_range(0.15..0.25)).max(c.px(6.0) * scale);
+    let sh = height * rng.gen_range(0.2..0.4);
+    let sx = if rng.gen_bool(0.5) { x + w * 0.06 } else { x + w * 0.94 - sw };
+    let sy = top + height * rng.gen_range(0.08..0.3);
+    [
+        Shape::rect(sx, sy, sw, sh).fill(color).blur(c.px(16.0) * scale).opacity(0.9).into(),
+        Shape::rect(sx, sy, sw, sh).fill(color.mix(WHITE, 0.3)).opacity(0.95).into(),
+    ]
+}
+
+/// Appends the rectangle as a closed subpath, wound like every other one so
+/// overlapping rectangles merge under the nonzero rule.
+fn rect(path: &mut Vec<Segment>, x: f64, y: f64, w: f64, h: f64) {
+    path.extend([
+        Segment::MoveTo(x, y),
+        Segment::LineTo(x + w, y),
+        Segment::LineTo(x + w, y + h),
+        Segment::LineTo(x, y + h),
+        Segment::Close,
+    ]);
+}
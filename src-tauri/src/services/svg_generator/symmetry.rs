@@ -0,0 +1,183 @@
+//! Symmetry applied on top of any style: a layer is cut down to a
+//! fundamental domain, and copies of that domain, reflected and rotated,
+//! cover the canvas again.
+//!
+//! Mirrors and the kaleidoscope work around the canvas center. The wallpaper
+//! groups repeat a cell across the canvas: p4m reflects an eighth of a square
+//! into the whole square, p6m a twelfth of a hexagon into the hexagon, and
+//! since every edge of the domain is a mirror line, neighbouring cells meet
+//! without seams. Domains are always cut from the middle of the layer, where
+//! the styles put most of their detail.
+
+use serde::{Deserialize, Serialize};
+
+use super::scene::Transform;
+
+/// Wedges narrower than this would leave almost nothing of the layer.
+const MAX_FOLDS: u32 = 24;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum Symmetry {
+    #[default]
+    None,
+    /// The left half, reflected onto the right.
+    MirrorHorizontal,
+    /// The top half, reflected onto the bottom.
+    MirrorVertical,
+    /// The top-left quarter, reflected across both center lines.
+    Quad,
+    /// A wedge around the center, reflected into `folds` mirrored pairs.
+    Kaleidoscope { folds: u32 },
+    /// Square cells with the symmetry of a square.
+    P4m,
+    /// Hexagonal cells with the symmetry of a hexagon.
+    P6m,
+}
+
+/// Where the copies of a layer go.
+pub struct Orbit {
+    /// Polygon cut out of the layer, in the layer's own coordinates.
+    pub domain: Vec<(f64, f64)>,
+    /// One transform per copy of the domain, each reaching into the canvas.
+    pub copies: Vec<Transform>,
+}
+
+impl Symmetry {
+    pub fn is_none(&self) -> bool {
+        *self == Symmetry::None
+    }
+
+    /// Whether a square tile drawn with this symmetry still repeats without
+    /// seams: mirror lines through the center reflect each edge onto the
+    /// opposite one, and p4m cells are half the tile. Kaleidoscopes and the
+    /// hexagonal cells of p6m don't line up with the tile's edges.
+    pub fn tiles(&self) -> bool {
+        !matches!(self, Symmetry::Kaleidoscope { .. } | Symmetry::P6m)
+    }
+
+    /// The domain and its copies on a `w`x`h` canvas; `None` when the
+    /// layer is drawn as it is.
+    pub fn orbit(&self, w: f64, h: f64) -> Option<Orbit> {
+        let (cx, cy) = (w / 2.0, h / 2.0);
+        let rect = |x1: f64, y1: f64| vec![(0.0, 0.0), (x1, 0.0), (x1, y1), (0.0, y1)];
+        let (domain, copies) = match *self {
+            Symmetry::None => return None,
+            Symmetry::MirrorHorizontal => (rect(cx, h), vec![Transform::IDENTITY, mirror(-1.0, 1.0, cx, cy)]),
+            Symmetry::MirrorVertical => (rect(w, cy), vec![Transform::IDENTITY, mirror(1.0, -1.0, cx, cy)]),
+            Symmetry::Quad => (
+                rect(cx, cy),
+                vec![
+                    Transform::IDENTITY,
+                    mirror(-1.0, 1.0, cx, cy),
+                    mirror(1.0, -1.0, cx, cy),
+                    mirror(-1.0, -1.0, cx, cy),
+                ],
+            ),
+            Symmetry::Kaleidoscope { folds } => kaleidoscope(folds.clamp(2, MAX_FOLDS), w, h),
+            Symmetry::P4m => {
+                let s = w.min(h) * 0.5;
+                let lattice = lattice(w, h, (s, 0.0), (0.0, s), s);
+                // Center, corner and edge midpoint of the middle cell
+                let domain = vec![(cx, cy), (cx - s / 2.0, cy - s / 2.0), (cx, cy - s / 2.0)];
+                (domain, wallpaper(&lattice, 4, cx, cy))
+            }
+            Symmetry::P6m => {
+                let r = w.min(h) * 0.3;
+                let (across, rise) = (r * 3f64.sqrt(), r * 1.5);
+                let lattice = lattice(w, h, (across, 0.0), (across / 2.0, rise), r);
+                // Center, top vertex and the midpoint of the edge after it
+                let domain = vec![(cx, cy), (cx, cy - r), (cx + across / 4.0, cy - rise / 2.0)];
+                (domain, wallpaper(&lattice, 6, cx, cy))
+            }
+        };
+
+        // Copies of the wallpaper groups go past the canvas edges
+        let copies = copies
+            .into_iter()
+            .filter(|t| {
+                let points: Vec<(f64, f64)> = domain.iter().map(|&(x, y)| t.apply(x, y)).collect();
+                points.iter().any(|p| p.0 > 0.0) && points.iter().any(|p| p.0 < w)
+                    && points.iter().any(|p| p.1 > 0.0) && points.iter().any(|p| p.1 < h)
+            })
+            .collect();
+        Some(Orbit { domain, copies })
+    }
+}
+
+/// Scaling by (`sx`, `sy`) around (cx, cy); -1 reflects.
+fn mirror(sx: f64, sy: f64, cx: f64, cy: f64) -> Transform {
+    Transform::translate(cx, cy)
+        .then(Transform::scale(sx, sy))
+        .then(Transform::translate(-cx, -cy))
+}
+
+/// A wedge of half a fold along the long side of the canvas, reflected
+/// across its first edge and rotated by whole folds. The wedge ends where
+/// the layer does, and the copies are scaled up around the center until
+/// they reach the corners of the canvas.
+fn kaleidoscope(folds: u32, w: f64, h: f64) -> (Vec<(f64, f64)>, Vec<Transform>) {
+    let (cx, cy) = (w / 2.0, h / 2.0);
+    let wedge = 180.0 / folds as f64;
+    let start = if w >= h { 0.0 } else { -90.0 } - wedge / 2.0;
+    let steps = (wedge / 15.0).ceil() as usize;
+    let angles: Vec<f64> = (0..=steps).map(|i| (start + wedge * i as f64 / steps as f64).to_radians()).collect();
+    // Distance from the center to the canvas edge, the nearest along the wedge
+    let reach = angles
+        .iter()
+        .map(|a| (cx / a.cos().abs()).min(cy / a.sin().abs()))
+        .fold(f64::MAX, f64::min);
+    let mut domain = vec![(cx, cy)];
+    domain.extend(angles.iter().map(|a| (cx + reach * a.cos(), cy + reach * a.sin())));
+
+    // The chords of the arc fall short of it by the cosine of half a step
+    let corner = (w * w + h * h).sqrt() / 2.0;
+    let zoom = corner / (reach * (wedge / steps as f64 / 2.0).to_radians().cos());
+    let reflect = Transform::rotate(start, cx, cy)
+        .then(mirror(1.0, -1.0, cx, cy))
+        .then(Transform::rotate(-start, cx, cy));
+    let copies = (0..folds)
+        .flat_map(|k| {
+            let turn = mirror(zoom, zoom, cx, cy).then(Transform::rotate(wedge * 2.0 * k as f64, cx, cy));
+            [turn, turn.then(reflect)]
+        })
+        .collect();
+    (domain, copies)
+}
+
+/// Cell centers of the lattice spanned by `u` and `v` through the canvas
+/// center, for every cell within `radius` of the canvas.
+fn lattice(w: f64, h: f64, u: (f64, f64), v: (f64, f64), radius: f64) -> Vec<(f64, f64)> {
+    let (cx, cy) = (w / 2.0, h / 2.0);
+    let reach = ((w.max(h) / 2.0 + radius) / v.1.min(u.0)).ceil() as i64 + 1;
+    let mut centers = Vec::new();
+    for j in -reach..=reach {
+        for i in -reach * 2..=reach * 2 {
+            let x = cx + u.0 * i as f64 + v.0 * j as f64;
+            let y = cy + u.1 * i as f64 + v.1 * j as f64;
+            if x > -radius && x < w + radius && y > -radius && y < h + radius {
+                centers.push((x, y));
+            }
+        }
+    }
+    centers
+}
+
+/// Moves the domain around (cx, cy) into every cell, as `order` rotations
+/// of itself and of its reflection across the vertical through the center.
+fn wallpaper(centers: &[(f64, f64)], order: u32, cx: f64, cy: f64) -> Vec<Transform> {
+    let turn = 360.0 / order as f64;
+    let mut cell = Vec::new();
+    for k in 0..order {
+        let rotate = Transform::rotate(turn * k as f64, cx, cy);
+        cell.push(rotate);
+        cell.push(rotate.then(mirror(-1.0, 1.0, cx, cy)));
+    }
+    centers
+        .iter()
+        .flat_map(|&(x, y)| {
+            let place = Transform::translate(x - cx, y - cy);
+            cell.iter().map(move |&t| place.then(t))
+        })
+        .collect()
+}
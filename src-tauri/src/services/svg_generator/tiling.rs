@@ -0,0 +1,300 @@
+//! Pattern tilings: Truchet, Smith and diagonal tiles, and Islamic star
+//! patterns, laid over square, hexagonal or triangular grids.
+//!
+//! Every grid cell is a polygon whose vertices run clockwise on screen, and
+//! every tile set is built from a cell's corners and edge midpoints, so each
+//! set works on each grid and motifs meet at shared edges.
+
+use std::f64::consts::PI;
+
+use rand::Rng;
+
+use super::scene::{Color, Node, Segment, Shape};
+use super::{pick, wrap, Canvas, Palette};
+use crate::services::noise;
+
+type Point = (f64, f64);
+
+#[derive(Clone, Copy)]
+enum Grid {
+    Square,
+    Hex,
+    Triangle,
+}
+
+#[derive(Clone, Copy)]
+enum TileSet {
+    /// Truchet's half-filled squares, generalized to half of any cell.
+    Truchet,
+    /// Smith's quarter-circle arcs joining edge midpoints around alternate corners.
+    Smith,
+    /// Straight strokes: "10 PRINT" diagonals on squares, midpoint chords elsewhere.
+    Diagonal,
+    /// Star-and-rosette strapwork by Hankin's polygons-in-contact method.
+    Star,
+}
+
+pub(super) fn generate(rng: &mut impl Rng, palette: &Palette, c: &Canvas) -> Vec<Node> {
+    let grid = match rng.gen_range(0..10) {
+        // Only square cells divide a square tile evenly
+        _ if c.tileable => Grid::Square,
+        0..=4 => Grid::Square,
+        5..=7 => Grid::Hex,
+        _ => Grid::Triangle,
+    };
+    let set = match rng.gen_range(0..4) {
+        0 => TileSet::Truchet,
+        1 => TileSet::Smith,
+        2 => TileSet::Diagonal,
+        _ => TileSet::Star,
+    };
+    let mut size = c.unit * rng.gen_range(0.06..0.14) / c.density.sqrt();
+    if c.tileable {
+        size = c.w / (c.w / size).round().max(1.0);
+    }
+    let cells = cells(grid, size, c);
+
+    let nodes = match set {
+        TileSet::Truchet => truchet(rng, palette, &cells),
+        TileSet::Smith | TileSet::Diagonal => {
+            strokes(rng, palette, c, &cells, size, matches!(set, TileSet::Smith), grid)
+        }
+        TileSet::Star => stars(rng, palette, &cells, size),
+    };
+    wrap(nodes, c)
+}
+
+/// Cells covering the canvas with a one-cell overhang, as clockwise polygons.
+/// A tile takes only the cells inside it, which `wrap` carries across its edges.
+fn cells(grid: Grid, size: f64, c: &Canvas) -> Vec<Vec<Point>> {
+    let mut cells = Vec::new();
+    match grid {
+        Grid::Square => {
+            let (first, last_row, last_col) = if c.tileable {
+                (0, (c.h / size).round() as i64 - 1, (c.w / size).round() as i64 - 1)
+            } else {
+                (-1, (c.h / size).ceil() as i64, (c.w / size).ceil() as i64)
+            };
+            for row in first..=last_row {
+                for col in first..=last_col {
+                    let (x, y) = (col as f64 * size, row as f64 * size);
+                    cells.push(vec![(x, y), (x + size, y), (x + size, y + size), (x, y + size)]);
+                }
+            }
+        }
+        Grid::Hex => {
+            // Flat-topped hexagons with `size` as the circumradius
+            let (dx, dy) = (size * 1.5, size * 3f64.sqrt());
+            for col in -1..=(c.w / dx).ceil() as i64 + 1 {
+                let shift = if col % 2 == 0 { 0.0 } else { dy / 2.0 };
+                for row in -1..=(c.h / dy).ceil() as i64 + 1 {
+                    let (cx, cy) = (col as f64 * dx, row as f64 * dy + shift);
+                    cells.push(
+                        (0..6)
+                            .map(|i| {
+                                let a = (i as f64 * 60.0 + 180.0).to_radians();
+                                (cx + a.cos() * size, cy + a.sin() * size)
+                            })
+                            .collect(),
+                    );
+                }
+            }
+        }
+        Grid::Triangle => {
+            let h = size * 3f64.sqrt() / 2.0;
+            for row in -1..=(c.h / h).ceil() as i64 {
+                let (top, bottom) = (row as f64 * h, (row + 1) as f64 * h);
+                let shift = if row % 2 == 0 { 0.0 } else { size / 2.0 };
+                for col in -2..=(c.w / size).ceil() as i64 + 1 {
+                    let x = col as f64 * size + shift;
+                    cells.push(vec![(x, bottom), (x + size / 2.0, top), (x + size, bottom)]);
+                    cells.push(vec![(x + size / 2.0, top), (x + size * 1.5, top), (x + size, bottom)]);
+                }
+            }
+        }
+    }
+    cells
+}
+
+fn midpoint(a: Point, b: Point) -> Point {
+    ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0)
+}
+
+fn centroid(cell: &[Point]) -> Point {
+    let n = cell.len() as f64;
+    (cell.iter().map(|p| p.0).sum::<f64>() / n, cell.iter().map(|p| p.1).sum::<f64>() / n)
+}
+
+/// Fill half of every cell, from a random corner round to the opposite side.
+fn truchet(rng: &mut impl Rng, palette: &Palette, cells: &[Vec<Point>]) -> Vec<Node> {
+    let (light, dark) = (pick(rng, palette), pick(rng, palette));
+    let mut halves = Vec::new();
+    for cell in cells {
+        let n = cell.len();
+        let r = rng.gen_range(0..n);
+        let mut half: Vec<Point> = (0..=n / 2).map(|k| cell[(r + k) % n]).collect();
+        if n % 2 == 1 {
+            half.push(midpoint(cell[(r + n / 2) % n], cell[(r + n / 2 + 1) % n]));
+        }
+        halves.extend(polygon_segments(&half));
+    }
+    // One underlay and one path for all halves keeps the scene small
+    let mut underlay = Vec::new();
+    for cell in cells {
+        underlay.extend(polygon_segments(cell));
+    }
+    vec![
+        Shape::path(underlay).fill(light).opacity(0.9).into(),
+        Shape::path(halves).fill(dark).into(),
+    ]
+}
+
+/// Arcs (Smith) or straight chords joining pairs of edge midpoints, grouped
+/// into one stroked path per color. Colors follow broad noise regions.
+fn strokes(
+    rng: &mut impl Rng,
+    palette: &Palette,
+    c: &Canvas,
+    cells: &[Vec<Point>],
+    size: f64,
+    arcs: bool,
+    grid: Grid,
+) -> Vec<Node> {
+    let colors: Vec<Color> = (0..3).map(|_| pick(rng, palette)).collect();
+    let noise_seed = rng.gen::<u64>();
+    let width = size * rng.gen_range(0.08..0.3);
+    let mut paths: Vec<Vec<Segment>> = vec![Vec::new(); colors.len()];
+
+    for cell in cells {
+        let (cx, cy) = centroid(cell);
+        let t = if c.tileable {
+            // As many noise features across the tile as elsewhere, repeating with it
+            let period = (c.w / c.unit * 2.0).round().max(1.0);
+            noise::value_periodic(noise_seed, cx / c.w * period, cy / c.h * period, period as i64)
+        } else {
+            noise::value(noise_seed, cx / c.unit * 2.0, cy / c.unit * 2.0)
+        };
+        let path = &mut paths[((t * colors.len() as f64) as usize).min(colors.len() - 1)];
+        let n = cell.len();
+        let mids: Vec<Point> = (0..n).map(|i| midpoint(cell[i], cell[(i + 1) % n])).collect();
+        let r = rng.gen_range(0..n);
+
+        if !arcs && matches!(grid, Grid::Square) {
+            // 10 PRINT: one corner-to-corner diagonal
+            let (a, b) = (cell[r % 2], cell[r % 2 + 2]);
+            path.extend([Segment::MoveTo(a.0, a.1), Segment::LineTo(b.0, b.1)]);
+            continue;
+        }
+
+        // Join midpoints of edges k and k + 1 around their shared corner. Odd
+        // cells (triangles) have one midpoint left, which reaches for the center.
+        for pair in 0..n / 2 {
+            let k = (r + pair * 2) % n;
+            let (a, b, corner) = (mids[k], mids[(k + 1) % n], cell[(k + 1) % n]);
+            path.push(Segment::MoveTo(a.0, a.1));
+            if arcs {
+                path.push(arc(corner, a, b));
+            } else {
+                path.push(Segment::LineTo(b.0, b.1));
+            }
+        }
+        if n % 2 == 1 {
+            let m = mids[(r + n - 1) % n];
+            path.extend([Segment::MoveTo(m.0, m.1), Segment::LineTo(cx, cy)]);
+        }
+    }
+
+    paths
+        .into_iter()
+        .zip(colors)
+        .filter(|(path, _)| !path.is_empty())
+        .map(|(path, color)| Shape::path(path).stroke(color, width).round_cap().opacity(0.9).into())
+        .collect()
+}
+
+/// Cubic approximation of the arc around `center` from `a` to `b`, taking the
+/// shorter way round. Both points must be equally far from the center.
+fn arc(center: Point, a: Point, b: Point) -> Segment {
+    let r = (a.0 - center.0).hypot(a.1 - center.1);
+    let a0 = (a.1 - center.1).atan2(a.0 - center.0);
+    let a1 = (b.1 - center.1).atan2(b.0 - center.0);
+    let sweep = (a1 - a0 + PI).rem_euclid(2.0 * PI) - PI;
+    let k = 4.0 / 3.0 * (sweep / 4.0).tan() * r;
+    Segment::CubicTo(
+        a.0 - k * a0.sin(),
+        a.1 + k * a0.cos(),
+        b.0 + k * a1.sin(),
+        b.1 - k * a1.cos(),
+        b.0,
+        b.1,
+    )
+}
+
+/// Hankin's method: two rays leave every edge midpoint at the contact angle
+/// and meet their neighbors' inside the cell. The rays continue across shared
+/// edges, so stars and rosettes form around the grid's corners.
+fn stars(rng: &mut impl Rng, palette: &Palette, cells: &[Vec<Point>], size: f64) -> Vec<Node> {
+    let contact = rng.gen_range(50.0..75.0f64).to_radians();
+    let field = pick(rng, palette);
+    let rosette = pick(rng, palette);
+    let strap = Color::hex(palette.bg).mix(Color { r: 255, g: 255, b: 255 }, 0.85);
+    let mut underlay = Vec::new();
+    let mut inner = Vec::new();
+    let mut lines = Vec::new();
+
+    for cell in cells {
+        let n = cell.len();
+        let center = centroid(cell);
+        let mut star = Vec::with_capacity(n * 2);
+        for i in 0..n {
+            let (v0, v1, v2) = (cell[i], cell[(i + 1) % n], cell[(i + 2) % n]);
+            let (m0, m1) = (midpoint(v0, v1), midpoint(v1, v2));
+            let d0 = ray(m0, (v1.0 - v0.0, v1.1 - v0.1), contact, center);
+            let d1 = ray(m1, (v1.0 - v2.0, v1.1 - v2.1), contact, center);
+            let p = intersect(m0, d0, m1, d1).unwrap_or(center);
+            star.extend([m0, p]);
+        }
+        underlay.extend(polygon_segments(cell));
+        inner.extend(polygon_segments(&star));
+        lines.extend(polygon_segments(&star));
+    }
+
+    let width = size * 0.09;
+    vec![
+        Shape::path(underlay).fill(field).into(),
+        Shape::path(inner).fill(rosette).opacity(0.85).into(),
+        Shape::path(lines.clone()).stroke(strap, width).into(),
+        // A dark core down each strap reads as interlaced ribbon
+        Shape::path(lines).stroke(Color::hex(palette.bg), width * 0.3).opacity(0.8).into(),
+    ]
+}
+
+/// Direction from edge midpoint `m` along `edge`, turned by `angle` towards `center`.
+fn ray(m: Point, edge: Point, angle: f64, center: Point) -> Point {
+    let len = edge.0.hypot(edge.1);
+    let (ex, ey) = (edge.0 / len, edge.1 / len);
+    let (sin, cos) = angle.sin_cos();
+    let turned = [(ex * cos - ey * sin, ex * sin + ey * cos), (ex * cos + ey * sin, -ex * sin + ey * cos)];
+    let inward = |d: &Point| d.0 * (center.0 - m.0) + d.1 * (center.1 - m.1);
+    if inward(&turned[0]) >= inward(&turned[1]) {
+        turned[0]
+    } else {
+        turned[1]
+    }
+}
+
+fn intersect(p: Point, d: Point, q: Point, e: Point) -> Option<Point> {
+    let det = d.0 * e.1 - d.1 * e.0;
+    if det.abs() < 1e-9 {
+        return None;
+    }
+    let t = ((q.0 - p.0) * e.1 - (q.1 - p.1) * e.0) / det;
+    Some((p.0 + d.0 * t, p.1 + d.1 * t))
+}
+
+fn polygon_segments(points: &[Point]) -> Vec<Segment> {
+    let mut path = vec![Segment::MoveTo(points[0].0, points[0].1)];
+    path.extend(points[1..].iter().map(|&(x, y)| Segment::LineTo(x, y)));
+    path.push(Segment::Close);
+    path
+}
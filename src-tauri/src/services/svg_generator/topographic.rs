@@ -0,0 +1,197 @@
+//! Topographic contour maps: a domain-warped noise heightfield cut into
+//! iso-lines by marching squares, smoothed into closed bézier contours.
+//!
+//! The field is sampled on a grid sized in canvas units, so the same seed
+//! draws the same map at any resolution. A ring of below-sea-level samples
+//! around the grid closes every contour, and segments are oriented with the
+//! high ground on the same side, so nested contours wind in opposite directions
+//! and fill as elevation bands under the nonzero rule.
+
+use std::collections::{BTreeMap, HashMap};
+
+use rand::Rng;
+
+use super::scene::{Color, Node, Segment, Shape};
+use super::{pick_ramp, ramp, Canvas, Palette};
+use crate::services::noise;
+
+/// Grid cells across the short side.
+const RESOLUTION: f64 = 90.0;
+
+/// Cells sampled past each canvas edge, so contours closing through the
+/// border ring do so out of sight.
+const MARGIN: usize = 3;
+
+pub(super) fn generate(rng: &mut impl Rng, palette: &Palette, c: &Canvas) -> Vec<Node> {
+    let seed = rng.gen::<u64>();
+    let scale = c.unit * rng.gen_range(0.5..1.1);
+    let warp = rng.gen_range(0.2..0.6);
+    let height = |x: f64, y: f64| {
+        let (u, v) = (x / scale, y / scale);
+        let wx = noise::perlin(seed ^ 1, u, v) * warp;
+        let wy = noise::perlin(seed ^ 2, u, v) * warp;
+        noise::perlin_fbm(seed, u + wx, v + wy, 4)
+    };
+    let field = Field::sample(c, &height);
+
+    let levels = c.count(rng.gen_range(12..24));
+    let filled = rng.gen_bool(0.5);
+    let stops = rng.gen_range(3..5);
+    let colors = pick_ramp(rng, palette, stops);
+    let bg = Color::hex(palette.bg);
+    let line_color = if filled { bg } else { colors[colors.len() - 1] };
+    let width = c.px(rng.gen_range(1.0..2.0));
+
+    let mut nodes = Vec::new();
+    if filled {
+        nodes.push(Shape::rect(0.0, 0.0, c.w, c.h).fill(ramp(&colors, 0.0)).into());
+    }
+    for k in 0..levels {
+        let t = (k as f64 + 0.5) / levels as f64;
+        let contours = field.contours(t);
+        if contours.is_empty() {
+            continue;
+        }
+        let path: Vec<Segment> = contours.iter().flat_map(|ring| smooth(ring)).collect();
+
+        if filled {
+            let band = ramp(&colors, (k + 1) as f64 / levels as f64);
+            nodes.push(Shape::path(path.clone()).fill(band).into());
+        }
+        // Every fifth line is an index contour, drawn heavier as on survey maps
+        let index = k % 5 == 4;
+        let (w, opacity) = if index { (width * 2.5, 0.9) } else { (width, 0.7) };
+        let color = if filled { line_color } else { ramp(&colors, t) };
+        nodes.push(Shape::path(path).stroke(color, w).opacity(opacity).into());
+    }
+    nodes
+}
+
+/// Heights on a grid reaching `MARGIN` cells past the canvas, normalized to
+/// 0.0..=1.0 inside a border ring of -1.0.
+struct Field {
+    cols: usize,
+    rows: usize,
+    cell: f64,
+    values: Vec<f64>,
+}
+
+impl Field {
+    fn sample(c: &Canvas, height: &impl Fn(f64, f64) -> f64) -> Field {
+        let cell = c.unit / RESOLUTION;
+        let cols = (c.w / cell).ceil() as usize + MARGIN * 2 + 1;
+        let rows = (c.h / cell).ceil() as usize + MARGIN * 2 + 1;
+        let mut field = Field { cols, rows, cell, values: vec![f64::NEG_INFINITY; cols * rows] };
+        for row in 1..rows - 1 {
+            for col in 1..cols - 1 {
+                let (x, y) = field.position(col as f64, row as f64);
+                field.values[col + row * cols] = height(x, y);
+            }
+        }
+        let values = &mut field.values;
+
+        let interior = values.iter().copied().filter(|v| v.is_finite());
+        let (min, max) = interior.fold((f64::MAX, f64::MIN), |(lo, hi), v| (lo.min(v), hi.max(v)));
+        let span = (max - min).max(f64::EPSILON);
+        for v in values.iter_mut().filter(|v| v.is_finite()) {
+            *v = (*v - min) / span;
+        }
+        // The border stays below every level, closing all contours
+        for v in values.iter_mut().filter(|v| !v.is_finite()) {
+            *v = -1.0;
+        }
+        field
+    }
+
+    fn at(&self, col: usize, row: usize) -> f64 {
+        self.values[col + row * self.cols]
+    }
+
+    fn position(&self, col: f64, row: f64) -> (f64, f64) {
+        let offset = MARGIN as f64;
+        ((col - offset) * self.cell, (row - offset) * self.cell)
+    }
+
+    /// Closed rings where the field crosses `level`, all with the high ground on the same side.
+    fn contours(&self, level: f64) -> Vec<Vec<(f64, f64)>> {
+        // Crossings are keyed by grid edge so neighboring cells agree on them:
+        // (col, row, 0) is the edge right of a corner, (col, row, 1) the edge below it
+        let mut next: BTreeMap<(usize, usize, u8), (usize, usize, u8)> = BTreeMap::new();
+        let mut points: HashMap<(usize, usize, u8), (f64, f64)> = HashMap::new();
+
+        for row in 0..self.rows - 1 {
+            for col in 0..self.cols - 1 {
+                // Corners and edges walked clockwise: top, right, bottom, left
+                let corners = [(col, row), (col + 1, row), (col + 1, row + 1), (col, row + 1)];
+                let edges = [(col, row, 0), (col + 1, row, 1), (col, row + 1, 0), (col, row, 1)];
+                let inside = corners.map(|(x, y)| self.at(x, y) > level);
+
+                let mut crossings = Vec::with_capacity(4);
+                for i in 0..4 {
+                    let j = (i + 1) % 4;
+                    if inside[i] == inside[j] {
+                        continue;
+                    }
+                    let key = edges[i];
+                    points.entry(key).or_insert_with(|| {
+                        let (a, b) = (corners[i], corners[j]);
+                        let (va, vb) = (self.at(a.0, a.1), self.at(b.0, b.1));
+                        let t = (level - va) / (vb - va);
+                        self.position(
+                            a.0 as f64 + (b.0 as f64 - a.0 as f64) * t,
+                            a.1 as f64 + (b.1 as f64 - a.1 as f64) * t,
+                        )
+                    });
+                    // Entering the high ground or leaving it along the walk
+                    crossings.push((key, inside[j]));
+                }
+
+                // Pair each entry with the exit that follows it, so every segment
+                // cuts off exactly one high run of the boundary
+                let n = crossings.len();
+                for i in 0..n {
+                    if crossings[i].1 {
+                        let exit = crossings[(i + 1) % n].0;
+                        next.insert(exit, crossings[i].0);
+                    }
+                }
+            }
+        }
+
+        let mut rings = Vec::new();
+        while let Some(&start) = next.keys().next() {
+            let mut ring = Vec::new();
+            let mut key = start;
+            while let Some(following) = next.remove(&key) {
+                ring.push(points[&key]);
+                key = following;
+            }
+            if ring.len() >= 3 {
+                rings.push(ring);
+            }
+        }
+        rings
+    }
+}
+
+/// Closed Catmull-Rom spline through `ring`, as cubic béziers.
+fn smooth(ring: &[(f64, f64)]) -> Vec<Segment> {
+    let n = ring.len();
+    let mut path = vec![Segment::MoveTo(ring[0].0, ring[0].1)];
+    for i in 0..n {
+        let p0 = ring[(i + n - 1) % n];
+        let p1 = ring[i];
+        let p2 = ring[(i + 1) % n];
+        let p3 = ring[(i + 2) % n];
+        path.push(Segment::CubicTo(
+            p1.0 + (p2.0 - p0.0) / 6.0,
+            p1.1 + (p2.1 - p0.1) / 6.0,
+            p2.0 - (p3.0 - p1.0) / 6.0,
+            p2.1 - (p3.1 - p1.1) / 6.0,
+            p2.0,
+            p2.1,
+        ));
+    }
+    path.push(Segment::Close);
+    path
+}
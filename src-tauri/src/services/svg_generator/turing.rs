@@ -0,0 +1,155 @@
+//! Turing patterns: Gray–Scott reaction–diffusion grown from a scatter of
+//! seeds, painted through the palette as one scalar field.
+//!
+//! Two chemicals diffuse at different rates while the second feeds on the
+//! first, which is replenished at the feed rate as the second decays at the
+//! kill rate. Those two rates pick the pattern: spots that keep dividing,
+//! stripes, branching coral or a maze-like labyrinth. The simulation runs on
+//! a grid of fixed height with wrapping edges, rows in parallel, so the same
+//! seed grows the same pattern at any resolution; the renderer upsamples the
+//! grid smoothly to the canvas.
+
+use rand::Rng;
+use rayon::prelude::*;
+
+use super::scene::{Color, GradientStop, Node, Paint, ScalarField, Shape};
+use super::{pick_ramp, Canvas, Palette};
+
+/// Grid rows at the default density. Patterns settle at a wavelength of
+/// about ten cells, so this draws a couple of dozen features across.
+const ROWS: f64 = 200.0;
+
+/// Diffusion rates of the two chemicals, per step.
+const DIFFUSE_A: f32 = 1.0;
+const DIFFUSE_B: f32 = 0.5;
+
+/// A feed and kill rate pair, and the steps it takes to fill the grid.
+struct Preset {
+    feed: f32,
+    kill: f32,
+    steps: usize,
+}
+
+const SPOTS: Preset = Preset { feed: 0.0367, kill: 0.0649, steps: 5000 };
+const STRIPES: Preset = Preset { feed: 0.042, kill: 0.0635, steps: 4000 };
+const CORAL: Preset = Preset { feed: 0.0545, kill: 0.062, steps: 3500 };
+const LABYRINTH: Preset = Preset { feed: 0.029, kill: 0.057, steps: 4000 };
+
+pub(super) fn generate(rng: &mut impl Rng, palette: &Palette, c: &Canvas) -> Vec<Node> {
+    let preset = match rng.gen_range(0..4) {
+        0 => SPOTS,
+        1 => STRIPES,
+        2 => CORAL,
+        _ => LABYRINTH,
+    };
+    // Busier prompts grow finer patterns
+    let rows = (ROWS * c.density.sqrt()).round().max(16.0) as usize;
+    let cols = ((rows as f64 * c.w / c.h).round() as usize).max(16);
+    let mut grid = Grid::seeded(rng, cols, rows);
+    // A slight nudge off the preset, so patterns of one kind still differ
+    let feed = preset.feed * rng.gen_range(0.98..1.02);
+    let kill = preset.kill * rng.gen_range(0.995..1.005);
+    for _ in 0..preset.steps {
+        grid.step(feed, kill);
+    }
+
+    let bg = Color::hex(palette.bg);
+    let stops = rng.gen_range(2..=3);
+    let colors = pick_ramp(rng, palette, stops);
+    let field = ScalarField {
+        x: 0.0,
+        y: 0.0,
+        width: c.w,
+        height: c.h,
+        cols: cols as u32,
+        rows: rows as u32,
+        values: grid.levels(),
+        stops: shading(bg, &colors, rng.gen_bool(0.25)),
+        wrap: c.tileable,
+    };
+    vec![Shape::rect(0.0, 0.0, c.w, c.h).paint(Paint::Field(field)).into()]
+}
+
+/// Stops holding the background below the pattern's edge and running
+/// through `colors` above it; `inverted` paints the gaps instead.
+fn shading(bg: Color, colors: &[Color], inverted: bool) -> Vec<GradientStop> {
+    let edge = 0.45;
+    let mut stops = vec![GradientStop::new(0.0, bg, 1.0), GradientStop::new(edge - 0.12, bg, 1.0)];
+    let n = colors.len();
+    for (i, &color) in colors.iter().enumerate() {
+        let offset = edge + (1.0 - edge) * i as f64 / (n - 1).max(1) as f64;
+        stops.push(GradientStop::new(offset, color, 1.0));
+    }
+    if inverted {
+        stops.reverse();
+        for stop in &mut stops {
+            stop.offset = 1.0 - stop.offset;
+        }
+    }
+    stops
+}
+
+/// Concentrations of both chemicals, row-major, with a second buffer the
+/// next step is written into.
+struct Grid {
+    cols: usize,
+    rows: usize,
+    cells: Vec<[f32; 2]>,
+    next: Vec<[f32; 2]>,
+}
+
+impl Grid {
+    /// The first chemical everywhere, and small squares of the second
+    /// scattered over the grid with a little noise to break their symmetry.
+    fn seeded(rng: &mut impl Rng, cols: usize, rows: usize) -> Grid {
+        let mut cells = vec![[1.0, 0.0]; cols * rows];
+        let seeds = (cols * rows / 900).max(3);
+        for _ in 0..seeds {
+            let (x, y) = (rng.gen_range(0..cols), rng.gen_range(0..rows));
+            let r = rng.gen_range(2..5);
+            for dy in 0..r * 2 {
+                for dx in 0..r * 2 {
+                    let i = (x + dx) % cols + (y + dy) % rows * cols;
+                    cells[i] = [0.5, 0.25 + rng.gen_range(0.0..0.05)];
+                }
+            }
+        }
+        Grid { cols, rows, next: cells.clone(), cells }
+    }
+
+    /// One explicit Euler step of unit length, with a 3×3 Laplacian that
+    /// wraps around the edges.
+    fn step(&mut self, feed: f32, kill: f32) {
+        let (cols, rows) = (self.cols, self.rows);
+        let cells = &self.cells;
+        self.next.par_chunks_mut(cols).enumerate().for_each(|(row, out)| {
+            let up = &cells[(row + rows - 1) % rows * cols..][..cols];
+            let here = &cells[row * cols..][..cols];
+            let down = &cells[(row + 1) % rows * cols..][..cols];
+            for (col, cell) in out.iter_mut().enumerate() {
+                let (l, r) = ((col + cols - 1) % cols, (col + 1) % cols);
+                let [a, b] = here[col];
+                let laplacian = |k: usize| {
+                    let adjacent = up[col][k] + down[col][k] + here[l][k] + here[r][k];
+                    let diagonal = up[l][k] + up[r][k] + down[l][k] + down[r][k];
+                    0.2 * adjacent + 0.05 * diagonal - here[col][k]
+                };
+                let reaction = a * b * b;
+                *cell = [
+                    a + DIFFUSE_A * laplacian(0) - reaction + feed * (1.0 - a),
+                    b + DIFFUSE_B * laplacian(1) + reaction - (kill + feed) * b,
+                ];
+            }
+        });
+        std::mem::swap(&mut self.cells, &mut self.next);
+    }
+
+    /// The second chemical stretched over the full 16-bit range.
+    fn levels(&self) -> Vec<u16> {
+        let max = self.cells.iter().fold(f32::EPSILON, |m, cell| m.max(cell[1]));
+        self.cells
+            .iter()
+            .map(|cell| ((cell[1] / max).clamp(0.0, 1.0) * u16::MAX as f32).round() as u16)
+            .collect()
+    }
+}
@@ -1,8 +1,15 @@
 use std::path::PathBuf;
 use std::sync::Mutex;
 
+use crate::services::raster::Rgba16Image;
+use crate::services::svg_generator::scene::Scene;
+
 #[derive(Default)]
 pub struct AppState {
     pub previous_wallpaper: Mutex<Option<String>>,
     pub temp_dir: Mutex<Option<PathBuf>>,
+    /// Scene behind the most recent vector-art result, kept for SVG export.
+    pub last_scene: Mutex<Option<Scene>>,
+    /// Downscaled copy of the image being adjusted, keyed by its path.
+    pub preview_source: Mutex<Option<(String, Rgba16Image)>>,
 }
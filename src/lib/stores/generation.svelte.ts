@@ -1,8 +1,77 @@
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
+import type { OutputOptions } from "./settings.svelte";
 
 export type GenerationStatus = "idle" | "starting" | "generating" | "upscaling" | "complete" | "error";
 
+/** Editable vector composition, mirrored from the backend's scene model. */
+export interface Scene {
+  width: number;
+  height: number;
+  background: string;
+  layers: SceneLayer[];
+  /** One tile of a repeating pattern, set as a tiled wallpaper; absent otherwise. */
+  tileable?: boolean;
+}
+
+export interface SceneLayer {
+  style: string;
+  /** Focal point of radial styles; move x/y and re-render to re-center the layer. */
+  focus: { x: number; y: number; anchor_x: number; anchor_y: number } | null;
+  /** Copies of part of the layer drawn in place of the whole; absent when off. */
+  symmetry?: Symmetry;
+  nodes: SceneNode[];
+}
+
+/** Mirrors, kaleidoscope or wallpaper group laid over a vector style. */
+export type Symmetry =
+  | { mode: "none" | "mirror_horizontal" | "mirror_vertical" | "quad" | "p4m" | "p6m" }
+  | { mode: "kaleidoscope"; folds: number };
+
+export type ScenePaint =
+  | { type: "none" }
+  | { type: "solid"; value: string }
+  | { type: "linear" | "radial" | "noise" | "escape_time" | "field"; value: Record<string, unknown> };
+
+export type SceneNode =
+  | {
+      type: "shape";
+      geometry: { kind: string; [key: string]: unknown };
+      fill: ScenePaint;
+      stroke?: { color: string; width: number; cap: "butt" | "round" };
+      opacity: number;
+      blur?: number;
+      transform?: Record<string, number>;
+    }
+  | { type: "group"; opacity: number; transform?: Record<string, number>; children: SceneNode[] };
+
+/** Post-processing effect; lengths are in pixels of a 1080p canvas. */
+export type Effect =
+  | { kind: "grain"; amount: number; size: number }
+  | { kind: "vignette"; strength: number; radius: number }
+  | { kind: "blur"; radius: number }
+  | { kind: "texture"; texture: "paper" | "canvas"; strength: number }
+  | { kind: "chromatic_aberration"; offset: number }
+  | { kind: "scanlines"; spacing: number; strength: number }
+  | ({ kind: "adjust" } & Adjustments);
+
+/** Tonal and color adjustments; every field is neutral at 0. */
+export interface Adjustments {
+  brightness: number;
+  contrast: number;
+  saturation: number;
+  /** Degrees. */
+  hue: number;
+  temperature: number;
+  dim: number;
+}
+
+export interface EffectChain {
+  /** Seed for grain and textures; the backend picks one when absent. */
+  seed?: number | null;
+  effects: Effect[];
+}
+
 export interface GenerationResult {
   image_path: string;
   original_width: number;
@@ -10,6 +79,15 @@ export interface GenerationResult {
   final_width: number;
   final_height: number;
   was_upscaled: boolean;
+  seed: number | null;
+  scene: Scene | null;
+  effects: EffectChain | null;
+}
+
+/** Strips encoded so far while a vector render streams to disk. */
+export interface RenderProgress {
+  done: number;
+  total: number;
 }
 
 interface GenerationRequest {
@@ -21,11 +99,22 @@ interface GenerationRequest {
   provider: "Pollinations" | "OpenAi";
   target_width: number;
   target_height: number;
+  seed?: number | null;
+  scale_factor?: number | null;
+  /** Overrides any symmetry the prompt asks for. */
+  symmetry?: Symmetry | null;
+  /** Compose a small seamless tile instead of the full canvas. */
+  tileable?: boolean;
+  output?: OutputOptions;
+  effects?: EffectChain;
 }
 
 let status = $state<GenerationStatus>("idle");
 let result = $state<GenerationResult | null>(null);
 let errorMessage = $state<string | null>(null);
+let renderProgress = $state<RenderProgress | null>(null);
+/** Full-resolution render of the current adjustments; the original stays in `result`. */
+let adjustedPath = $state<string | null>(null);
 let lastRequest = $state<GenerationRequest | null>(null);
 let lastUpscaleEnabled = $state(false);
 let lastUpscaleFactor = $state(4);
@@ -37,6 +126,9 @@ function setupListener() {
   listen<string>("generation-progress", (event) => {
     status = event.payload as GenerationStatus;
   });
+  listen<RenderProgress>("render-progress", (event) => {
+    renderProgress = event.payload;
+  });
 }
 
 export function getGenerationStore() {
@@ -46,6 +138,10 @@ export function getGenerationStore() {
     get status() { return status; },
     get result() { return result; },
     get error() { return errorMessage; },
+    get renderProgress() { return renderProgress; },
+    get adjustedPath() { return adjustedPath; },
+    /** The adjusted render when there is one, otherwise the original. */
+    get imagePath() { return adjustedPath ?? result?.image_path ?? null; },
 
     get canRegenerate() { return lastRequest !== null; },
 
@@ -56,7 +152,9 @@ export function getGenerationStore() {
 
       status = "starting";
       result = null;
+      adjustedPath = null;
       errorMessage = null;
+      renderProgress = null;
       try {
         result = await invoke<GenerationResult>("generate_image", { request });
 
@@ -82,6 +180,46 @@ export function getGenerationStore() {
       }
     },
 
+    /** Re-render an edited scene without rerolling the composition. */
+    async renderScene(scene: Scene) {
+      status = "generating";
+      errorMessage = null;
+      renderProgress = null;
+      try {
+        result = await invoke<GenerationResult>("render_scene", {
+          scene,
+          output: lastRequest?.output ?? null,
+          effects: result?.effects ?? null,
+        });
+        adjustedPath = null;
+        status = "complete";
+      } catch (e) {
+        errorMessage = String(e);
+        status = "error";
+      }
+    },
+
+    /** Base64 PNG of the adjustments over a downscaled copy of the original. */
+    async previewAdjustments(adjustments: Adjustments): Promise<string | null> {
+      if (!result) return null;
+      return invoke<string>("preview_adjustments", { imagePath: result.image_path, adjustments });
+    },
+
+    /** Render the adjustments at full resolution into a new file beside the original. */
+    async applyAdjustments(adjustments: Adjustments) {
+      if (!result) return;
+      renderProgress = null;
+      adjustedPath = await invoke<string>("apply_adjustments", {
+        imagePath: result.image_path,
+        adjustments,
+        output: lastRequest?.output ?? null,
+      });
+    },
+
+    clearAdjustments() {
+      adjustedPath = null;
+    },
+
     async regenerate() {
       if (!lastRequest) return;
       await this.generate(lastRequest, lastUpscaleEnabled, lastUpscaleFactor);
@@ -90,6 +228,7 @@ export function getGenerationStore() {
     reset() {
       status = "idle";
       result = null;
+      adjustedPath = null;
       errorMessage = null;
     },
   };
@@ -62,6 +62,15 @@ export function getMonitorStore() {
       return m ? { width: m.width, height: m.height } : { width: 1920, height: 1080 };
     },
 
+    /** Scale factor of the target display; null when it is a custom resolution. */
+    get targetScaleFactor(): number | null {
+      if (useCustomResolution || !layout) return null;
+      if (selectedMode === "spanning") {
+        return Math.max(1, ...layout.monitors.map((m) => m.scale_factor));
+      }
+      return layout.monitors[selectedMonitorIndex]?.scale_factor ?? null;
+    },
+
     get targetResolution(): { width: number; height: number } {
       if (useCustomResolution) {
         return { width: customWidth, height: customHeight };
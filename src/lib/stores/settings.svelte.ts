@@ -1,11 +1,19 @@
 import { invoke } from "@tauri-apps/api/core";
 
+/** How rendered wallpapers are encoded to PNG. */
+export interface OutputOptions {
+  bit_depth: "eight" | "sixteen";
+  dither: boolean;
+  color_space: "srgb" | "display_p3";
+}
+
 export interface AppSettings {
   ai_provider: string;
   openai_api_key: string | null;
   save_directory: string | null;
   upscale_enabled: boolean;
   upscale_factor: number;
+  output: OutputOptions;
 }
 
 const defaultSettings: AppSettings = {
@@ -14,6 +22,7 @@ const defaultSettings: AppSettings = {
   save_directory: null,
   upscale_enabled: true,
   upscale_factor: 4,
+  output: { bit_depth: "eight", dither: true, color_space: "srgb" },
 };
 
 let settings = $state<AppSettings>({ ...defaultSettings });
@@ -23,6 +23,21 @@ export const STYLES: Style[] = [
   { id: "waves", label: "Waves", icon: "\u223F", promptFragment: "wave patterns, flowing curves, undulating lines, rhythmic patterns" },
   { id: "dots", label: "Dots & Circles", icon: "\u25CF", promptFragment: "dot patterns, concentric circles, polka dots, radial patterns, halftone" },
   { id: "silkflow", label: "Silk Flow", icon: "\u2740", promptFragment: "flowing silk petals, smooth curves radiating from center, translucent layers" },
+  { id: "lowpoly", label: "Low Poly", icon: "\u25E2", promptFragment: "low-poly crystalline facets, delaunay triangles, voronoi mosaic, faceted gradient" },
+  { id: "flowfield", label: "Flow Field", icon: "\u224B", promptFragment: "flow field, thousands of evenly spaced streamlines following noise, generative art" },
+  { id: "topographic", label: "Topographic", icon: "\u25CE", promptFragment: "topographic contour map, elevation lines, terrain iso-lines, layered elevation bands" },
+  { id: "tiling", label: "Tiling", icon: "\u25A7", promptFragment: "seamless tiling pattern, truchet tiles, islamic star and rosette strapwork, repeating geometric tessellation" },
+  { id: "retroscape", label: "Retroscape", icon: "\u25D2", promptFragment: "synthwave retro landscape, striped setting sun, neon perspective grid floor, mountain silhouettes, starry sky" },
+  { id: "packing", label: "Packing", icon: "\u2B58", promptFragment: "circle packing, tightly packed non-overlapping circles of many sizes, apollonian gasket" },
+  { id: "cosmos", label: "Cosmos", icon: "\u2726", promptFragment: "deep space, glowing nebula clouds, layered starfields, ringed planets" },
+  { id: "fractal", label: "Fractal", icon: "\u2042", promptFragment: "fractal art, l-system plants, sierpinski and koch recursion, mandelbrot and julia sets with smooth color bands" },
+  { id: "maze", label: "Maze", icon: "\u25A6", promptFragment: "perfect maze, labyrinth on square, hexagonal or circular grid, solution path traced in a gradient" },
+  { id: "aurora", label: "Aurora", icon: "\u2248", promptFragment: "soft blurred mesh gradient of glowing color blobs, aurora borealis curtains over a night sky" },
+  { id: "pixel", label: "Pixel", icon: "\u25A9", promptFragment: "pixel art, low resolution scene with crisp square pixels, dithered to a retro console palette" },
+  { id: "aperiodic", label: "Aperiodic", icon: "\u2B1F", promptFragment: "aperiodic Penrose or hat monotile tiling, never repeating, tiles shaded by type and orientation" },
+  { id: "harmonograph", label: "Harmonograph", icon: "\u221E", promptFragment: "harmonograph and spirograph drawing, one fine continuous line looping in decaying pendulum curves, ink shifting color along its length" },
+  { id: "turing", label: "Turing", icon: "\u25D4", promptFragment: "turing reaction-diffusion pattern, organic spots, stripes and coral labyrinths grown like animal markings, soft-edged" },
+  { id: "skyline", label: "Skyline", icon: "\u25A5", promptFragment: "layered city skyline silhouettes receding into haze, towers with spires and antennas, lit windows and neon signs under a time-of-day sky" },
 ];
 
 export const COLOR_SCHEMES: ColorScheme[] = [
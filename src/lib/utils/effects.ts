@@ -0,0 +1,72 @@
+import type { Effect } from "$lib/stores/generation.svelte";
+
+export interface EffectOption {
+  id: string;
+  label: string;
+  icon: string;
+  description: string;
+  /** Effect with its default parameters. */
+  defaults: Effect;
+  /** Parameter driven by the intensity slider, with its range. */
+  amount: { key: string; min: number; max: number; step: number };
+}
+
+// Lengths are in pixels of a 1080p canvas; the backend scales them to the output size.
+export const EFFECTS: EffectOption[] = [
+  {
+    id: "grain",
+    label: "Film Grain",
+    icon: "∷",
+    description: "Fine photographic noise",
+    defaults: { kind: "grain", amount: 0.3, size: 1.2 },
+    amount: { key: "amount", min: 0.05, max: 1, step: 0.05 },
+  },
+  {
+    id: "vignette",
+    label: "Vignette",
+    icon: "◎",
+    description: "Darkened corners",
+    defaults: { kind: "vignette", strength: 0.5, radius: 0.4 },
+    amount: { key: "strength", min: 0.1, max: 1, step: 0.05 },
+  },
+  {
+    id: "blur",
+    label: "Soft Blur",
+    icon: "◌",
+    description: "Gaussian blur for a calm background",
+    defaults: { kind: "blur", radius: 4 },
+    amount: { key: "radius", min: 1, max: 30, step: 1 },
+  },
+  {
+    id: "paper",
+    label: "Paper",
+    icon: "░",
+    description: "Printed paper fibers",
+    defaults: { kind: "texture", texture: "paper", strength: 0.3 },
+    amount: { key: "strength", min: 0.05, max: 1, step: 0.05 },
+  },
+  {
+    id: "canvas",
+    label: "Canvas",
+    icon: "▒",
+    description: "Woven canvas texture",
+    defaults: { kind: "texture", texture: "canvas", strength: 0.3 },
+    amount: { key: "strength", min: 0.05, max: 1, step: 0.05 },
+  },
+  {
+    id: "aberration",
+    label: "Chromatic",
+    icon: "◑",
+    description: "Red/blue fringes towards the edges",
+    defaults: { kind: "chromatic_aberration", offset: 4 },
+    amount: { key: "offset", min: 1, max: 20, step: 1 },
+  },
+  {
+    id: "scanlines",
+    label: "Scanlines",
+    icon: "☰",
+    description: "CRT lines, great with Synthwave and Cyberpunk",
+    defaults: { kind: "scanlines", spacing: 4, strength: 0.3 },
+    amount: { key: "strength", min: 0.05, max: 1, step: 0.05 },
+  },
+];
@@ -0,0 +1,138 @@
+import type { SceneLayer, SceneNode } from "$lib/stores/generation.svelte";
+
+type Matrix = { a: number; b: number; c: number; d: number; e: number; f: number };
+
+const IDENTITY: Matrix = { a: 1, b: 0, c: 0, d: 1, e: 0, f: 0 };
+
+/** A shape found by its parent list, so it can be spliced out. */
+type Hit = { area: number; list: SceneNode[]; index: number };
+
+function multiply(m: Matrix, n: Matrix): Matrix {
+  return {
+    a: m.a * n.a + m.c * n.b,
+    b: m.b * n.a + m.d * n.b,
+    c: m.a * n.c + m.c * n.d,
+    d: m.b * n.c + m.d * n.d,
+    e: m.a * n.e + m.c * n.f + m.e,
+    f: m.b * n.e + m.d * n.f + m.f,
+  };
+}
+
+/** Points that bound a geometry before its transform, padded by `pad`. */
+function corners(geometry: Record<string, unknown>, pad: number): [number, number][] {
+  const g = geometry as Record<string, number>;
+  let points: [number, number][];
+  switch (geometry.kind) {
+    case "rect":
+      points = [[g.x, g.y], [g.x + g.width, g.y + g.height]];
+      break;
+    case "circle":
+      points = [[g.cx - g.r, g.cy - g.r], [g.cx + g.r, g.cy + g.r]];
+      break;
+    case "ellipse":
+      points = [[g.cx - g.rx, g.cy - g.ry], [g.cx + g.rx, g.cy + g.ry]];
+      break;
+    case "line":
+      points = [[g.x1, g.y1], [g.x2, g.y2]];
+      break;
+    case "polygon":
+      points = geometry.points as [number, number][];
+      break;
+    case "path":
+      // Segments are { move_to: [x, y] }, { cubic_to: [x1, y1, x2, y2, x, y] } or "close"
+      points = [];
+      for (const segment of geometry.segments as unknown[]) {
+        if (typeof segment === "string") continue;
+        for (const coords of Object.values(segment as Record<string, number[]>)) {
+          for (let i = 0; i + 1 < coords.length; i += 2) points.push([coords[i], coords[i + 1]]);
+        }
+      }
+      break;
+    default:
+      points = [];
+  }
+  if (points.length === 0) return [];
+  const xs = points.map((p) => p[0]);
+  const ys = points.map((p) => p[1]);
+  const [x0, x1] = [Math.min(...xs) - pad, Math.max(...xs) + pad];
+  const [y0, y1] = [Math.min(...ys) - pad, Math.max(...ys) + pad];
+  return [[x0, y0], [x1, y0], [x0, y1], [x1, y1]];
+}
+
+/** The shape under (x, y) with the smallest box among `nodes` and `best`, found by its parent list. */
+function smallestAt(
+  nodes: SceneNode[],
+  x: number,
+  y: number,
+  parent: Matrix,
+  best: Hit | null,
+): Hit | null {
+  nodes.forEach((node, index) => {
+    const m = node.transform ? multiply(parent, node.transform as Matrix) : parent;
+    if (node.type === "group") {
+      best = smallestAt(node.children, x, y, m, best);
+      return;
+    }
+    const box = corners(node.geometry, (node.stroke?.width ?? 0) / 2).map(([px, py]) => [
+      m.a * px + m.c * py + m.e,
+      m.b * px + m.d * py + m.f,
+    ]);
+    if (box.length === 0) return;
+    const xs = box.map((p) => p[0]);
+    const ys = box.map((p) => p[1]);
+    const [x0, x1, y0, y1] = [Math.min(...xs), Math.max(...xs), Math.min(...ys), Math.max(...ys)];
+    if (x < x0 || x > x1 || y < y0 || y > y1) return;
+    const area = (x1 - x0) * (y1 - y0);
+    if (!best || area <= best.area) best = { area, list: nodes, index };
+  });
+  return best;
+}
+
+/**
+ * Remove the shape under (x, y), in scene units, from `layers`. The smallest
+ * box wins, so clicking a star picks the star rather than the sky behind it.
+ * Shapes are matched where they are drawn before any symmetry copies them.
+ */
+export function removeShapeAt(layers: SceneLayer[], x: number, y: number): boolean {
+  let best: Hit | null = null;
+  for (const layer of layers) {
+    best = smallestAt(layer.nodes, x, y, IDENTITY, best);
+  }
+  if (!best) return false;
+  best.list.splice(best.index, 1);
+  return true;
+}
+
+function rotateHex(hex: string, degrees: number): string {
+  const [r, g, b] = [1, 3, 5].map((i) => parseInt(hex.slice(i, i + 2), 16) / 255);
+  const max = Math.max(r, g, b);
+  const min = Math.min(r, g, b);
+  const l = (max + min) / 2;
+  const d = max - min;
+  if (d === 0) return hex;
+  const s = d / (1 - Math.abs(2 * l - 1));
+  let h = max === r ? ((g - b) / d) % 6 : max === g ? (b - r) / d + 2 : (r - g) / d + 4;
+  h = (((h * 60 + degrees) % 360) + 360) % 360;
+
+  const c = (1 - Math.abs(2 * l - 1)) * s;
+  const x = c * (1 - Math.abs(((h / 60) % 2) - 1));
+  const m = l - c / 2;
+  const [r1, g1, b1] =
+    h < 60 ? [c, x, 0] : h < 120 ? [x, c, 0] : h < 180 ? [0, c, x] : h < 240 ? [0, x, c] : h < 300 ? [x, 0, c] : [c, 0, x];
+  return "#" + [r1, g1, b1].map((v) => Math.round((v + m) * 255).toString(16).padStart(2, "0")).join("");
+}
+
+/** Turn every color in the layer's fills, strokes and gradient stops around the hue wheel. */
+export function shiftHue(layer: SceneLayer, degrees: number) {
+  const visit = (value: unknown): unknown => {
+    if (typeof value === "string") {
+      return /^#[0-9a-f]{6}$/i.test(value) ? rotateHex(value, degrees) : value;
+    }
+    if (Array.isArray(value)) return value.map(visit);
+    if (value && typeof value === "object") {
+      return Object.fromEntries(Object.entries(value).map(([k, v]) => [k, visit(v)]));
+    }
+    return value;
+  };
+  if (degrees !== 0) layer.nodes = visit(layer.nodes) as SceneNode[];
+}
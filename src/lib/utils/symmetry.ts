@@ -0,0 +1,54 @@
+import type { Symmetry } from "$lib/stores/generation.svelte";
+
+export interface SymmetryOption {
+  id: Symmetry["mode"];
+  label: string;
+  icon: string;
+  description: string;
+  defaults: Symmetry;
+}
+
+export const SYMMETRIES: SymmetryOption[] = [
+  {
+    id: "mirror_horizontal",
+    label: "Mirror",
+    icon: "◧",
+    description: "Left half reflected onto the right",
+    defaults: { mode: "mirror_horizontal" },
+  },
+  {
+    id: "mirror_vertical",
+    label: "Flip",
+    icon: "⬒",
+    description: "Top half reflected onto the bottom",
+    defaults: { mode: "mirror_vertical" },
+  },
+  {
+    id: "quad",
+    label: "Quad",
+    icon: "⊞",
+    description: "One quarter reflected across both center lines",
+    defaults: { mode: "quad" },
+  },
+  {
+    id: "kaleidoscope",
+    label: "Kaleidoscope",
+    icon: "✳",
+    description: "A wedge mirrored around the center",
+    defaults: { mode: "kaleidoscope", folds: 6 },
+  },
+  {
+    id: "p4m",
+    label: "Square Tiles",
+    icon: "▦",
+    description: "Wallpaper group p4m: square cells of mirrored triangles",
+    defaults: { mode: "p4m" },
+  },
+  {
+    id: "p6m",
+    label: "Hex Tiles",
+    icon: "⬢",
+    description: "Wallpaper group p6m: hexagonal cells of mirrored triangles",
+    defaults: { mode: "p6m" },
+  },
+];